@@ -0,0 +1,103 @@
+use super::{token::Token, Assembler};
+use crate::{
+	error_syntax,
+	ir::{self, BasicValue, IrBasicValue},
+	message::MessageResult,
+};
+
+impl Assembler<'_> {
+	/// `fn name(R1: i32): i32 = { blocks }`, an extern fn has an empty body.
+	pub fn assemble_function(&mut self) -> MessageResult<ir::Function> {
+		let extern_function = self.is(Token::Extern);
+		if extern_function {
+			self.next()?;
+		}
+		self.expect(Token::Fn)?;
+		let name = self.expect_ident()?;
+		self.registers.clear();
+		self.params.clear();
+
+		self.expect(Token::LParen)?;
+		let mut args = Vec::new();
+		let mut variadic_args = None;
+		while !self.is(Token::RParen) {
+			if self.is(Token::DotDotDot) {
+				variadic_args = Some(self.expect(Token::DotDotDot)?);
+				break;
+			}
+			let arg = self.expect_ident()?;
+			self.expect(Token::Colon)?;
+			let type_id = self.assemble_type()?;
+			self.registers.insert(arg.clone(), type_id);
+			self.params.insert(arg.clone());
+			args.push(IrBasicValue::new(BasicValue::Register(arg), type_id).as_param());
+			// `R1: str...`
+			if !self.is(Token::RParen) && !self.is(Token::DotDotDot) {
+				self.expect(Token::Comma)?;
+			}
+		}
+		self.expect(Token::RParen)?;
+		self.expect(Token::Colon)?;
+		let ret = self.assemble_type()?;
+		self.expect(Token::Assign)?;
+
+		let mut function = ir::Function::new(name, false, args, ret);
+		if extern_function {
+			function.as_extern_function(variadic_args.is_some());
+		} else if let Some(range) = variadic_args {
+			let message = error_syntax!("only extern functions take variadic arguments").code(803);
+			return Err(self.error(message, range));
+		}
+
+		let range = self.expect(Token::LBrace)?;
+		self.skip_newlines()?;
+		while !self.is(Token::RBrace) {
+			function.add_block(self.assemble_block()?);
+			self.skip_newlines()?;
+		}
+		self.expect(Token::RBrace)?;
+		if extern_function && !function.blocks.is_empty() {
+			let message =
+				error_syntax!("extern function '{}' can't have a body", function.name).code(803);
+			return Err(self.error(message, range));
+		}
+		Ok(function)
+	}
+
+	/// `blk1: instr`, one instruction per line up to the next label.
+	fn assemble_block(&mut self) -> MessageResult<ir::IrBlock> {
+		let label = self.assemble_label()?;
+		self.expect(Token::Colon)?;
+		let mut block = ir::IrBlock::new(label);
+		self.skip_newlines()?;
+		while !self.is(Token::RBrace) && !self.is_label() {
+			block.append_instr(self.assemble_instr()?);
+			if !self.is_line_end() {
+				return Err(self.unexpected("the end of the line"));
+			}
+			self.skip_newlines()?;
+		}
+		Ok(block)
+	}
+
+	fn is_label(&self) -> bool {
+		self.is(Token::Ident) && self.peek() == Some(Token::Colon)
+	}
+
+	/// `blk2`, or `entry` for the first block.
+	pub fn assemble_label(&mut self) -> MessageResult<usize> {
+		let range = self.range;
+		let name = self.expect_ident()?;
+		if name == "entry" {
+			return Ok(1);
+		}
+		let number = name.strip_prefix("blk_").or(name.strip_prefix("blk"));
+		match number.and_then(|number| number.parse().ok()) {
+			Some(label) if label > 0 => Ok(label),
+			_ => Err(
+				self
+					.error(error_syntax!("invalid label '{}', expected e.g. 'blk1'", name).code(801), range),
+			),
+		}
+	}
+}
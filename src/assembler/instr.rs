@@ -0,0 +1,143 @@
+use super::{token::Token, Assembler};
+use crate::{
+	checker::types::TypeId,
+	error_syntax,
+	ir::{self, BasicValue, IrBasicValue},
+	message::MessageResult,
+};
+
+impl Assembler<'_> {
+	pub fn assemble_instr(&mut self) -> MessageResult<ir::Instr> {
+		let range = self.range;
+		let name = self.expect_ident()?;
+		match name.as_str() {
+			"load" | "set" | "mov" => self.assemble_un_instr(&name),
+			"drop" => Ok(ir::Instr::Drop(self.assemble_value()?)),
+			"ret" if self.is_line_end() => Ok(ir::Instr::Ret(None)),
+			"ret" => Ok(ir::Instr::Ret(Some(self.assemble_value()?))),
+			"jmp" => Ok(ir::Instr::Jmp(ir::JmpInstr::new(self.assemble_label()?))),
+			"jmp_if" => self.assemble_jmp_if_instr(),
+			"call" => self.assemble_call_instr(None),
+			_ if self.is(Token::Assign) => {
+				self.next()?;
+				self.assemble_assign_instr(&name)
+			}
+			_ => Err(self.error(error_syntax!("unknown instruction '{}'", name).code(802), range)),
+		}
+	}
+
+	/// `R2 = add i32 R1, i32 1`, `R2 = stack i32` and the like.
+	fn assemble_assign_instr(&mut self, dest: &str) -> MessageResult<ir::Instr> {
+		let range = self.range;
+		let name = self.expect_ident()?;
+		if let Some(instr) = bin_instr(&name) {
+			let left = self.assemble_value()?;
+			self.expect(Token::Comma)?;
+			let right = self.assemble_value()?;
+			let type_id = if name.starts_with("cmp_") { TypeId::BOOL } else { left.type_id };
+			let dest = self.assemble_dest(dest, type_id);
+			return Ok(instr(ir::BinInstr::new(dest, left, right)));
+		}
+		match name.as_str() {
+			"stack" => {
+				let size = self.assemble_type()?;
+				let dest = self.assemble_dest(dest, size);
+				Ok(ir::SallocInstr::new(dest, size).into())
+			}
+			"heap" => {
+				// the size is a plain number, as the builder makes it
+				let size = self.assemble_basic_value(TypeId::I32)?;
+				let dest = self.assemble_dest(dest, self.types["ptr"]);
+				Ok(ir::Instr::Halloc(ir::UnInstr::new(dest, size)))
+			}
+			"call" => self.assemble_call_instr(Some(dest)),
+			// `R3 = Person getptr ptr R2 1`
+			self_name if self.is(Token::Ident) && self.text() == "getptr" => {
+				let self_name = self_name.to_owned();
+				self.next()?;
+				let self_base = self.assemble_value()?;
+				let offset = self.expect_usize()?;
+				let dest = self.assemble_dest(dest, self.types["ptr"]);
+				Ok(ir::GetPtrInstr::new(self_name, self_base, offset, dest).into())
+			}
+			_ => Err(self.error(error_syntax!("unknown instruction '{}'", name).code(802), range)),
+		}
+	}
+
+	/// `load R2, i32 R1`, `set R1, i32 10` and `mov R1, i32 R2`.
+	fn assemble_un_instr(&mut self, name: &str) -> MessageResult<ir::Instr> {
+		let dest = self.expect_ident()?;
+		self.expect(Token::Comma)?;
+		let src = self.assemble_value()?;
+		let dest = self.assemble_dest(&dest, src.type_id);
+		let instr = ir::UnInstr::new(dest, src);
+		match name {
+			"load" => Ok(ir::Instr::Load(instr)),
+			"set" => Ok(ir::Instr::Set(instr)),
+			_ => Ok(ir::Instr::Mov(instr)),
+		}
+	}
+
+	/// `jmp_if bool R1, blk2, blk3`
+	fn assemble_jmp_if_instr(&mut self) -> MessageResult<ir::Instr> {
+		let cond = self.assemble_value()?;
+		self.expect(Token::Comma)?;
+		let true_label = self.assemble_label()?;
+		self.expect(Token::Comma)?;
+		let false_label = self.assemble_label()?;
+		Ok(ir::Instr::JmpIf(ir::JmpIfInstr::new(cond, true_label, false_label)))
+	}
+
+	/// `R2 = call i32 name(i32 R1)`, a call without a value has no dest.
+	fn assemble_call_instr(&mut self, dest: Option<&str>) -> MessageResult<ir::Instr> {
+		let ret_id = self.assemble_type()?;
+		let range = self.range;
+		let callee = self.expect_ident()?;
+		self.expect(Token::LParen)?;
+		let mut args = Vec::new();
+		while !self.is(Token::RParen) {
+			args.push(self.assemble_value()?);
+			if !self.is(Token::RParen) {
+				self.expect(Token::Comma)?;
+			}
+		}
+		self.expect(Token::RParen)?;
+		let dest = match dest {
+			Some(dest) => self.assemble_dest(dest, ret_id),
+			None if ret_id.is_empty_type() => {
+				// never read, the builder makes one for every call
+				IrBasicValue::new(BasicValue::Register("_".to_owned()), TypeId::VOID)
+			}
+			None => {
+				let message =
+					error_syntax!("'{}' returns a value, it needs a dest register", callee).code(803);
+				return Err(self.error(message, range));
+			}
+		};
+		Ok(ir::CallInstr::new(dest, callee, ret_id, args).into())
+	}
+}
+
+fn bin_instr(name: &str) -> Option<fn(ir::BinInstr) -> ir::Instr> {
+	let instr = match name {
+		"add" => ir::Instr::Add,
+		"sub" => ir::Instr::Sub,
+		"mul" => ir::Instr::Mul,
+		"div" => ir::Instr::Div,
+		"mod" => ir::Instr::Mod,
+		"cmp_eq" => ir::Instr::CmpEq,
+		"cmp_ne" => ir::Instr::CmpNe,
+		"cmp_lt" => ir::Instr::CmpLt,
+		"cmp_gt" => ir::Instr::CmpGt,
+		"cmp_le" => ir::Instr::CmpLe,
+		"cmp_ge" => ir::Instr::CmpGe,
+		"and" => ir::Instr::And,
+		"or" => ir::Instr::Or,
+		"shl" => ir::Instr::Shl,
+		"shr" => ir::Instr::Shr,
+		"not" => ir::Instr::Not,
+		"neg" => ir::Instr::Neg,
+		_ => return None,
+	};
+	Some(instr)
+}
@@ -0,0 +1,44 @@
+use super::{token::Token, Assembler};
+use crate::{
+	checker::types::{StructType, Type},
+	error_syntax, ir,
+	message::MessageResult,
+};
+
+impl Assembler<'_> {
+	/// `type Person = { i32, i64 }, size=16`, the fields may wrap over lines.
+	pub fn assemble_struct(&mut self) -> MessageResult<ir::Struct> {
+		self.expect(Token::Type)?;
+		let range = self.range;
+		let name = self.expect_ident()?;
+		if self.types.contains_key(&name) {
+			return Err(self.error(error_syntax!("type '{}' is already defined", name).code(803), range));
+		}
+		self.expect(Token::Assign)?;
+		self.expect(Token::LBrace)?;
+		self.skip_newlines()?;
+		let mut ir_struct = ir::Struct::new_with_name(Vec::new(), name.as_str());
+		while !self.is(Token::RBrace) {
+			ir_struct.add_field(self.assemble_type()?);
+			self.skip_newlines()?;
+			if !self.is(Token::RBrace) {
+				self.expect(Token::Comma)?;
+				self.skip_newlines()?;
+			}
+		}
+		self.expect(Token::RBrace)?;
+		self.expect(Token::Comma)?;
+		let range = self.range;
+		if self.expect_ident()? != "size" {
+			return Err(self.error(error_syntax!("expected 'size'").code(801), range));
+		}
+		self.expect(Token::Assign)?;
+		// kept as printed, it's what the builder laid the struct out with
+		ir_struct.size = self.expect_usize()?;
+
+		let type_id = self.type_store.add_type(Type::Struct(StructType::new(name.clone())));
+		self.type_store.add_type_definition(self.mod_id, name.clone(), type_id);
+		self.types.insert(name, type_id);
+		Ok(ir_struct)
+	}
+}
@@ -0,0 +1,206 @@
+use logos::{Lexer, Logos};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+	checker::types::{BorrowType, Type, TypeId, TypeStore},
+	error_syntax,
+	ir::{self, BasicValue, IrBasicValue},
+	loader::{Loader, ModId},
+	message::{Message, MessageResult},
+	range::Range,
+};
+
+mod function;
+mod instr;
+mod ir_struct;
+mod token;
+mod value;
+
+use token::Token;
+
+/// parses a `.lnr` mod, the textual ir the disassembler prints, back into ir.
+/// its struct types are added to `type_store`.
+pub fn assemble_mod(
+	mod_id: ModId,
+	loader: &Loader,
+	type_store: &mut TypeStore,
+) -> MessageResult<ir::IR> {
+	let source = loader.lookup_source_unchecked(mod_id);
+	let mut assembler = Assembler::new(&source.raw, mod_id, type_store);
+	assembler.assemble_program(source.pathname.clone())
+}
+
+pub struct Assembler<'a> {
+	lexer: Lexer<'a, Token>,
+	token: Option<Token>,
+	range: Range,
+	mod_id: ModId,
+	type_store: &'a mut TypeStore,
+	/// the builtins, `ptr` and the structs declared so far, by the names the disassembler prints
+	types: FxHashMap<String, TypeId>,
+	/// the types of the registers of the current function, the text only has them where
+	/// they're used, e.g. `R1 = stack i32` and then `set R1, i32 10`
+	registers: FxHashMap<String, TypeId>,
+	params: FxHashSet<String>,
+}
+
+impl<'a> Assembler<'a> {
+	pub fn new(source: &'a str, mod_id: ModId, type_store: &'a mut TypeStore) -> Self {
+		let mut types = FxHashMap::default();
+		for id in 0..TypeId::LENGTH {
+			let type_id = TypeId(id as u64);
+			types.insert(type_store.lookup_display_ir_type(type_id), type_id);
+		}
+		// pointers are opaque in the text, any borrow prints as `ptr`
+		let ptr = type_store.add_type(Type::Borrow(BorrowType::new(TypeId::UNIT, false)));
+		types.insert("ptr".to_owned(), ptr);
+		let lexer = Token::lexer(source);
+		let registers = FxHashMap::default();
+		let params = FxHashSet::default();
+		Self {
+			lexer,
+			token: None,
+			range: Range::default(),
+			mod_id,
+			type_store,
+			types,
+			registers,
+			params,
+		}
+	}
+
+	pub fn assemble_program(&mut self, pathname: String) -> MessageResult<ir::IR> {
+		let mut program = ir::IR::new(pathname);
+		self.next()?;
+		self.skip_newlines()?;
+		while let Some(token) = self.token {
+			match token {
+				Token::Type => program.add_struct(self.assemble_struct()?),
+				Token::Global => program.add_global(self.assemble_global()?),
+				Token::Fn | Token::Extern => program.add_function(self.assemble_function()?),
+				_ => return Err(self.unexpected("'fn', 'type' or 'global'")),
+			}
+			self.skip_newlines()?;
+		}
+		Ok(program)
+	}
+
+	/// `global test_failed: i32`, a zeroed value every function can reach.
+	fn assemble_global(&mut self) -> MessageResult<ir::IrBind> {
+		self.expect(Token::Global)?;
+		let name = self.expect_ident()?;
+		self.expect(Token::Colon)?;
+		let type_id = self.assemble_type()?;
+		Ok(ir::IrBind::new(name, type_id))
+	}
+
+	/// a type by its printed name, e.g. `i32`, `ptr` or a struct name.
+	fn assemble_type(&mut self) -> MessageResult<TypeId> {
+		let range = self.range;
+		let name = self.expect_ident()?;
+		match self.types.get(&name) {
+			Some(type_id) => Ok(*type_id),
+			None => Err(self.error(error_syntax!("unknown type '{}'", name).code(802), range)),
+		}
+	}
+
+	/// a register, typed as it was defined if it was.
+	fn register(&self, name: &str, type_id: TypeId) -> IrBasicValue {
+		let value = IrBasicValue::new(BasicValue::Register(name.to_owned()), type_id);
+		// params are values, not stack slots to load from
+		if self.params.contains(name) {
+			return value.as_param();
+		}
+		value
+	}
+
+	/// the dest of an instruction, `type_id` is its type unless it was defined before.
+	fn assemble_dest(&mut self, name: &str, type_id: TypeId) -> IrBasicValue {
+		let type_id = *self.registers.entry(name.to_owned()).or_insert(type_id);
+		self.register(name, type_id)
+	}
+
+	// tokens
+	//
+	fn next(&mut self) -> MessageResult<()> {
+		self.token = match self.lexer.next() {
+			Some(Ok(token)) => Some(token),
+			Some(Err(_)) => {
+				let range = Range::from_span(self.lexer.span());
+				let message = error_syntax!("unexpected '{}'", self.lexer.slice()).code(801);
+				return Err(self.error(message, range));
+			}
+			None => None,
+		};
+		self.range = Range::from_span(self.lexer.span());
+		Ok(())
+	}
+
+	/// the token after the current one.
+	fn peek(&self) -> Option<Token> {
+		self.lexer.clone().next().and_then(Result::ok)
+	}
+
+	fn is(&self, token: Token) -> bool {
+		self.token == Some(token)
+	}
+
+	fn is_line_end(&self) -> bool {
+		matches!(self.token, None | Some(Token::Newline) | Some(Token::RBrace))
+	}
+
+	fn text(&self) -> &'a str {
+		self.lexer.slice()
+	}
+
+	fn skip_newlines(&mut self) -> MessageResult<()> {
+		while self.is(Token::Newline) {
+			self.next()?;
+		}
+		Ok(())
+	}
+
+	fn expect(&mut self, token: Token) -> MessageResult<Range> {
+		if !self.is(token) {
+			return Err(self.unexpected(&format!("'{}'", token)));
+		}
+		let range = self.range;
+		self.next()?;
+		Ok(range)
+	}
+
+	fn expect_ident(&mut self) -> MessageResult<String> {
+		if !self.is(Token::Ident) {
+			return Err(self.unexpected("a name"));
+		}
+		let text = self.text().to_owned();
+		self.next()?;
+		Ok(text)
+	}
+
+	fn expect_usize(&mut self) -> MessageResult<usize> {
+		let range = self.range;
+		if !self.is(Token::Number) {
+			return Err(self.unexpected("a number"));
+		}
+		let text = self.text();
+		let number = text
+			.parse()
+			.map_err(|_| self.error(error_syntax!("invalid number '{}'", text).code(801), range))?;
+		self.next()?;
+		Ok(number)
+	}
+
+	fn unexpected(&self, expected: &str) -> Message {
+		let found = match self.token {
+			Some(Token::Newline) => "the end of the line".to_owned(),
+			Some(_) => format!("'{}'", self.text()),
+			None => "the end of the file".to_owned(),
+		};
+		self.error(error_syntax!("expected {}, found {}", expected, found).code(801), self.range)
+	}
+
+	fn error(&self, message: Message, range: Range) -> Message {
+		message.range(range).mod_id(self.mod_id)
+	}
+}
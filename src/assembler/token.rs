@@ -0,0 +1,73 @@
+use core::fmt;
+
+use logos::Logos;
+
+/// the tokens of the textual ir, as printed by the disassembler.
+/// newlines are kept, `ret` takes an optional value up to the end of its line.
+#[derive(Logos, Debug, Clone, Copy, PartialEq, Eq)]
+#[logos(skip r"[ \t\f\r]+")]
+#[logos(skip r"//[^\n]*")]
+pub enum Token {
+	#[token("\n")]
+	Newline,
+
+	#[token("fn")]
+	Fn,
+	#[token("extern")]
+	Extern,
+	#[token("type")]
+	Type,
+	#[token("global")]
+	Global,
+
+	#[token("(")]
+	LParen,
+	#[token(")")]
+	RParen,
+	#[token("{")]
+	LBrace,
+	#[token("}")]
+	RBrace,
+	#[token(",")]
+	Comma,
+	#[token(":")]
+	Colon,
+	#[token("=")]
+	Assign,
+	#[token("...")]
+	DotDotDot,
+
+	// mangled names have dots, e.g. `mod2.println`
+	#[regex(r"[a-zA-Z_][a-zA-Z0-9_]*(\.[a-zA-Z0-9_]+)*")]
+	Ident,
+	#[regex(r"-?[0-9]+(\.[0-9]+)?|-inf")]
+	Number,
+	#[regex(r#""([^"\\]|\\.)*""#)]
+	String,
+	#[regex(r#"'([^'\\]|\\.)*'"#)]
+	Char,
+}
+
+impl fmt::Display for Token {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Token::Newline => write!(f, "new line"),
+			Token::Fn => write!(f, "fn"),
+			Token::Extern => write!(f, "extern"),
+			Token::Type => write!(f, "type"),
+			Token::Global => write!(f, "global"),
+			Token::LParen => write!(f, "("),
+			Token::RParen => write!(f, ")"),
+			Token::LBrace => write!(f, "{{"),
+			Token::RBrace => write!(f, "}}"),
+			Token::Comma => write!(f, ","),
+			Token::Colon => write!(f, ":"),
+			Token::Assign => write!(f, "="),
+			Token::DotDotDot => write!(f, "..."),
+			Token::Ident => write!(f, "ident"),
+			Token::Number => write!(f, "number"),
+			Token::String => write!(f, "string"),
+			Token::Char => write!(f, "char"),
+		}
+	}
+}
@@ -0,0 +1,87 @@
+use super::{token::Token, Assembler};
+use crate::{
+	checker::types::TypeId,
+	error_syntax,
+	ir::{BasicValue, IrBasicValue},
+	message::MessageResult,
+};
+
+impl Assembler<'_> {
+	/// `i32 R1`, `str "hi\n"`, a type and then a register or a literal.
+	pub fn assemble_value(&mut self) -> MessageResult<IrBasicValue> {
+		let type_id = self.assemble_type()?;
+		self.assemble_basic_value(type_id)
+	}
+
+	pub fn assemble_basic_value(&mut self, type_id: TypeId) -> MessageResult<IrBasicValue> {
+		let range = self.range;
+		let text = self.text();
+		let value = match self.token {
+			Some(Token::Number) if type_id.is_float() => match text.parse() {
+				Ok(value) => BasicValue::Float(value),
+				Err(_) => {
+					return Err(self.error(error_syntax!("invalid float '{}'", text).code(801), range))
+				}
+			},
+			// ints are printed unsigned, a negative one is taken as its bits
+			Some(Token::Number) => {
+				match text.parse().or_else(|_| text.parse::<i64>().map(|value| value as u64)) {
+					Ok(value) => BasicValue::Int(value),
+					Err(_) => {
+						return Err(self.error(error_syntax!("invalid integer '{}'", text).code(801), range))
+					}
+				}
+			}
+			Some(Token::String) => BasicValue::String(self.unescape(&text[1..text.len() - 1])?),
+			Some(Token::Char) => {
+				let value = self.unescape(&text[1..text.len() - 1])?;
+				let mut chars = value.chars();
+				match (chars.next(), chars.next()) {
+					(Some(value), None) => BasicValue::Char(value),
+					_ => {
+						return Err(
+							self.error(error_syntax!("expected one char, found {}", text).code(801), range),
+						)
+					}
+				}
+			}
+			Some(Token::Ident) => match text {
+				"true" => BasicValue::Bool(true),
+				"false" => BasicValue::Bool(false),
+				"inf" | "NaN" if type_id.is_float() => BasicValue::Float(text.parse().unwrap_or(f64::NAN)),
+				_ => {
+					self.next()?;
+					return Ok(self.register(text, type_id));
+				}
+			},
+			_ => return Err(self.unexpected("a register or a literal")),
+		};
+		self.next()?;
+		Ok(IrBasicValue::new(value, type_id))
+	}
+
+	/// the escapes the disassembler writes, the same as in lemon strings.
+	fn unescape(&self, text: &str) -> MessageResult<String> {
+		let mut unescaped = String::with_capacity(text.len());
+		let mut chars = text.chars();
+		while let Some(char) = chars.next() {
+			if char != '\\' {
+				unescaped.push(char);
+				continue;
+			}
+			match chars.next() {
+				Some(char @ ('\'' | '"' | '\\')) => unescaped.push(char),
+				Some('n') => unescaped.push('\n'),
+				Some('r') => unescaped.push('\r'),
+				Some('t') => unescaped.push('\t'),
+				Some('0') => unescaped.push('\0'),
+				found => {
+					let found = found.map(String::from).unwrap_or_default();
+					let message = error_syntax!("unknown escape sequence '\\{}'", found).code(801);
+					return Err(self.error(message, self.range));
+				}
+			}
+		}
+		Ok(unescaped)
+	}
+}
@@ -10,6 +10,14 @@ pub use tree_type::*;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
 	pub stmts: Vec<Stmt>,
+	pub comments: Vec<Comment>,
+}
+
+/// a `//` or `/* */` comment, kept as trivia for the formatter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+	pub text: String,
+	pub range: Range,
 }
 
 // ------- statements -------
@@ -374,6 +382,7 @@ pub struct FnStmt {
 	pub body: FnBody,
 	pub range: Range, // fn range
 	pub generics: Vec<Generic>,
+	pub attrs: Vec<Attribute>,
 }
 
 impl FnStmt {
@@ -397,6 +406,33 @@ impl FnStmt {
 	pub fn is_generic(&self) -> bool {
 		!self.generics.is_empty()
 	}
+
+	pub fn set_attrs(&mut self, attrs: Vec<Attribute>) {
+		self.attrs = attrs;
+	}
+
+	/// `@test`, run by `lemon test` and left out of every other build.
+	pub fn is_test(&self) -> bool {
+		self.attrs.iter().any(|attr| attr.lexeme() == "test")
+	}
+}
+
+// @<name>(<args>), e.g. `@allow(unused_variable)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribute {
+	pub name: Ident,
+	pub args: Vec<Ident>,
+	pub range: Range,
+}
+
+impl Attribute {
+	pub fn lexeme(&self) -> &str {
+		&self.name.text
+	}
+
+	pub fn get_range(&self) -> Range {
+		self.range
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -508,6 +544,10 @@ impl Expr {
 		}
 	}
 
+	pub fn is_import(&self) -> bool {
+		matches!(self, Expr::Import(_))
+	}
+
 	// pub fn valid_assign_expr(&self) -> bool {
 	// 	matches!(self, Expr::Ident(_))
 	// 		| matches!(self, Expr::Borrow(_))
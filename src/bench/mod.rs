@@ -0,0 +1,179 @@
+use std::{
+	path::{Path, PathBuf},
+	process::{Command, Stdio},
+	time::{Duration, Instant},
+};
+
+use clap::ArgMatches;
+use inkwell::{targets::FileType, OptimizationLevel};
+use target_lexicon::HOST;
+
+use crate::{
+	bench_report::{millis, BenchReport, CompileTimes, ProgramReport, RunTimes},
+	builder::Builder,
+	checker::{context::Context, Checker},
+	cross::Cross,
+	file_system::FileSystem,
+	lexer::Token,
+	linker::Linker,
+	llvm,
+	loader::Loader,
+	parser::Parser,
+	report::throw_error,
+	shio::ShioConfig,
+	verifier::{verify_ir, verify_ownership},
+};
+use logos::Logos;
+
+/// `lemon bench`, compiles every program of a dir at one opt level, runs each
+/// of them a few times and writes how long it all took as json.
+pub fn run(matches: &ArgMatches) {
+	let dir = PathBuf::from(matches.get_one::<String>("dir").expect("dir has a default"));
+	let opt_level = matches.get_one::<String>("opt-level").and_then(|level| level.parse().ok());
+	let opt_level: u8 = opt_level.unwrap_or(2);
+	let runs = *matches.get_one::<usize>("runs").expect("runs has a default");
+	let output = match matches.get_one::<String>("output") {
+		Some(output) => PathBuf::from(output),
+		None => dir.join("results.json"),
+	};
+
+	let programs = match matches.get_many::<String>("programs") {
+		Some(names) => names.map(|name| dir.join(name)).collect(),
+		None => FileSystem::package_sources(&dir).unwrap_or_else(|message| throw_error(message.text)),
+	};
+	let out_dir = std::env::temp_dir().join("lemon-bench");
+	std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| throw_error(err.to_string()));
+
+	let mut report = BenchReport {
+		compiler: env!("CARGO_PKG_VERSION").to_owned(),
+		target: HOST.to_string(),
+		opt_level,
+		runs,
+		programs: Vec::with_capacity(programs.len()),
+	};
+	for path in programs {
+		let name = path.strip_prefix(&dir).unwrap_or(&path).display().to_string();
+		let program = match bench_program(&path, opt_level, runs, &out_dir) {
+			Ok((compile, run)) => {
+				ProgramReport { name, skipped: None, compile: Some(compile), run: Some(run) }
+			}
+			Err(reason) => ProgramReport::skipped(name, reason),
+		};
+		report.programs.push(program);
+	}
+
+	report.print();
+	report
+		.write(&output)
+		.unwrap_or_else(|err| throw_error(format!("failed to write '{}', {}", output.display(), err)));
+	println!("wrote '{}'", output.display());
+}
+
+fn bench_program(
+	path: &Path,
+	opt_level: u8,
+	runs: usize,
+	out_dir: &Path,
+) -> Result<(CompileTimes, RunTimes), String> {
+	let (compile, binary) = compile_program(path, opt_level, out_dir)?;
+	let run = time_runs(&binary, runs)?;
+	Ok((compile, run))
+}
+
+/// the phases of `lemon compile`. a program that doesn't parse, check, verify, emit
+/// or link is skipped rather than ending the bench. the builder and the llvm backend
+/// still exit on an error, but past the verifier that's a bug in the compiler.
+fn compile_program(
+	path: &Path,
+	opt_level: u8,
+	out_dir: &Path,
+) -> Result<(CompileTimes, PathBuf), String> {
+	let mut times = CompileTimes::default();
+	let shio = ShioConfig::with_defaults(path.to_path_buf());
+	let file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+	let mut loader = Loader::new(shio, file_system);
+	let mod_id = loader.load_entry().map_err(|message| message.text)?;
+	let source = loader.lookup_source_unchecked(mod_id).clone();
+
+	let timer = Instant::now();
+	let mut lexer = Token::lexer(source.raw.as_str());
+	let ast =
+		Parser::new(&mut lexer, mod_id, &mut loader).parse_program().map_err(|message| message.text)?;
+	loader.add_mod(mod_id, ast);
+	times.parse_ms = millis(timer.elapsed());
+
+	let timer = Instant::now();
+	let mut ctx = Context::new();
+	Checker::new(&mut ctx, &mut loader).check_messages(mod_id);
+	times.check_ms = millis(timer.elapsed());
+	let messages = loader.take_messages();
+	if messages.any_errors() {
+		return Err(format!("it doesn't check, {}", messages.summary()));
+	}
+
+	let timer = Instant::now();
+	let ir = Builder::new(&ctx.type_store, &mut ctx.event, &mut loader).build(mod_id);
+	times.build_ms = millis(timer.elapsed());
+	if !ir.has_function("main") {
+		return Err("it has no main function".to_owned());
+	}
+
+	let timer = Instant::now();
+	let mut messages = verify_ir(&ir, &ctx.type_store);
+	messages.extend(verify_ownership(&ir, &ctx.type_store));
+	times.verify_ms = millis(timer.elapsed());
+	if let Some(message) = messages.first() {
+		return Err(format!("its ir doesn't verify, {}", message.text));
+	}
+
+	let timer = Instant::now();
+	let llvm_context = inkwell::context::Context::create();
+	let llvm_module = llvm::create_module_from_source(&llvm_context, &source);
+	let mut llvm = llvm::Llvm::new(&llvm_context, llvm_module, &loader, &ctx.type_store);
+	llvm.compile_ir(&ir);
+	times.llvm_ms = millis(timer.elapsed());
+
+	let timer = Instant::now();
+	let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+	let object = out_dir.join(format!("{}.o", stem));
+	let mut cross = Cross::new(&HOST.to_string());
+	cross.opt(optimization_level(opt_level));
+	cross.emit(&llvm.module, FileType::Object, &object)?;
+	times.emit_ms = millis(timer.elapsed());
+
+	let timer = Instant::now();
+	let binary = Linker::new(object).try_link()?;
+	times.link_ms = millis(timer.elapsed());
+	times.sum();
+	Ok((times, PathBuf::from(binary)))
+}
+
+/// one run to warm up, then `runs` timed ones with the output thrown away.
+fn time_runs(binary: &Path, runs: usize) -> Result<RunTimes, String> {
+	let (exit_code, _) = run_once(binary)?;
+	let mut samples = Vec::with_capacity(runs);
+	for _ in 0..runs {
+		let (_, elapsed) = run_once(binary)?;
+		samples.push(elapsed);
+	}
+	Ok(RunTimes::new(exit_code, &samples))
+}
+
+fn run_once(binary: &Path) -> Result<(i32, Duration), String> {
+	let mut command = Command::new(binary);
+	command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+	let timer = Instant::now();
+	let status = command.status().map_err(|err| format!("failed to run it, {}", err))?;
+	let elapsed = timer.elapsed();
+	let exit_code = status.code().ok_or("it was killed by a signal")?;
+	Ok((exit_code, elapsed))
+}
+
+fn optimization_level(level: u8) -> OptimizationLevel {
+	match level {
+		0 => OptimizationLevel::None,
+		1 => OptimizationLevel::Less,
+		3 => OptimizationLevel::Aggressive,
+		_ => OptimizationLevel::Default,
+	}
+}
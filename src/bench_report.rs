@@ -0,0 +1,125 @@
+// the results of `lemon bench`, what each run took and how much it varied.
+use std::{fs, path::Path, time::Duration};
+
+use serde::Serialize;
+
+use crate::time::format_time;
+
+/// what `lemon bench` writes, one entry per program.
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+	pub compiler: String,
+	pub target: String,
+	pub opt_level: u8,
+	pub runs: usize,
+	pub programs: Vec<ProgramReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgramReport {
+	pub name: String,
+	/// why the program wasn't timed, e.g. it doesn't check
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub skipped: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub compile: Option<CompileTimes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub run: Option<RunTimes>,
+}
+
+impl ProgramReport {
+	pub fn skipped(name: String, reason: String) -> Self {
+		Self { name, skipped: Some(reason), compile: None, run: None }
+	}
+}
+
+/// milliseconds spent in each phase of the compiler.
+#[derive(Debug, Default, Serialize)]
+pub struct CompileTimes {
+	pub parse_ms: f64,
+	pub check_ms: f64,
+	pub build_ms: f64,
+	pub verify_ms: f64,
+	pub llvm_ms: f64,
+	pub emit_ms: f64,
+	pub link_ms: f64,
+	pub total_ms: f64,
+}
+
+impl CompileTimes {
+	pub fn sum(&mut self) {
+		let phases = [
+			self.parse_ms,
+			self.check_ms,
+			self.build_ms,
+			self.verify_ms,
+			self.llvm_ms,
+			self.emit_ms,
+			self.link_ms,
+		];
+		self.total_ms = phases.iter().sum();
+	}
+}
+
+/// wall time of the runs in milliseconds, the deviation is the sample standard deviation.
+#[derive(Debug, Serialize)]
+pub struct RunTimes {
+	pub exit_code: i32,
+	pub mean_ms: f64,
+	pub deviation_ms: f64,
+	pub min_ms: f64,
+	pub max_ms: f64,
+	pub samples_ms: Vec<f64>,
+}
+
+impl RunTimes {
+	pub fn new(exit_code: i32, samples: &[Duration]) -> Self {
+		let samples_ms: Vec<f64> = samples.iter().copied().map(millis).collect();
+		let count = samples_ms.len() as f64;
+		let mean_ms = samples_ms.iter().sum::<f64>() / count.max(1.0);
+		let squares: f64 = samples_ms.iter().map(|sample| (sample - mean_ms).powi(2)).sum();
+		let deviation_ms = match samples_ms.len() {
+			0 | 1 => 0.0,
+			_ => (squares / (count - 1.0)).sqrt(),
+		};
+		let min_ms = samples_ms.iter().copied().fold(f64::INFINITY, f64::min);
+		let max_ms = samples_ms.iter().copied().fold(0.0, f64::max);
+		Self { exit_code, mean_ms, deviation_ms, min_ms, max_ms, samples_ms }
+	}
+}
+
+pub fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+impl BenchReport {
+	pub fn write(&self, path: &Path) -> Result<(), String> {
+		if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+			fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+		}
+		let json = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+		fs::write(path, json + "\n").map_err(|err| err.to_string())
+	}
+
+	/// e.g. "stress.ln  1.20ms ± 0.08ms  compiled in 41ms"
+	pub fn print(&self) {
+		let width = self.programs.iter().map(|program| program.name.len()).max().unwrap_or(0);
+		for program in &self.programs {
+			let text = match (&program.skipped, &program.compile, &program.run) {
+				(Some(reason), _, _) => format!("skipped, {}", reason),
+				(None, Some(compile), Some(run)) => format!(
+					"{} ± {}  compiled in {}",
+					format_millis(run.mean_ms),
+					format_millis(run.deviation_ms),
+					format_millis(compile.total_ms)
+				),
+				_ => continue,
+			};
+			println!("{:width$}  {}", program.name, text, width = width);
+		}
+	}
+}
+
+fn format_millis(ms: f64) -> String {
+	format_time(Duration::from_secs_f64(ms / 1000.0), false)
+}
@@ -0,0 +1,78 @@
+use super::Builder;
+use crate::{
+	ast,
+	checker::types::TypeId,
+	ir::{self, IrBasicValue},
+};
+
+// what a failed assert exits with outside of a test
+const ASSERT_EXIT_CODE: usize = 101;
+
+impl Builder<'_> {
+	/// the checker leaves the callee of the builtin `assert` without a type.
+	pub fn is_builtin_assert(&self, expr: &ast::CallExpr) -> bool {
+		match &*expr.callee {
+			ast::Expr::Ident(ident) => {
+				ident.lexeme() == "assert" && self.lookup_event_type_optional(ident.get_range()).is_none()
+			}
+			_ => false,
+		}
+	}
+
+	/// prints where the assert failed and its message to stderr. a test fn returns
+	/// that it failed, other fns of a test build set the failed flag and go on, and
+	/// anywhere else the program exits.
+	pub fn build_assert_call(&mut self, expr: &mut ast::CallExpr) -> IrBasicValue {
+		let range = expr.get_range();
+		let mut args = self.build_function_args(&mut expr.args);
+		let (Some(message), Some(cond)) = (args.pop(), args.pop()) else {
+			self.internal_error_with_range("'assert' expects a cond and a message", range, self.loader);
+		};
+		let passed_block = self.ctx.current_block.create_new_block();
+		let failed_block = self.ctx.current_block.create_new_block();
+		let jmp_if = ir::JmpIfInstr::new(cond, passed_block.into(), failed_block.into());
+		self.append_instr(jmp_if.into(), Some(range));
+
+		self.switch_to_assert_block(failed_block.into(), range);
+		let source = self.loader.lookup_source_unchecked(self.mod_id_unchecked());
+		let location = source.location(range.start);
+		let params = [TypeId::I32, TypeId::STR];
+		let printf = self.declare_extern_function("dprintf", &params, TypeId::I32, true);
+		let dest = self.ctx.create_register(printf);
+		if !printf.is_empty_type() {
+			self.append_instr(ir::SallocInstr::new(dest.clone(), printf).into(), Some(range));
+		}
+		let stderr = IrBasicValue::from(2usize);
+		let format = "%s: assertion failed: %s\n".to_owned();
+		let args = vec![stderr, format.into(), location.into(), message];
+		let call = ir::CallInstr::new(dest, "dprintf".to_owned(), printf, args);
+		self.append_instr(call.into(), Some(range));
+
+		if self.in_test_fn {
+			self.drop_local_function_values(None);
+			let failed = IrBasicValue::from(1usize);
+			self.append_instr(ir::Instr::Ret(Some(failed)), Some(range));
+		} else if self.tests.is_some() {
+			let set = ir::UnInstr::new(self.test_failed_flag(), 1usize.into());
+			self.append_instr(ir::Instr::Set(set), Some(range));
+			self.append_instr(ir::JmpInstr::new(passed_block.into()).into(), Some(range));
+		} else {
+			let ret = self.declare_extern_function("exit", &[TypeId::I32], TypeId::VOID, false);
+			let dest = self.ctx.create_register(ret);
+			let code = IrBasicValue::from(ASSERT_EXIT_CODE);
+			let call = ir::CallInstr::new(dest, "exit".to_owned(), ret, vec![code]);
+			self.append_instr(call.into(), Some(range));
+			self.append_instr(ir::JmpInstr::new(passed_block.into()).into(), Some(range));
+		}
+
+		self.switch_to_assert_block(passed_block.into(), range);
+		self.ctx.create_register(TypeId::VOID)
+	}
+
+	fn switch_to_assert_block(&mut self, label: usize, range: crate::range::Range) {
+		let result = self.ctx.current_block.switch_to_label(label.into());
+		result.unwrap_or_else(|message| {
+			message.mod_id(self.mod_id_unchecked()).range(range).report(self.loader)
+		});
+	}
+}
@@ -1,11 +1,19 @@
-use crate::ast;
+use crate::{ast, ir::Instr};
 
 use super::Builder;
 
 impl Builder<'_> {
 	pub fn build_block_stmt(&mut self, block_stmt: &mut ast::BlockStmt) {
+		self.ctx.push_block_scope();
 		block_stmt.stmts.iter_mut().for_each(|stmt| {
 			self.build_stmt(stmt);
 		});
+		// the heap values of the block end with it, a `ret` already dropped them
+		if !self.ctx.current_block.has_returned {
+			for value in self.ctx.collect_unbound_values() {
+				self.append_instr(Instr::Drop(value), None);
+			}
+		}
+		self.ctx.pop_scope();
 	}
 }
@@ -1,13 +1,20 @@
 use super::Builder;
 use crate::{
-	ast, error_build,
+	ast,
+	checker::types::Type,
+	error_build,
 	ir::{self, IrBasicValue},
+	loader::ModId,
+	range::Range,
 };
 
 type CalleeResolvedType = (String, Option<IrBasicValue>);
 
 impl Builder<'_> {
 	pub fn build_call_expr(&mut self, expr: &mut ast::CallExpr) -> IrBasicValue {
+		if self.is_builtin_assert(expr) {
+			return self.build_assert_call(expr);
+		}
 		let range = expr.get_range();
 		let ret_type = self.lookup_event_type(range);
 		let dest = self.create_basic_value(ret_type);
@@ -35,7 +42,9 @@ impl Builder<'_> {
 	#[inline(always)]
 	fn resolve_callee(&mut self, expr: &mut ast::CallExpr) -> CalleeResolvedType {
 		match &mut *expr.callee {
-			ast::Expr::Ident(ident) => (ident.lexeme().to_string(), None),
+			ast::Expr::Ident(ident) => {
+				(self.resolve_function_symbol(self.mod_id_unchecked(), ident), None)
+			}
 			ast::Expr::Associate(associate_expr) => self.resolve_associate_expr(associate_expr),
 			ast::Expr::Member(member) => self.resolve_member_expr(member),
 			_ => todo!("unrecognized callee: {:?}", expr.callee),
@@ -44,18 +53,24 @@ impl Builder<'_> {
 
 	#[inline(always)]
 	fn resolve_associate_expr(&mut self, expr: &mut ast::AssociateExpr) -> CalleeResolvedType {
-		let self_name = expr.self_name.lexeme();
+		if let Some(mod_id) = self.lookup_event_mod_id(expr.get_range()) {
+			return (self.resolve_function_symbol(mod_id, &expr.method), None);
+		}
+		let self_name = self.struct_symbol(expr.self_name.lexeme());
 		let method_name = expr.method.lexeme();
-		(self.create_bind_method_with_selfname(self_name, method_name), None)
+		(self.create_bind_method_with_selfname(&self_name, method_name), None)
 	}
 
 	#[inline(always)]
 	fn resolve_member_expr(&mut self, member: &mut ast::MemberExpr) -> CalleeResolvedType {
+		if let Some(mod_id) = self.lookup_event_mod_id(member.left.get_range()) {
+			return (self.resolve_function_symbol(mod_id, &member.method), None);
+		}
 		self.ctx.push_struct_member_scope();
 		let self_value = self.build_expr(&mut member.left);
 		// let self_register = self_value.value.as_str();
 		self.ctx.pop_scope();
-		let self_name = match self.type_store.lookup_struct_name(self_value.get_type()) {
+		let self_name = match self.type_store.lookup_struct_symbol(self_value.get_type()) {
 			Some(name) => name,
 			None => error_build!("callee '{}' not found", member.method.lexeme()).report(self.loader),
 		};
@@ -64,7 +79,21 @@ impl Builder<'_> {
 		(method, Some(self_value))
 	}
 
-	fn build_function_args(&mut self, args_expr: &mut [ast::Expr]) -> Vec<IrBasicValue> {
+	// extern fns keep their C names, everything else is mangled by its mod
+	fn resolve_function_symbol(&self, mod_id: ModId, ident: &ast::Ident) -> String {
+		let type_id = self.lookup_event_type(ident.get_range());
+		if let Some(Type::ExternFn(_)) = self.type_store.lookup_type(type_id) {
+			return ident.lexeme().to_owned();
+		}
+		self.mangle_symbol(mod_id, ident.lexeme())
+	}
+
+	fn lookup_event_mod_id(&self, range: Range) -> Option<ModId> {
+		let type_id = self.lookup_event_type_optional(range)?;
+		self.type_store.lookup_module_type(type_id).map(|module| module.mod_id)
+	}
+
+	pub fn build_function_args(&mut self, args_expr: &mut [ast::Expr]) -> Vec<IrBasicValue> {
 		let mut basic_values = Vec::with_capacity(args_expr.len());
 		for expr in args_expr.iter_mut() {
 			let basic_value = self.build_expr(expr);
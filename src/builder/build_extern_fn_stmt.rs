@@ -1,9 +1,17 @@
-use crate::{ast, ir};
+use crate::{
+	ast,
+	checker::types::TypeId,
+	ir::{self, BasicValue, IrBasicValue},
+};
 
 use super::Builder;
 
 impl Builder<'_> {
 	pub fn build_extern_fn_stmt(&mut self, fn_stmt: &mut ast::ExternFnStmt) {
+		// extern symbols are shared, declare them once even if many mods do
+		if self.ir.has_function(fn_stmt.name.lexeme()) {
+			return;
+		}
 		let ret_type = self.lookup_event_type(fn_stmt.get_range());
 		self.ctx.push_function_scope(ret_type);
 		let name = fn_stmt.name.lexeme().to_owned();
@@ -15,4 +23,26 @@ impl Builder<'_> {
 		self.ctx.pop_scope();
 		self.push_function_with_blocks(func);
 	}
+
+	/// declares a libc function the builder calls on its own, e.g. `dprintf` for a
+	/// failed `assert`. a program's own declaration wins, its return type is returned.
+	pub fn declare_extern_function(
+		&mut self,
+		name: &str,
+		params: &[TypeId],
+		ret: TypeId,
+		variadic_args: bool,
+	) -> TypeId {
+		if let Some(found) = self.ir.functions.iter().find(|function| function.name == name) {
+			return found.ret;
+		}
+		let args = params.iter().enumerate().map(|(index, param)| {
+			let register = BasicValue::Register(format!("R{}", index + 1));
+			IrBasicValue::new(register, *param).as_param()
+		});
+		let mut func = ir::Function::new(name.to_owned(), false, args.collect(), ret);
+		func.as_extern_function(variadic_args);
+		self.ir.add_function(func);
+		ret
+	}
 }
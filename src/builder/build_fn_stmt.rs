@@ -4,16 +4,31 @@ use super::Builder;
 
 impl Builder<'_> {
 	pub fn build_fn_stmt(&mut self, fn_stmt: &mut ast::FnStmt) {
+		let mod_id = self.mod_id_unchecked();
+		if fn_stmt.is_test() && !self.is_building_tests_of(mod_id) {
+			return;
+		}
+		// the test harness is the entry's main
+		let is_entry = self.entry_mod_id == Some(mod_id);
+		if self.tests.is_some() && is_entry && fn_stmt.name.lexeme() == "main" {
+			return;
+		}
 		let range = fn_stmt.get_range();
 		let ret_type = self.lookup_event_type(range);
 		self.ctx.push_function_scope(ret_type);
-		let name = fn_stmt.name.lexeme().to_owned();
+		let name = self.mangle_symbol(self.mod_id_unchecked(), fn_stmt.name.lexeme());
 		let args: Vec<_> = fn_stmt.params.iter_mut().map(|arg| self.build_bind(arg)).collect();
 		let ret = self.lookup_event_type(range);
 		let comptime = false;
 		let func = ir::Function::new(name, comptime, args, ret);
+		self.in_test_fn = fn_stmt.is_test();
 		self.build_fn_body(&mut fn_stmt.body);
 		self.ctx.pop_scope();
+		if self.in_test_fn {
+			self.in_test_fn = false;
+			self.push_test_function(func, fn_stmt);
+			return;
+		}
 		self.push_function_with_blocks(func);
 	}
 
@@ -27,11 +42,16 @@ impl Builder<'_> {
 
 	pub fn build_fn_body(&mut self, body: &mut ast::FnBody) {
 		if let ast::FnBody::Expr(expr) = body {
-			let ret_value = self.build_expr(expr);
-			self.append_instr(ir::Instr::Ret(Some(ret_value)), Some(expr.get_range()));
-			// if !self.ctx.current_block.has_returned {
-			// 	self.drop_local_function_values(None);
-			// }
+			let mut ret_value = self.build_expr(expr);
+			// e.g. `x * x` is left in a stack slot, the function returns the value
+			let ret_value = self
+				.ctx
+				.function_return_type()
+				.filter(|ret| !ret.is_empty_type())
+				.map(|ret_type| self.ensure_loaded(ret_value.with_new_type(ret_type), expr.get_range()));
+			let ret_register = ret_value.as_ref().and_then(|value| value.value.try_get_register());
+			self.drop_local_function_values(ret_register);
+			self.append_instr(ir::Instr::Ret(ret_value), Some(expr.get_range()));
 		}
 		if let ast::FnBody::Block(block) = body {
 			for stmt in block.stmts.iter_mut() {
@@ -39,6 +59,10 @@ impl Builder<'_> {
 			}
 			if !self.ctx.current_block.has_returned {
 				self.drop_local_function_values(None);
+				// a void function returns at its end, the backends don't add it
+				if self.ctx.function_return_type().is_none_or(|ret| ret.is_empty_type()) {
+					self.append_instr(ir::Instr::Ret(None), None);
+				}
 			}
 		}
 	}
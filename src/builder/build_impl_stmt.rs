@@ -6,16 +6,18 @@ use crate::{
 
 impl Builder<'_> {
 	pub fn build_impl_stmt(&mut self, impl_stmt: &mut ImplStmt) {
-		let self_name = impl_stmt.self_name.lexeme();
+		let self_name = self.struct_symbol(impl_stmt.self_name.lexeme());
 		let self_range = impl_stmt.self_name.get_range();
-		let self_type = self.type_store.lookup_type_definition(self_name).copied();
+		let mod_id = self.mod_id_unchecked();
+		let self_type = self.type_store.lookup_type_definition(mod_id, impl_stmt.self_name.lexeme());
+		let self_type = self_type.copied();
 		let self_type = self_type.unwrap_or_else(|| {
 			self.internal_error_with_range("could not resolve type of self", self_range, self.loader)
 		});
-		self.ctx.push_implementation_scope(self_name, self_type);
+		self.ctx.push_implementation_scope(self_name.as_str(), self_type);
 
 		for item in impl_stmt.items.iter_mut() {
-			self.build_method(impl_stmt.self_name.lexeme(), item);
+			self.build_method(&self_name, item);
 		}
 	}
 
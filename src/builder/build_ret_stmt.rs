@@ -12,15 +12,14 @@ impl Builder<'_> {
 			let ret_value = ret_value.with_new_type(ret_type);
 			let ret_value = self.ensure_loaded(ret_value, expr.get_range());
 
-			if ret_value.is_register() {
-				self.drop_local_function_values(Some(ret_value.value.as_str()));
-			}
+			// a literal returns nothing the function owns, everything is dropped
+			self.drop_local_function_values(ret_value.value.try_get_register());
 
 			let instr = ir::Instr::Ret(Some(ret_value));
 			self.append_instr(instr, Some(r.get_range()));
-			// self.drop_local_function_values(None);
-			// let instr = ir::Instr::Ret(None);
-			// self.append_instr(instr, Some(r.get_range()));
+			return;
 		}
+		self.drop_local_function_values(None);
+		self.append_instr(ir::Instr::Ret(None), Some(r.get_range()));
 	}
 }
@@ -13,7 +13,7 @@ impl Builder<'_> {
 		let struct_init_range = struct_init_expr.get_range();
 
 		let self_type = self.lookup_event_type(struct_init_range);
-		let self_name = struct_init_expr.name.lexeme();
+		let self_name = &self.struct_symbol(struct_init_expr.name.lexeme());
 		let self_size = *self.ctx.struct_sizes.get(self_name).unwrap_or_else(|| {
 			let message = error_build!("struct `{}` does not exist", self_name);
 			message.range(struct_init_range).report(self.loader);
@@ -0,0 +1,191 @@
+use super::Builder;
+use crate::{
+	ast,
+	checker::types::TypeId,
+	ir::{self, IrBasicValue, IrBind},
+	loader::ModId,
+};
+
+/// set by an assert that fails outside of a test fn, e.g. in a helper it calls.
+/// such an assert can't return the failure, so the harness clears this before each
+/// test and checks it after.
+const TEST_FAILED: &str = "test_failed";
+
+/// a `@test` fn of the package, it returns 0 once every assert passed.
+pub struct TestFn {
+	pub symbol: String,
+	pub name: String,
+}
+
+impl Builder<'_> {
+	/// builds the `@test` fns of the package and a `main` that runs them, the ones
+	/// whose names contain `filter` only.
+	pub fn with_tests(mut self, filter: Option<String>) -> Self {
+		self.tests = Some(Vec::new());
+		self.test_filter = filter;
+		self.ir.add_global(IrBind::new(TEST_FAILED.to_owned(), TypeId::I32));
+		self
+	}
+
+	/// the register of the `TEST_FAILED` global.
+	pub fn test_failed_flag(&self) -> IrBasicValue {
+		IrBasicValue::new(ir::BasicValue::Register(TEST_FAILED.to_owned()), TypeId::I32)
+	}
+
+	/// dependencies are tested on their own.
+	pub fn is_building_tests_of(&self, mod_id: ModId) -> bool {
+		self.tests.is_some() && !self.loader.is_dependency_mod(mod_id)
+	}
+
+	/// a test fn returns whether it failed, `ret` becomes `ret i32 0`.
+	pub fn push_test_function(&mut self, mut func: ir::Function, fn_stmt: &ast::FnStmt) {
+		let mod_id = self.mod_id_unchecked();
+		let passed = IrBasicValue::from(0usize);
+		func.ret = TypeId::I32;
+		let symbol = func.name.clone();
+		self.push_function_with_blocks(func);
+		let func = self.ir.functions.last_mut().unwrap();
+		for instr in func.blocks.iter_mut().flat_map(|block| block.instrs_mut().iter_mut()) {
+			if let ir::Instr::Ret(value @ None) = instr {
+				*value = Some(passed.clone());
+			}
+		}
+
+		let name = match self.entry_mod_id == Some(mod_id) {
+			true => fn_stmt.name.lexeme().to_owned(),
+			false => {
+				let pathname = &self.loader.lookup_source_unchecked(mod_id).pathname;
+				format!("{}::{}", pathname.trim_end_matches(".ln"), fn_stmt.name.lexeme())
+			}
+		};
+		self.tests.get_or_insert_with(Vec::new).push(TestFn { symbol, name });
+	}
+
+	/// `main` calls every test that matches the filter, prints how it went and
+	/// exits with 1 if any failed. a test failed if it returned so or set `TEST_FAILED`.
+	pub fn build_test_harness(&mut self, entry_mod_id: ModId) {
+		let tests = self.tests.take().unwrap_or_default();
+		let filter = self.test_filter.take();
+		let (tests, filtered): (Vec<_>, Vec<_>) = tests
+			.into_iter()
+			.partition(|test| filter.as_deref().is_none_or(|filter| test.name.contains(filter)));
+
+		self.mod_id = Some(entry_mod_id);
+		self.ctx.push_function_scope(TypeId::I32);
+		let printf =
+			self.declare_extern_function("dprintf", &[TypeId::I32, TypeId::STR], TypeId::I32, true);
+
+		let count = match tests.len() {
+			1 => "running 1 test\n".to_owned(),
+			count => format!("running {} tests\n", count),
+		};
+		self.build_print(printf, vec![count.into()]);
+		let passed = self.build_counter();
+		let failed = self.build_counter();
+
+		for test in &tests {
+			let flag = self.test_failed_flag();
+			self.append_instr(ir::Instr::Set(ir::UnInstr::new(flag.clone(), 0usize.into())), None);
+			let result = self.ctx.create_register(TypeId::I32);
+			self.append_instr(ir::SallocInstr::new(result.clone(), TypeId::I32).into(), None);
+			let call = ir::CallInstr::new(result.clone(), test.symbol.clone(), TypeId::I32, Vec::new());
+			self.append_instr(call.into(), None);
+			let result = self.build_load(result, TypeId::I32);
+			let flag = self.build_load(flag, TypeId::I32);
+			let either = self.ctx.create_register(TypeId::I32);
+			self.append_instr(ir::SallocInstr::new(either.clone(), TypeId::I32).into(), None);
+			let or = ir::BinInstr::new(either.clone(), result, flag);
+			self.append_instr(ir::Instr::Or(or), None);
+			let either = self.build_load(either, TypeId::I32);
+			let is_ok = self.build_cmp_eq(either, 0usize.into());
+
+			let ok_block = self.ctx.current_block.create_new_block();
+			let failed_block = self.ctx.current_block.create_new_block();
+			let next_block = self.ctx.current_block.create_new_block();
+			let jmp_if = ir::JmpIfInstr::new(is_ok, ok_block.into(), failed_block.into());
+			self.append_instr(jmp_if.into(), None);
+
+			for (block, status, counter) in [(ok_block, "ok", &passed), (failed_block, "FAILED", &failed)]
+			{
+				self.switch_to_harness_block(block.into());
+				let text = format!("test {} ... {}\n", test.name, status);
+				self.build_print(printf, vec![text.into()]);
+				self.build_increment(counter.clone());
+				self.append_instr(ir::JmpInstr::new(next_block.into()).into(), None);
+			}
+			self.switch_to_harness_block(next_block.into());
+		}
+
+		let passed = self.build_load(passed, TypeId::I32);
+		let failed = self.build_load(failed, TypeId::I32);
+		let all_passed = self.build_cmp_eq(failed.clone(), 0usize.into());
+		let ok_block = self.ctx.current_block.create_new_block();
+		let failed_block = self.ctx.current_block.create_new_block();
+		let jmp_if = ir::JmpIfInstr::new(all_passed, ok_block.into(), failed_block.into());
+		self.append_instr(jmp_if.into(), None);
+
+		for (block, status, code) in [(ok_block, "ok", 0usize), (failed_block, "FAILED", 1)] {
+			self.switch_to_harness_block(block.into());
+			let text = format!(
+				"\ntest result: {}. %d passed; %d failed; {} filtered out\n",
+				status,
+				filtered.len()
+			);
+			self.build_print(printf, vec![text.into(), passed.clone(), failed.clone()]);
+			self.append_instr(ir::Instr::Ret(Some(code.into())), None);
+		}
+
+		self.ctx.pop_scope();
+		let func = ir::Function::new("main".to_owned(), false, Vec::new(), TypeId::I32);
+		self.push_function_with_blocks(func);
+		self.mod_id = None;
+	}
+
+	fn build_print(&mut self, printf: TypeId, args: Vec<IrBasicValue>) {
+		let dest = self.ctx.create_register(printf);
+		if !printf.is_empty_type() {
+			self.append_instr(ir::SallocInstr::new(dest.clone(), printf).into(), None);
+		}
+		let stdout = IrBasicValue::from(1usize);
+		let args = [stdout].into_iter().chain(args).collect();
+		let call = ir::CallInstr::new(dest, "dprintf".to_owned(), printf, args);
+		self.append_instr(call.into(), None);
+	}
+
+	fn build_counter(&mut self) -> IrBasicValue {
+		let counter = self.ctx.create_register(TypeId::I32);
+		self.append_instr(ir::SallocInstr::new(counter.clone(), TypeId::I32).into(), None);
+		let set = ir::UnInstr::new(counter.clone(), 0usize.into());
+		self.append_instr(ir::Instr::Set(set), None);
+		counter
+	}
+
+	fn build_increment(&mut self, counter: IrBasicValue) {
+		let value = self.build_load(counter.clone(), TypeId::I32);
+		let sum = self.ctx.create_register(TypeId::I32);
+		self.append_instr(ir::SallocInstr::new(sum.clone(), TypeId::I32).into(), None);
+		let add = ir::BinInstr::new(sum.clone(), value, 1usize.into());
+		self.append_instr(ir::Instr::Add(add), None);
+		let sum = self.build_load(sum, TypeId::I32);
+		self.append_instr(ir::Instr::Set(ir::UnInstr::new(counter, sum)), None);
+	}
+
+	fn build_cmp_eq(&mut self, left: IrBasicValue, right: IrBasicValue) -> IrBasicValue {
+		let dest = self.ctx.create_register(TypeId::BOOL);
+		self.append_instr(ir::SallocInstr::new(dest.clone(), TypeId::BOOL).into(), None);
+		let cmp = ir::BinInstr::new(dest.clone(), left, right);
+		self.append_instr(ir::Instr::CmpEq(cmp), None);
+		dest
+	}
+
+	fn build_load(&mut self, src: IrBasicValue, type_id: TypeId) -> IrBasicValue {
+		let dest = self.ctx.create_register(type_id);
+		self.append_instr(ir::Instr::Load(ir::UnInstr::new(dest.clone(), src)), None);
+		dest
+	}
+
+	fn switch_to_harness_block(&mut self, label: usize) {
+		let result = self.ctx.current_block.switch_to_label(label.into());
+		result.unwrap_or_else(|message| message.mod_id(self.mod_id_unchecked()).report(self.loader));
+	}
+}
@@ -11,9 +11,10 @@ impl Builder<'_> {
 			TypeDefKind::Struct(struct_def_stmt) => {
 				// todo: is the best way? but why :( two data structures? we relly need?
 				let (mut ir_struct, field_table) = self.build_struct_def_stmt(struct_def_stmt);
-				ir_struct.set_name(type_def_stmt.lexeme());
-				self.ctx.define_struct_fields(type_def_stmt.lexeme().into(), field_table);
-				self.ctx.struct_sizes.insert(type_def_stmt.lexeme().into(), ir_struct.size);
+				let symbol = self.struct_symbol(type_def_stmt.lexeme());
+				ir_struct.set_name(&symbol);
+				self.ctx.define_struct_fields(symbol.clone(), field_table);
+				self.ctx.struct_sizes.insert(symbol, ir_struct.size);
 				self.ir.add_struct(ir_struct);
 			}
 			_ => error_build!("unsupported type definition kind")
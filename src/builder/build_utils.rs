@@ -7,7 +7,7 @@ impl Builder<'_> {
 		if value_type.is_builtin_type() || self.type_store.is_borrow(value_type) {
 			return None;
 		}
-		let value_name = match self.type_store.lookup_struct_name(value_type) {
+		let value_name = match self.type_store.lookup_struct_symbol(value_type) {
 			Some(name) => name,
 			None => {
 				let type_text = self.type_store.lookup_display_ir_type(value_type);
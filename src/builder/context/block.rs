@@ -3,6 +3,7 @@ use crate::{
 	error_build,
 	ir::{Instr, IrBlock},
 	message::MessageResult,
+	range::Range,
 };
 
 pub struct Block {
@@ -47,8 +48,8 @@ impl Block {
 		blocks
 	}
 
-	pub fn append_instr(&mut self, instr: Instr) -> MessageResult<()> {
-		self.current_block_mut()?.append_instr(instr);
+	pub fn append_instr(&mut self, instr: Instr, range: Option<Range>) -> MessageResult<()> {
+		self.current_block_mut()?.append_instr_with_range(instr, range);
 		Ok(())
 	}
 
@@ -72,6 +72,10 @@ impl Context {
 		self.push_scope(scope);
 	}
 
+	pub fn push_block_scope(&mut self) {
+		self.push_scope(Scope::new());
+	}
+
 	pub fn push_struct_member_scope(&mut self) {
 		self.push_scope(Scope::new_struct_member_scope());
 	}
@@ -138,4 +142,16 @@ impl Context {
 	pub fn collect_unbound_values(&self) -> Vec<IrBasicValue> {
 		self.current_scope().collect_unbound_values()
 	}
+
+	/// the values of every scope up to the function's, a `ret` leaves them all.
+	pub fn collect_function_unbound_values(&self) -> Vec<IrBasicValue> {
+		let mut values = Vec::new();
+		for scope in self.scope_stack.iter().rev() {
+			values.extend(scope.collect_unbound_values());
+			if scope.is_function() {
+				break;
+			}
+		}
+		values
+	}
 }
@@ -69,7 +69,6 @@ impl Scope {
 	pub fn is_struct_member(&self) -> bool {
 		matches!(self.kind, ScopeKind::StructMember)
 	}
-	#[allow(dead_code)]
 	pub fn is_function(&self) -> bool {
 		matches!(self.kind, ScopeKind::Function { .. })
 	}
@@ -7,6 +7,7 @@ use crate::ir::{Instr, IrBasicValue, IR};
 use crate::loader::{Loader, ModId};
 use crate::range::Range;
 use crate::{ast, error_build, ir};
+use build_test_harness::TestFn;
 use context::Context;
 
 mod build_assign_expr;
@@ -18,6 +19,7 @@ mod build_if_expr;
 mod build_deref_expr;
 mod build_expr;
 
+mod build_assert_call;
 mod build_associate_expr;
 mod build_block_stmt;
 mod build_extern_fn_stmt;
@@ -31,6 +33,7 @@ mod build_member_expr;
 mod build_ret_stmt;
 mod build_struct_def_stmt;
 mod build_struct_init_expr;
+mod build_test_harness;
 mod build_type_def_stmt;
 mod build_utils;
 
@@ -41,16 +44,45 @@ pub struct Builder<'br> {
 	event: &'br mut Event,
 	loader: &'br mut Loader,
 	mod_id: Option<ModId>,
+	entry_mod_id: Option<ModId>,
+	// `lemon test`, the test fns built so far
+	tests: Option<Vec<TestFn>>,
+	test_filter: Option<String>,
+	in_test_fn: bool,
 }
 
 impl<'br> Builder<'br> {
 	pub fn new(type_store: &'br TypeStore, event: &'br mut Event, loader: &'br mut Loader) -> Self {
 		let ctx = Context::new();
 		let ir = IR::default();
-		Self { ctx, event, ir, type_store, loader, mod_id: None }
+		Self {
+			ctx,
+			event,
+			ir,
+			type_store,
+			loader,
+			mod_id: None,
+			entry_mod_id: None,
+			tests: None,
+			test_filter: None,
+			in_test_fn: false,
+		}
+	}
+
+	pub fn build(&mut self, entry_mod_id: ModId) -> IR {
+		self.entry_mod_id = Some(entry_mod_id);
+		// every loaded mod is reachable from the entry through imports
+		for mod_id in self.loader.mod_ids() {
+			self.build_mod(mod_id);
+		}
+		if self.tests.is_some() {
+			self.build_test_harness(entry_mod_id);
+		}
+		self.entry_mod_id = None;
+		mem::take(&mut self.ir)
 	}
 
-	pub fn build(&mut self, mod_id: ModId) -> IR {
+	fn build_mod(&mut self, mod_id: ModId) {
 		self.mod_id = Some(mod_id);
 		let mut program = self.loader.take_mod_result(mod_id).unwrap_or_else(|message| {
 			message.report(self.loader);
@@ -60,7 +92,19 @@ impl<'br> Builder<'br> {
 			self.build_stmt(stmt);
 		}
 		self.mod_id = None;
-		mem::take(&mut self.ir)
+	}
+
+	/// symbols of the entry mod keep their names, e.g. `main`.
+	pub fn mangle_symbol(&self, mod_id: ModId, name: &str) -> String {
+		if self.entry_mod_id == Some(mod_id) {
+			return name.to_owned();
+		}
+		mod_id.mangle(name)
+	}
+
+	/// the ir name of a struct of the current mod, the checker mangled it the same way.
+	pub fn struct_symbol(&self, name: &str) -> String {
+		self.mangle_symbol(self.mod_id_unchecked(), name)
 	}
 
 	#[inline(always)]
@@ -90,7 +134,7 @@ impl<'br> Builder<'br> {
 		let m = error_build!("{}", msg);
 		m.note_internal().report(loader);
 	}
-	fn internal_error_with_range(&self, msg: &str, range: Range, loader: &Loader) -> ! {
+	pub fn internal_error_with_range(&self, msg: &str, range: Range, loader: &Loader) -> ! {
 		let m = error_build!("{}", msg).mod_id(self.mod_id_unchecked());
 		m.note_internal().range(range).report(loader);
 	}
@@ -98,11 +142,12 @@ impl<'br> Builder<'br> {
 	pub fn push_function_with_blocks(&mut self, mut function: ir::Function) {
 		let blocks = self.ctx.current_block.extract_blocks();
 		function.extend_blocks(blocks);
+		function.mod_id = self.mod_id;
 		self.ir.add_function(function);
 	}
 
 	pub fn drop_local_function_values(&mut self, ret_value: Option<&str>) {
-		for value in self.ctx.collect_unbound_values() {
+		for value in self.ctx.collect_function_unbound_values() {
 			if ret_value.map(|ret_value| value.value.as_str() != ret_value).unwrap_or(true) {
 				self.append_instr(Instr::Drop(value), None);
 			}
@@ -111,6 +156,9 @@ impl<'br> Builder<'br> {
 
 	fn build_stmt(&mut self, stmt: &mut ast::Stmt) {
 		match stmt {
+			// imported mods are built on their own
+			ast::Stmt::Let(let_stmt) if let_stmt.expr.is_import() => {}
+			ast::Stmt::ConstDel(const_del) if const_del.expr.is_import() => {}
 			ast::Stmt::Let(let_stmt) => self.build_let_stmt(let_stmt),
 			ast::Stmt::Fn(fn_stmt) => self.build_fn_stmt(fn_stmt),
 			ast::Stmt::ExternFn(extern_fn_stmt) => self.build_extern_fn_stmt(extern_fn_stmt),
@@ -133,7 +181,7 @@ impl<'br> Builder<'br> {
 
 	#[inline]
 	pub fn append_instr(&mut self, instr: Instr, range: Option<Range>) {
-		if let Err(message) = self.ctx.current_block.append_instr(instr) {
+		if let Err(message) = self.ctx.current_block.append_instr(instr, range) {
 			message.mod_id(self.mod_id_unchecked()).range_if_some(range).report(self.loader);
 		}
 	}
@@ -1,7 +1,7 @@
 use super::ptr::RefId;
 use std::ops::{Index, IndexMut};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Arena<T> {
 	items: Vec<T>,
 }
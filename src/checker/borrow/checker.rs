@@ -3,14 +3,14 @@ use super::error;
 use super::ptr::{RefAccess, RefData, RefId, RefState};
 use crate::checker::context::scope::Scope;
 use crate::checker::typed_value::{RefSource, TypedValue};
-use crate::error_ownership;
 use crate::message::MessageResult;
+use crate::range::Range;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::fmt::Write;
 
 pub type BorrowTracker = FxHashMap<RefId, FxHashSet<RefId>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BorrowChecker {
 	pub arena: Arena<RefData>,
 	pub tracker: BorrowTracker,
@@ -48,6 +48,12 @@ impl BorrowChecker {
 		Ok(new_id)
 	}
 
+	pub fn set_range(&mut self, ref_id: RefId, range: Range) {
+		if let Some(ref_data) = self.arena.get_mut(ref_id) {
+			ref_data.range = Some(range);
+		}
+	}
+
 	pub fn create_raw_copy(&mut self) -> RefId {
 		let id = RefId(self.arena.len());
 		let data = RefData::new(id, RefAccess::RawCopy);
@@ -65,11 +71,11 @@ impl BorrowChecker {
 	pub fn borrow_owner(&mut self, value: &mut TypedValue) -> MessageResult<RefId> {
 		for base in value.source.iter() {
 			let Some(owner_ref) = self.arena.get(base) else {
-				return Err(error_ownership!("cannot move '{}': dropped", base));
+				return Err(error::move_while_droped(base.as_string()));
 			};
 
 			if !owner_ref.access.is_owner() || owner_ref.state.is_droped() {
-				return Err(error_ownership!("cannot move '{}': dropped", base));
+				return Err(error::move_while_droped(base.as_string()));
 			}
 		}
 		for base in value.source.iter() {
@@ -135,10 +141,12 @@ impl BorrowChecker {
 			for alive_borrower in self.lookup_alive_borrowers(&value.source) {
 				let ref_data = &self.arena[alive_borrower];
 				if ref_data.access.is_immutable() {
-					return Err(error::mutable_while_immutable_exists(base.as_string()));
+					let message = error::mutable_while_immutable_exists(base.as_string());
+					return Err(message.label_if_some(ref_data.range, "immutable borrow here"));
 				}
 				if ref_data.access.is_mutable() {
-					return Err(error::mutable_more_than_once(base.as_string()));
+					let message = error::mutable_more_than_once(base.as_string());
+					return Err(message.label_if_some(ref_data.range, "first mutable borrow here"));
 				}
 			}
 		}
@@ -146,12 +154,12 @@ impl BorrowChecker {
 	}
 
 	pub fn can_borrow_immutable(&mut self, value: &TypedValue) -> MessageResult<()> {
-		let debug = self.dump_tracker_state();
-		println!("{}", debug);
-		if self.lookup_alive_borrowers(&value.source).any(|id| self.arena[id].access.is_mutable()) {
+		let mutable =
+			self.lookup_alive_borrowers(&value.source).find(|id| self.arena[*id].access.is_mutable());
+		if let Some(mutable_id) = mutable {
 			let owner_name = value.source.as_string();
 			let message = error::immutable_while_mutable_exists(owner_name);
-			return Err(message);
+			return Err(message.label_if_some(self.arena[mutable_id].range, "mutable borrow here"));
 		}
 		Ok(())
 	}
@@ -159,11 +167,11 @@ impl BorrowChecker {
 	pub fn can_borrow_owner(&self, value: &TypedValue) -> MessageResult<()> {
 		for owner in value.source.iter() {
 			let Some(data) = self.arena.get(owner) else {
-				return Err(error_ownership!("cannot move '{}': dropped", owner.as_string()));
+				return Err(error::move_while_droped(owner.as_string()));
 			};
 
 			if data.state.is_droped() || !data.access.is_owner() {
-				return Err(error_ownership!("cannot move '{}': dropped", owner.as_string()));
+				return Err(error::move_while_droped(owner.as_string()));
 			}
 		}
 		Ok(())
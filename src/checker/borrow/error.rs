@@ -2,24 +2,29 @@ use crate::{error_ownership, message::Message};
 
 #[inline]
 pub fn mutable_more_than_once(name: impl Into<String>) -> Message {
-	error_ownership!("cannot borrow '{}' as mutable more than once", name.into())
+	error_ownership!("cannot borrow '{}' as mutable more than once", name.into()).code(203)
 }
 #[inline]
 pub fn mutable_while_droped(name: impl Into<String>) -> Message {
-	error_ownership!("cannot borrow '{}' as mutable while droped", name.into())
+	error_ownership!("cannot borrow '{}' as mutable while droped", name.into()).code(204)
 }
 
 #[inline]
 pub fn immutable_while_mutable_exists(name: impl Into<String>) -> Message {
-	error_ownership!("cannot borrow '{}' as immutable while mutable exists", name.into())
+	error_ownership!("cannot borrow '{}' as immutable while mutable exists", name.into()).code(202)
 }
 
 #[inline]
 pub fn mutable_while_immutable_exists(name: impl Into<String>) -> Message {
-	error_ownership!("cannot borrow '{}' as mutable while immutable exists", name.into())
+	error_ownership!("cannot borrow '{}' as mutable while immutable exists", name.into()).code(202)
 }
 
 #[inline]
 pub fn immutable_while_droped(name: impl Into<String>) -> Message {
-	error_ownership!("cannot borrow '{}' as immutable while droped", name.into())
+	error_ownership!("cannot borrow '{}' as immutable while droped", name.into()).code(204)
+}
+
+#[inline]
+pub fn move_while_droped(name: impl Into<String>) -> Message {
+	error_ownership!("cannot move '{}': dropped", name.into()).code(208)
 }
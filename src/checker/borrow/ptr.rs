@@ -1,3 +1,5 @@
+use crate::range::Range;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RefAccess {
 	Owner,
@@ -93,15 +95,17 @@ pub struct RefData {
 	pub access: RefAccess,
 	pub state: RefState,
 	pub origin: RefOrigin,
+	/// where the borrow was taken, e.g. `&mut x`
+	pub range: Option<Range>,
 }
 
 impl RefData {
 	pub fn new(id: RefId, access: RefAccess) -> Self {
-		Self { id, access, state: RefState::default(), origin: RefOrigin::External }
+		Self { id, access, state: RefState::default(), origin: RefOrigin::External, range: None }
 	}
 
 	pub fn new_local(id: RefId, access: RefAccess) -> Self {
-		Self { id, access, state: RefState::default(), origin: RefOrigin::Local }
+		Self { id, access, state: RefState::default(), origin: RefOrigin::Local, range: None }
 	}
 
 	pub fn new_owned(id: RefId) -> Self {
@@ -5,7 +5,7 @@ use crate::{
 };
 
 use super::{
-	diags::SyntaxErr,
+	diags::{suggest, SyntaxErr},
 	types::{Type, TypeId},
 	CheckResult, Checker, ExpectSome,
 };
@@ -40,7 +40,8 @@ impl Checker<'_> {
 			self.equal_type_expected(value.typed_value.type_id, found, ident.get_range())?;
 			return Ok(value.typed_value.type_id);
 		}
-		Err(SyntaxErr::not_found_value(lexeme, ident.get_range()))
+		let similar = suggest::similar_name(lexeme, self.ctx.visible_names());
+		Err(SyntaxErr::did_you_mean(SyntaxErr::not_found_value(lexeme, ident.get_range()), similar))
 	}
 
 	fn assign_deref_expr(
@@ -83,11 +84,13 @@ impl Checker<'_> {
 				return Ok(field.type_id);
 			}
 
-			let method = member.method.lexeme().to_owned();
+			let similar = suggest::similar_name(lexeme, struct_type.field_names()).map(str::to_owned);
+			let method = lexeme.to_owned();
 			let found = self._display_type_value(struct_type.into());
-			return Err(SyntaxErr::not_found_method_named(method, found, member.get_range()));
+			let message = SyntaxErr::not_found_method_named(method, found, member.get_range());
+			return Err(SyntaxErr::did_you_mean(message, similar.as_deref()));
 		}
-		todo!("assign member expr {:?}", self_type)
+		Err(SyntaxErr::unsupported("assigning this member", member.get_range()))
 	}
 
 	pub fn try_mutate_expr(&self, expr: &ast::Expr) -> MessageResult<(String, bool)> {
@@ -96,7 +99,7 @@ impl Checker<'_> {
 			ast::Expr::Member(member) => self.try_mutate_expr(&member.left),
 			ast::Expr::Deref(deref) => self.try_mutate_expr(&deref.expr),
 			ast::Expr::Assign(assign) => self.try_mutate_expr(&assign.right),
-			_ => todo!("code {:?}", expr),
+			_ => Err(SyntaxErr::unsupported("mutating this expression", expr.get_range())),
 		}
 	}
 
@@ -105,6 +108,7 @@ impl Checker<'_> {
 		if let Some(value) = self.ctx.lookup_variable_value(lexeme) {
 			return Ok((lexeme.to_owned(), value.mutable));
 		}
-		Err(SyntaxErr::not_found_value(lexeme, ident.get_range()))
+		let similar = suggest::similar_name(lexeme, self.ctx.visible_names());
+		Err(SyntaxErr::did_you_mean(SyntaxErr::not_found_value(lexeme, ident.get_range()), similar))
 	}
 }
@@ -1,19 +1,43 @@
 use super::context::scope::ScopeKind;
-use super::diags::SyntaxErr;
+use super::diags::{suggest, SyntaxErr};
+use super::types::TypeId;
 use super::{CheckResult, Checker};
 use crate::ast;
+use crate::message::Message;
+use crate::range::Range;
 
 impl Checker<'_> {
 	pub fn check_associate_expr(&mut self, associate: &mut ast::AssociateExpr) -> CheckResult {
 		let self_name = associate.self_name.lexeme();
-		let self_type_id = match self.ctx.type_store.lookup_type_definition(self_name) {
-			Some(type_id) => *type_id,
-			None => return Err(SyntaxErr::not_found_type(self_name, associate.self_name.get_range())),
+		// e.g. `math::add`, where `math` is an imported module
+		let (self_type_id, is_associated) = match self.lookup_module_type_id(self_name) {
+			Some(type_id) => (type_id, false),
+			None => match self.ctx.lookup_type_definition(self_name) {
+				Some(type_id) => (*type_id, true),
+				None => return Err(self.not_found_type(self_name, associate.self_name.get_range())),
+			},
 		};
-		self.ctx.enter_scope(ScopeKind::accessor(self_type_id, true));
+		if is_associated {
+			self.register_member_use(self_type_id, "", associate.self_name.get_range());
+		}
+		self.ctx.enter_scope(ScopeKind::accessor(self_type_id, is_associated));
 		let ret_type = self.check_ident_expr(&mut associate.method)?;
 		self.ctx.exit_scope();
 		self.register_type(self_type_id, associate.get_range());
 		Ok(ret_type)
 	}
+
+	fn lookup_module_type_id(&mut self, name: &str) -> Option<TypeId> {
+		let value = self.ctx.use_variable_value(name)?;
+		if value.typed_value.module {
+			return Some(value.typed_value.type_id);
+		}
+		None
+	}
+
+	pub(super) fn not_found_type(&self, name: &str, range: Range) -> Message {
+		let similar =
+			suggest::similar_name(name, self.ctx.type_store.type_definition_names(self.ctx.mod_id));
+		SyntaxErr::did_you_mean(SyntaxErr::not_found_type(name, range), similar)
+	}
 }
@@ -46,7 +46,7 @@ impl Checker<'_> {
 			// bitwise
 			AND | OR | SHL | XOR => self._check_bitwise(left, right, operator)?,
 			SHR => self._check_bitwise(left, right, operator)?,
-			_ => todo!(),
+			_ => return Err(SyntaxErr::unsupported("this operator", operator.get_range())),
 		};
 		self.register_type(found_id, range);
 		self.register_type(found_id, operator.get_range());
@@ -77,7 +77,7 @@ impl Checker<'_> {
 		rt: TypeId,
 		operator: &Operator,
 	) -> MessageResult<TypeId> {
-		todo!("check range operator")
+		Err(SyntaxErr::unsupported("the range operator", operator.get_range()))
 	}
 
 	fn check_cmp_operator(
@@ -1,16 +1,25 @@
 use crate::ast;
 
-use super::{context::scope::ScopeKind, CheckResult, Checker};
+use super::{context::scope::ScopeKind, diags::TypeCheckWarn, CheckResult, Checker};
 
 impl Checker<'_> {
 	pub fn check_block_stmt(&mut self, block: &mut ast::BlockStmt) -> CheckResult {
-		// todo: warn unreachable code
+		self.warn_unreachable_stmts(&block.stmts);
 		self.ctx.enter_scope(ScopeKind::block_scope());
-		let mut ret_type = None;
-		for stmt in block.stmts.iter_mut() {
-			ret_type = self.check_stmt(stmt)?;
-		}
+		let ret_type = self.check_block_stmts(&mut block.stmts);
 		self.ctx.exit_scope();
 		Ok(ret_type)
 	}
+
+	/// warns on the statements following a return.
+	pub fn warn_unreachable_stmts(&mut self, stmts: &[ast::Stmt]) {
+		let Some(ret_at) = stmts.iter().position(|stmt| stmt.ends_with_ret()) else {
+			return;
+		};
+		let unreachable = &stmts[ret_at + 1..];
+		if let (Some(first), Some(last)) = (unreachable.first(), unreachable.last()) {
+			let range = first.get_range().merged_with(&last.get_range());
+			self.ctx.add_warning(TypeCheckWarn::unreachable(range));
+		}
+	}
 }
@@ -24,6 +24,7 @@ impl Checker<'_> {
 			Ok(id) => id,
 			Err(err) => return Err(err.range(range)),
 		};
+		self.ctx.borrow.set_range(ref_id, range);
 
 		Ok(Some(TypedValue::new(target.type_id, ref_id)))
 	}
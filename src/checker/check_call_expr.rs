@@ -6,6 +6,9 @@ use crate::message::MessageResult;
 
 impl Checker<'_> {
 	pub fn check_call_expr(&mut self, c: &mut ast::CallExpr) -> CheckResult {
+		if self.is_builtin_assert(c) {
+			return self.check_assert_call(c);
+		}
 		let callee = self.check_expr(&mut c.callee).some(c.callee.get_range())?;
 		let (params, return_type, var_packed) = self.fn_signature(callee.type_id, c.get_range())?;
 
@@ -22,6 +25,28 @@ impl Checker<'_> {
 		// self.make_ret_value(&args, ret_ty, c.get_range())
 	}
 
+	/// `assert(cond, msg)`, unless the program defines its own `assert`.
+	fn is_builtin_assert(&self, c: &ast::CallExpr) -> bool {
+		let ast::Expr::Ident(ident) = &*c.callee else {
+			return false;
+		};
+		let name = ident.lexeme();
+		name == "assert"
+			&& self.ctx.lookup_variable_value(name).is_none()
+			&& self.ctx.lookup_function_value(name).is_none()
+	}
+
+	// the callee has no type, that's how the builder tells the builtin apart
+	fn check_assert_call(&mut self, c: &mut ast::CallExpr) -> CheckResult {
+		let params = [TypeId::BOOL, TypeId::STR];
+		if let Err(message) = self.check_call_arguments(&mut c.args, &params, false) {
+			return Err(message.range_if_some(Some(c.get_range())));
+		}
+		self.register_type(TypeId::VOID, c.get_range());
+		let owner = self.ctx.borrow.create_owner();
+		Ok(Some(TypedValue::new(TypeId::VOID, owner)))
+	}
+
 	pub fn check_call_arguments(
 		&mut self,
 		arguments: &mut [ast::Expr],
@@ -45,6 +70,10 @@ impl Checker<'_> {
 			}
 			self.register_type(found_infered, argument_range);
 		}
+		// the args a variadic fn packs have no expected type, they're still values in use
+		for argument in arguments.iter_mut().skip(expecteds.len()) {
+			self.check_expr(argument).some(argument.get_range())?;
+		}
 		Ok(())
 	}
 
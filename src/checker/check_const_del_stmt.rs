@@ -1,3 +1,4 @@
+use super::context::value::Value;
 use super::{diags::SyntaxErr, Checker};
 use super::{synthesis, CheckResult, ExpectSome};
 use crate::ast;
@@ -16,6 +17,7 @@ impl Checker<'_> {
 				return Err(SyntaxErr::type_annotation_not_allowed_for_module(range));
 			}
 			self.ctx.type_store.add_mod_name(found.type_id, &lexeme);
+			self.ctx.add_value(&lexeme, Value::new(found.clone(), false));
 			return Ok(Some(found));
 		}
 
@@ -6,7 +6,7 @@ impl Checker<'_> {
 		if !self.ctx.is_global_scope() {
 			return Err(SyntaxErr::const_outside_global_scope(c.range));
 		}
-		todo!()
+		Err(SyntaxErr::unsupported("a const fn", c.range))
 		// Ok(TypedValue::default())
 	}
 }
@@ -1,6 +1,6 @@
 use crate::ast;
 
-use super::{CheckResult, Checker};
+use super::{diags::SyntaxErr, CheckResult, Checker};
 impl Checker<'_> {
 	pub fn check_expr(&mut self, expr: &mut ast::Expr) -> CheckResult {
 		match expr {
@@ -16,7 +16,7 @@ impl Checker<'_> {
 			ast::Expr::Import(import_expr) => self.check_import_expr(import_expr),
 			ast::Expr::Associate(associate_expr) => self.check_associate_expr(associate_expr),
 			ast::Expr::Member(member_expr) => self.check_member_expr(member_expr),
-			_ => todo!("code {:?}", expr),
+			_ => Err(SyntaxErr::unsupported("this expression", expr.get_range())),
 		}
 	}
 }
@@ -8,7 +8,7 @@ impl Checker<'_> {
 		let fn_type = synthesise_extren_fn_stmt(extrn_fn, self.ctx, self.ctx.mod_id)?;
 		let lexeme = extrn_fn.name.lexeme();
 		let type_id = self.ctx.type_store.add_type(fn_type.into());
-		self.ctx.add_function_value(lexeme, type_id);
+		self.ctx.add_function_value(lexeme, type_id, extrn_fn.name.get_range());
 		Ok(None)
 	}
 }
@@ -6,6 +6,7 @@ use super::types::TypeId;
 use super::{synthesis, CheckResult, Checker, ExpectSome};
 
 use crate::ast;
+use crate::lints::{self, Lint};
 use crate::message::Message;
 use crate::range::Range;
 
@@ -21,9 +22,16 @@ impl Checker<'_> {
 		let fn_args_types = fn_type.args.clone();
 		let fn_type_id = self.ctx.type_store.add_type(fn_type.into());
 
-		self.register_function_type(lexeme, fn_type_id, range)?;
+		let name_range = fn_stmt.name.get_range();
+		self.register_function_type(lexeme, fn_type_id, range, name_range)?;
 		self.register_type(return_id, range);
-		self.ctx.enter_scope(ScopeKind::function(return_id));
+		self.register_type(fn_type_id, name_range);
+		if fn_stmt.is_test() {
+			self.check_test_fn(fn_stmt, return_id)?;
+		}
+		let ret_range = fn_stmt.ret_type.as_ref().map(|ret_type| ret_type.get_range());
+		self.ctx.enter_scope(ScopeKind::function(return_id, ret_range));
+		self.check_lint_attrs(&fn_stmt.attrs)?;
 
 		self.register_fn_parameters(&mut fn_stmt.params, &fn_args_types)?;
 
@@ -33,6 +41,11 @@ impl Checker<'_> {
 
 		if fn_stmt.is_pub {
 			self.ctx.add_pub_function(lexeme.into(), fn_type_id);
+			if let Some(mod_type_id) = self.ctx.type_store.lookup_mod(mod_id).copied() {
+				self.register_declaration(mod_type_id, lexeme, name_range);
+			}
+		} else {
+			self.ctx.add_private_item(lexeme.into());
 		}
 
 		Ok(None)
@@ -47,12 +60,56 @@ impl Checker<'_> {
 
 			let owner_id = self.ctx.borrow.create_owner();
 			let typed_value = TypedValue::new(*type_id, owner_id);
-			let value = Value::new(typed_value, mutable);
+			let value = Value::new(typed_value, mutable).declared_at(param.ident.get_range());
+			self.register_type(*type_id, param.ident.get_range());
 			self.ctx.add_value(param_name, value);
 		}
 		Ok(())
 }
 
+	/// the test harness calls a `@test` fn on its own, it has to be a mod level fn
+	/// that takes and returns nothing.
+	fn check_test_fn(&self, fn_stmt: &ast::FnStmt, return_id: TypeId) -> Result<(), Message> {
+		let name = fn_stmt.lexeme();
+		let range = fn_stmt.name.get_range();
+		let attr = fn_stmt.attrs.iter().find(|attr| attr.lexeme() == "test");
+		if let Some(attr) = attr.filter(|attr| !attr.args.is_empty()) {
+			return Err(SyntaxErr::invalid_test_fn(name, "'@test' takes no args", attr.get_range()));
+		}
+		let reason = if !self.ctx.is_global_scope() {
+			"a test function has to be at the top of its mod"
+		} else if !fn_stmt.params.is_empty() || fn_stmt.is_generic() {
+			"a test function takes no params"
+		} else if !return_id.is_empty_type() {
+			"a test function returns nothing"
+		} else {
+			return Ok(());
+		};
+		Err(SyntaxErr::invalid_test_fn(name, reason, range))
+	}
+
+	/// applies `@allow`, `@warn` and `@deny` to the current scope.
+	fn check_lint_attrs(&mut self, attrs: &[ast::Attribute]) -> Result<(), Message> {
+		if attrs.is_empty() {
+			return Ok(());
+		}
+		let mut levels = self.ctx.lint_levels().clone();
+		// `@test` is checked on its own
+		for attr in attrs.iter().filter(|attr| attr.lexeme() != "test") {
+			let name = attr.lexeme();
+			let Some(level) = lints::parse_level(name) else {
+				return Err(SyntaxErr::unknown_attribute(name, attr.name.get_range()));
+			};
+			for arg in attr.args.iter() {
+				let lint = Lint::from_name(arg.lexeme());
+				let lint = lint.ok_or_else(|| SyntaxErr::unknown_lint(arg.lexeme(), arg.get_range()))?;
+				levels.set(lint, level);
+			}
+		}
+		self.ctx.set_scope_lints(levels);
+		Ok(())
+	}
+
 	#[inline(always)]
 	pub fn check_fn_body(&mut self, stmt: &mut ast::FnBody) -> CheckResult {
 		match stmt {
@@ -62,11 +119,8 @@ impl Checker<'_> {
 	}
 
 	pub fn check_fn_block_stmt(&mut self, stmt: &mut ast::BlockStmt) -> CheckResult {
-		let mut last_ret = None;
-		for stmt in &mut stmt.stmts {
-			last_ret = self.check_stmt(stmt)?;
-		}
-		Ok(last_ret)
+		self.warn_unreachable_stmts(&stmt.stmts);
+		Ok(self.check_block_stmts(&mut stmt.stmts))
 	}
 
 	pub fn check_inplicit_return(&mut self, expr: &mut ast::Expr) -> CheckResult {
@@ -82,7 +136,8 @@ impl Checker<'_> {
 		if !self.equal_type_id(expected_ret_id, ret_value.type_id) {
 			let expected = self.display_type(expected_ret_id);
 			let found = self.display_type(ret_value.type_id);
-			return Err(SyntaxErr::type_mismatch(expected, found, expr.get_range()));
+			let ret_range = self.ctx.get_return_range();
+			return Err(SyntaxErr::return_type_mismatch(expected, found, expr.get_range(), ret_range));
 		}
 
 		Ok(Some(ret_value))
@@ -97,7 +152,7 @@ impl Checker<'_> {
 	}
 
 	#[rustfmt::skip]
-	fn register_function_type(&mut self,name: &str, type_id: TypeId, range: Range) -> Result<(), Message> {
+	fn register_function_type(&mut self,name: &str, type_id: TypeId, range: Range, name_range: Range) -> Result<(), Message> {
 		if self.ctx.has_implementation_scope() {
 			let self_type_id = self.ctx.get_scope().get_self_scope_type().unwrap();
 			let self_type = self.lookup_stored_mut_type(self_type_id);
@@ -107,9 +162,10 @@ impl Checker<'_> {
 			}
 			struct_type.add_function(name.to_string(), type_id);
 			struct_type.add_associate(name.to_string(), type_id);
+			self.register_declaration(self_type_id, name, name_range);
 			return Ok(());
 		}
-		self.ctx.add_function_value(name, type_id);
+		self.ctx.add_function_value(name, type_id, name_range);
 		Ok(())
 	}
 }
@@ -1,9 +1,9 @@
-use super::{CheckResult, Checker};
+use super::{diags::SyntaxErr, CheckResult, Checker};
 use crate::ast;
 
 impl Checker<'_> {
 	pub fn check_for_stmt(&mut self, for_stmt: &mut ast::ForStmt) -> CheckResult {
-		todo!()
+		Err(SyntaxErr::unsupported("a for loop", for_stmt.get_range()))
 		// let test_type = self.check_expr(&mut for_stmt.test)?;
 		// self.equal_type_expected(TypeId::BOOL, test_type, for_stmt.test.get_range())?;
 
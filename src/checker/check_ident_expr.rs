@@ -1,8 +1,8 @@
 use crate::ast;
 use crate::error_type;
-use crate::report::report_message_without_module;
 
-use super::diags::SyntaxErr;
+use super::diags::{suggest, SyntaxErr};
+use super::events::EventId;
 use super::types::{ModuleType, StructType, Type};
 use super::{CheckResult, Checker, TypedValue};
 
@@ -15,48 +15,63 @@ impl Checker<'_> {
 			return self.check_self_access(ident);
 		}
 
-		if let Some(value) = self.ctx.lookup_variable_value(name).cloned() {
+		if let Some(value) = self.ctx.use_variable_value(name).cloned() {
 			self.register_type(value.typed_value.type_id, range);
+			if let Some(declared) = value.range {
+				self.register_definition(range, EventId::new(self.ctx.mod_id, declared));
+			}
 			return Ok(Some(value.typed_value));
 		}
 
-		if let Some(fn_type_id) = self.ctx.lookup_function_value(name).map(|v| v.type_id) {
+		if let Some(function) = self.ctx.lookup_function_value(name).cloned() {
+			let fn_type_id = function.type_id;
 			self.register_type(fn_type_id, range);
+			if let Some(declared) = function.range {
+				self.register_definition(range, EventId::new(self.ctx.mod_id, declared));
+			}
 			// todo: copy or owned?
 			let raw_copy = self.ctx.borrow.create_raw_copy();
 			return Ok(Some(TypedValue::new(fn_type_id, raw_copy)));
 		}
 
-		Err(SyntaxErr::not_found_value(name, range))
+		let similar = suggest::similar_name(name, self.ctx.visible_names());
+		Err(SyntaxErr::did_you_mean(SyntaxErr::not_found_value(name, range), similar))
 	}
 
 	fn check_self_access(&mut self, ident: &mut ast::Ident) -> CheckResult {
 		let self_type_id = self.ctx.get_accessor_scope_type().expect("accessor scope not found");
 		let self_type = self.lookup_stored_type_without_borrow(self_type_id).clone();
 
+		let owner_id = self.ctx.type_store.resolve_borrow_type(self_type_id);
+		self.register_member_use(owner_id, ident.lexeme(), ident.get_range());
 		match self_type {
 			Type::Struct(s) => self.check_struct_access(ident, &s),
 			Type::Mod(m) => self.check_module_access(ident, &m),
-			other => todo!("unexpected self type in accessor: {:?}", other),
+			_ => Err(SyntaxErr::unsupported("accessing this type", ident.get_range())),
 		}
 	}
 
-	fn check_module_access(&self, ident: &mut ast::Ident, mod_type: &ModuleType) -> CheckResult {
+	fn check_module_access(&mut self, ident: &mut ast::Ident, mod_type: &ModuleType) -> CheckResult {
 		let mod_id = mod_type.mod_id;
 		let range = ident.get_range();
 		let name = ident.lexeme();
 
 		let module = match self.ctx.get_module(mod_id) {
 			Some(m) => m,
-			// return Err(SyntaxErr::module_not_found(mod_id, range)); //
-			None => report_message_without_module(&error_type!("module not found: {}", mod_id)),
+			None => return Err(error_type!("module not found: {}", mod_id).range(range)),
 		};
 
-		if module.get_value(name).is_some() || module.get_function(name).is_some() {
-			return Ok(None);
+		if let Some(type_id) = module.get_function(name).or(module.get_value(name)).copied() {
+			self.register_type(type_id, range);
+			let raw_copy = self.ctx.borrow.create_raw_copy();
+			return Ok(Some(TypedValue::new(type_id, raw_copy)));
 		}
 
-		Err(SyntaxErr::not_found_pub_item(name.into(), range))
+		let message = SyntaxErr::not_found_pub_item(name.into(), range);
+		if module.is_private(name) {
+			return Err(SyntaxErr::not_pub_item(message, name));
+		}
+		Err(SyntaxErr::did_you_mean(message, suggest::similar_name(name, module.pub_names())))
 	}
 
 	fn check_struct_access(
@@ -70,12 +85,15 @@ impl Checker<'_> {
 		if self.ctx.is_associated_scope() {
 			if let Some(type_id) = struct_type.get_associate(name) {
 				self.register_type(*type_id, range);
-				return Ok(None);
+				let raw_copy = self.ctx.borrow.create_raw_copy();
+				return Ok(Some(TypedValue::new(*type_id, raw_copy)));
 			}
 
 			let self_type = Type::from(struct_type.clone());
 			let found = self.display_type_value(&self_type);
-			return Err(SyntaxErr::not_found_associate_field(name.to_owned(), found, range));
+			let message = SyntaxErr::not_found_associate_field(name.to_owned(), found, range);
+			let similar = suggest::similar_name(name, struct_type.associated.keys().map(String::as_str));
+			return Err(SyntaxErr::did_you_mean(message, similar));
 		}
 
 		if let Some(field) = struct_type.get_field(name) {
@@ -85,11 +103,13 @@ impl Checker<'_> {
 
 		if let Some(method_id) = struct_type.get_fn(name) {
 			self.register_type(*method_id, range);
-			return Ok(None);
+			let raw_copy = self.ctx.borrow.create_raw_copy();
+			return Ok(Some(TypedValue::new(*method_id, raw_copy)));
 		}
 
 		let self_type = Type::from(struct_type.clone());
 		let found = self.display_type_value(&self_type);
-		Err(SyntaxErr::not_found_method_named(name.to_owned(), found, range))
+		let message = SyntaxErr::not_found_method_named(name.to_owned(), found, range);
+		Err(SyntaxErr::did_you_mean(message, suggest::similar_name(name, struct_type.member_names())))
 	}
 }
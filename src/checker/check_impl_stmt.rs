@@ -5,12 +5,13 @@ use super::{context::scope::ScopeKind, diags::SyntaxErr, CheckResult, Checker};
 impl Checker<'_> {
 	pub fn check_impl_stmt(&mut self, impl_stmt: &mut ast::ImplStmt) -> CheckResult {
 		let self_name = impl_stmt.self_name.lexeme();
-		let self_type_id = self.ctx.type_store.lookup_type_definition(self_name).copied();
+		let self_type_id = self.ctx.lookup_type_definition(self_name).copied();
 		if self_type_id.is_none() {
-			return Err(SyntaxErr::not_found_type(self_name, impl_stmt.self_name.get_range()));
+			return Err(self.not_found_type(self_name, impl_stmt.self_name.get_range()));
 		}
 		// todo: is correct?
 		let self_type_id = self_type_id.unwrap();
+		self.register_member_use(self_type_id, "", impl_stmt.self_name.get_range());
 		let self_type = self.lookup_stored_mut_type(self_type_id);
 
 		if !self_type.can_implemented() {
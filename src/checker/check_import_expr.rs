@@ -2,6 +2,7 @@ use crate::ast::{self};
 use crate::loader::ModId;
 use crate::range::Range;
 
+use super::context::scope::Scope;
 use super::diags::SyntaxErr;
 use super::typed_value::TypedValue;
 use super::types::ModuleType;
@@ -14,32 +15,49 @@ impl Checker<'_> {
 		let owner_id = self.ctx.borrow.create_owner();
 		let mod_id = match import_expr.mod_id {
 			Some(mod_id) => mod_id,
-			None => return Err(SyntaxErr::not_found_module(filename.as_str(), range)),
+			None => {
+				let message = SyntaxErr::not_found_module(filename.as_str(), range);
+				return Err(self.loader.suggest_mod(message, &filename, self.ctx.mod_id));
+			}
 		};
 		if let Some(type_id) = self.ctx.type_store.lookup_mod(mod_id) {
 			return Ok(Some(TypedValue::new_module(*type_id, owner_id)));
 		}
-		self.ctx.add_mod(mod_id);
-		self.check_mod(mod_id, range)?;
+		// the parser rejects import cycles, so the mod isn't being checked already
 		let module_type = ModuleType::new(mod_id);
 		let type_id = self.ctx.type_store.add_type(module_type.into());
 		self.ctx.type_store.add_mod(mod_id, type_id);
+		if self.ctx.get_module(mod_id).is_none() {
+			self.ctx.add_mod(mod_id);
+			self.check_mod(mod_id, range)?;
+		}
 		Ok(Some(TypedValue::new_module(type_id, owner_id)))
 	}
 
+	/// checks a mod of the package that nothing imports, e.g. for `lemon test`.
+	pub fn check_package_mod(&mut self, mod_id: ModId) {
+		if self.ctx.type_store.lookup_mod(mod_id).is_none() {
+			let type_id = self.ctx.type_store.add_type(ModuleType::new(mod_id).into());
+			self.ctx.type_store.add_mod(mod_id, type_id);
+			self.ctx.add_mod(mod_id);
+			if let Err(message) = self.check_mod(mod_id, Range::default()) {
+				self.loader.add_message(message.mod_id(mod_id));
+			}
+		}
+		for warning in self.ctx.take_warnings() {
+			self.loader.add_message(warning);
+		}
+		self.loader.report_messages();
+	}
+
 	pub fn check_mod(&mut self, mod_id: ModId, range: Range) -> CheckResult {
-		let source = self.loader.lookup_source_unchecked(mod_id).clone();
-		#[rustfmt::skip]
-		let mut ast = self.loader.lookup_mod_result(mod_id).cloned().unwrap_or_else(|message| {
-		  message.report(self.loader);
-		});
+		let mut ast = self.loader.lookup_mod_result(mod_id).cloned()?;
 		let temp_mod_id = self.ctx.mod_id;
 		self.ctx.swap_mod(mod_id);
-		for stmt in ast.stmts.iter_mut() {
-			if let Err(message) = self.check_stmt(stmt) {
-				message.mod_id(mod_id).report(self.loader);
-			}
-		}
+		// each mod is checked in its own global scope
+		let temp_scopes = self.ctx.swap_scopes(vec![Scope::default()]);
+		self.check_mod_stmts(mod_id, &mut ast.stmts);
+		self.ctx.swap_scopes(temp_scopes);
 		self.ctx.swap_mod(temp_mod_id);
 		Ok(None)
 	}
@@ -22,11 +22,15 @@ impl Checker<'_> {
 		if !self.equal_type_id(expected_id, final_id) {
 			let found_ty = self.display_type(final_id);
 			let expected_ty = self.display_type(expected_id);
-			return Err(SyntaxErr::type_mismatch(expected_ty, found_ty, range));
+			let message = SyntaxErr::type_mismatch(expected_ty, found_ty, range);
+			let annotation = let_stmt.bind.ty.as_ref().map(|ty| ty.get_range());
+			return Err(message.label_if_some(annotation, "expected because of this type"));
 		}
 		self.register_type(final_id, range);
+		self.register_type(final_id, let_stmt.bind.ident.get_range());
 		found.infer_type(final_id);
-		self.ctx.add_value(lexeme, Value::new(found, mutable));
+		let value = Value::new(found, mutable).declared_at(let_stmt.bind.ident.get_range());
+		self.ctx.add_value(lexeme, value);
 
 		Ok(None)
 	}
@@ -21,7 +21,10 @@ impl Checker<'_> {
 				return Err(SyntaxErr::cannot_return_local_reference(range));
 			}
 
-			self.equal_type_expected(ret_id, found.type_id, range)?;
+			let ret_range = self.ctx.get_return_range();
+			self
+				.equal_type_expected(ret_id, found.type_id, range)
+				.map_err(|message| SyntaxErr::expected_by_return(message, ret_range))?;
 			self.register_type(ret_id, range);
 
 			return Ok(Some(found));
@@ -31,7 +34,8 @@ impl Checker<'_> {
 		if !ret_id.is_void_type() {
 			let found = self.display_type(TypeId::VOID);
 			let ret = self.display_type(ret_id);
-			return Err(SyntaxErr::type_mismatch(ret, found, range));
+			let ret_range = self.ctx.get_return_range();
+			return Err(SyntaxErr::return_type_mismatch(ret, found, range, ret_range));
 		}
 
 		self.register_type(ret_id, range);
@@ -1,4 +1,4 @@
-use super::diags::SyntaxErr;
+use super::diags::{suggest, SyntaxErr};
 use super::{CheckResult, Checker, ExpectSome, TypedValue};
 use crate::ast;
 
@@ -6,11 +6,12 @@ impl Checker<'_> {
 	pub fn check_struct_init_expr(&mut self, init: &mut ast::StructInitExpr) -> CheckResult {
 		let lexeme = init.name.lexeme();
 		let range = init.get_range();
-		let Some(found_id) = self.ctx.type_store.lookup_type_definition(lexeme).copied() else {
-			return Err(SyntaxErr::not_found_type(lexeme, init.name.get_range()));
+		let Some(found_id) = self.ctx.lookup_type_definition(lexeme).copied() else {
+			return Err(self.not_found_type(lexeme, init.name.get_range()));
 		};
 
 		self.register_type(found_id, range);
+		self.register_member_use(found_id, "", init.name.get_range());
 
 		// remove clone :(
 		let mut found_type = self.lookup_stored_type(found_id).clone();
@@ -36,10 +37,12 @@ impl Checker<'_> {
 			let lexeme = field_expr.name.lexeme();
 			let range = field_expr.name.get_range();
 			if !found_struct_type.has_field(lexeme) {
-				return Err(SyntaxErr::not_found_field(lexeme, range));
+				let similar = suggest::similar_name(lexeme, found_struct_type.field_names());
+				return Err(SyntaxErr::did_you_mean(SyntaxErr::not_found_field(lexeme, range), similar));
 			}
 
 			let field_type = found_struct_type.get_field(lexeme).unwrap();
+			self.register_member_use(found_id, lexeme, range);
 
 			// if !field_type.is_mut {
 			// 	return Err(SyntaxErr::cannot_assign_immutable(lexeme, range));
@@ -18,12 +18,24 @@ impl Checker<'_> {
 		let name_range = type_def.name.get_range();
 		let struct_def = type_def.get_struct_def().unwrap();
 		let fields = synthesise_struct_def(struct_def, self.ctx, self.ctx.mod_id)?;
+		let field_names: Vec<_> = struct_def
+			.fields
+			.iter()
+			.map(|field| (field.lexeme().to_owned(), field.get_range()))
+			.collect();
 		let mut struct_type = StructType::new(lexeme.to_owned());
+		if !self.ctx.is_entry_module(self.ctx.mod_id) {
+			struct_type.symbol = self.ctx.mod_id.mangle(&lexeme);
+		}
 		struct_type.with_fields(fields);
 		let type_id = self.ctx.type_store.add_type(struct_type.into());
 		self.register_type(type_id, range);
 		self.register_type(type_id, name_range);
-		self.ctx.type_store.add_type_definition(lexeme, type_id);
+		self.register_declaration(type_id, "", name_range);
+		for (field_name, field_range) in field_names {
+			self.register_declaration(type_id, field_name, field_range);
+		}
+		self.ctx.add_type_definition(lexeme, type_id);
 		Ok(None)
 	}
 
@@ -31,7 +43,7 @@ impl Checker<'_> {
 		let alias_def = type_def.get_alias().unwrap();
 		let lexeme = type_def.lexeme().to_string();
 		let type_id = synthesise_ast_type(alias_def, self.ctx)?;
-		self.ctx.type_store.add_type_definition(lexeme, type_id);
+		self.ctx.add_type_definition(lexeme, type_id);
 		Ok(None)
 	}
 }
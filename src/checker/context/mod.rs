@@ -1,9 +1,16 @@
 use super::{
 	borrow::BorrowChecker,
+	diags::TypeCheckWarn,
 	events::Event,
-	types::{TypeId, TypeStore},
+	types::{Type, TypeId, TypeStore},
+};
+use crate::{
+	lints::LintLevels,
+	loader::ModId,
+	message::{Message, Severity},
+	note,
+	range::Range,
 };
-use crate::loader::ModId;
 use module::Module;
 use rustc_hash::FxHashMap;
 use scope::{Scope, ScopeKind};
@@ -13,7 +20,7 @@ mod module;
 pub mod scope;
 pub mod value;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Context {
 	pub scopes: Vec<Scope>,
 	pub event: Event,
@@ -22,6 +29,9 @@ pub struct Context {
 	pub mods: FxHashMap<ModId, Module>,
 	pub mod_id: ModId,
 	pub mark_use: bool,
+	pub warnings: Vec<Message>,
+	/// lint levels of the package, scopes may override them
+	pub lints: LintLevels,
 }
 
 impl Context {
@@ -42,6 +52,8 @@ impl Context {
 			mods,
 			mod_id,
 			mark_use: false,
+			warnings: Vec::new(),
+			lints: LintLevels::default(),
 		}
 	}
 
@@ -59,12 +71,71 @@ impl Context {
 	}
 
 	pub fn exit_scope(&mut self) {
+		// warned before popping, the lints of the scope still apply
+		let unused: Vec<(String, Value)> = match self.scopes.last() {
+			Some(scope) => scope.variables.iter().filter(|(name, value)| value.is_unused(name)),
+			None => return,
+		}
+		.map(|(name, value)| (name.clone(), value.clone()))
+		.collect();
+		for (name, value) in unused.iter() {
+			self.warn_unused_value(name, value);
+		}
 		let Some(scope) = self.scopes.pop() else {
 			return;
 		};
 		self.borrow.release_all_from_scope(&scope);
 	}
 
+	/// exits scopes left open by a failed check, down to `depth`.
+	/// unused warnings are skipped, the uses after the error weren't checked.
+	pub fn exit_scopes_to(&mut self, depth: usize) {
+		while self.scopes.len() > depth {
+			let Some(scope) = self.scopes.pop() else {
+				return;
+			};
+			self.borrow.release_all_from_scope(&scope);
+		}
+	}
+
+	// ======= warning methods =======
+	/// reports a warning at the level of its lint, allowed ones are dropped.
+	pub fn add_warning(&mut self, warning: TypeCheckWarn) {
+		let lint = warning.lint();
+		let level = self.lint_levels().level(lint);
+		if level == Severity::Allow {
+			return;
+		}
+		let mut message = Message::from(warning).note(note!("from lint '{}'", lint));
+		message.severity = level;
+		self.warnings.push(message.mod_id(self.mod_id));
+	}
+
+	/// the lint levels of the innermost scope setting them.
+	pub fn lint_levels(&self) -> &LintLevels {
+		let scoped = self.scopes.iter().rev().find_map(|scope| scope.lints.as_ref());
+		scoped.unwrap_or(&self.lints)
+	}
+
+	pub fn set_scope_lints(&mut self, lints: LintLevels) {
+		self.get_scope_mut().lints = Some(lints);
+	}
+
+	pub fn take_warnings(&mut self) -> Vec<Message> {
+		std::mem::take(&mut self.warnings)
+	}
+
+	fn warn_unused_value(&mut self, name: &str, value: &Value) {
+		let Some(range) = value.range.filter(|_| value.is_unused(name)) else {
+			return;
+		};
+		let type_id = value.typed_value.type_id;
+		match self.type_store.lookup_type(type_id) {
+			Some(Type::Borrow(_)) => self.add_warning(TypeCheckWarn::unused_borrow(range)),
+			_ => self.add_warning(TypeCheckWarn::unused(name, range)),
+		}
+	}
+
 	pub fn is_global_scope(&self) -> bool {
 		self.get_scope().is_global_scope()
 	}
@@ -112,6 +183,12 @@ impl Context {
 		self.scopes.iter().rev().find_map(Scope::get_return_type)
 	}
 
+	/// the return type annotation of the enclosing function.
+	pub fn get_return_range(&self) -> Option<Range> {
+		let scope = self.scopes.iter().rev().find(|scope| scope.kind.get_return_type().is_some());
+		scope.and_then(|scope| scope.kind.get_return_range())
+	}
+
 	// ======= module methods =======
 	pub fn get_module(&self, mod_id: ModId) -> Option<&Module> {
 		self.mods.get(&mod_id)
@@ -125,6 +202,10 @@ impl Context {
 		self.mod_id = mod_id;
 	}
 
+	pub fn swap_scopes(&mut self, scopes: Vec<Scope>) -> Vec<Scope> {
+		std::mem::replace(&mut self.scopes, scopes)
+	}
+
 	pub fn add_mod(&mut self, mod_id: ModId) {
 		self.mods.insert(mod_id, Module::new(mod_id));
 		// self.swap_mod(mod_id);
@@ -154,17 +235,36 @@ impl Context {
 		}
 	}
 
+	pub fn add_private_item(&mut self, name: String) {
+		if let Some(module) = self.mods.get_mut(&self.mod_id) {
+			module.add_private(name);
+		}
+	}
+
 	// ======= value methods =======
 	pub fn add_value(&mut self, name: &str, value: Value) {
-		self.get_scope_mut().add_variable(name.to_string(), value);
+		let previous = self.lookup_variable_value(name).and_then(|previous| previous.range);
+		if let (Some(previous), Some(range)) = (previous, value.range) {
+			self.add_warning(TypeCheckWarn::shadowed(name, range, previous));
+		}
+		if let Some(shadowed) = self.get_scope_mut().add_variable(name.to_string(), value) {
+			self.warn_unused_value(name, &shadowed);
+		}
+	}
+
+	/// looks up a value, marking it as used.
+	pub fn use_variable_value(&mut self, name: &str) -> Option<&Value> {
+		let value = self.lookup_variable_value_mut(name)?;
+		value.used = true;
+		Some(value)
 	}
 
 	pub fn add_type_definition(&mut self, name: String, type_id: TypeId) {
-		self.type_store.add_type_definition(name, type_id);
+		self.type_store.add_type_definition(self.mod_id, name, type_id);
 	}
 
 	pub fn lookup_type_definition(&self, name: &str) -> Option<&TypeId> {
-		self.type_store.lookup_type_definition(name)
+		self.type_store.lookup_type_definition(self.mod_id, name)
 	}
 
 	pub fn lookup_variable_value(&self, name: &str) -> Option<&Value> {
@@ -174,14 +274,22 @@ impl Context {
 		self.scopes.iter_mut().rev().find_map(|scope| scope.lookup_variable_mut(name))
 	}
 
-	pub fn add_function_value(&mut self, name: &str, type_id: TypeId) {
-		let value = FunctionValue::new_runtime(type_id);
+	pub fn add_function_value(&mut self, name: &str, type_id: TypeId, range: Range) {
+		let value = FunctionValue::new_runtime(type_id).declared_at(range);
 		self.get_scope_mut().add_function(name.to_string(), value);
 	}
 
 	pub fn lookup_function_value(&self, name: &str) -> Option<&FunctionValue> {
 		self.scopes.iter().rev().find_map(|scope| scope.lookup_function(name))
 	}
+
+	/// names of every value and function in scope, for suggestions.
+	pub fn visible_names(&self) -> impl Iterator<Item = &str> {
+		let scopes = self.scopes.iter().rev();
+		scopes
+			.flat_map(|scope| scope.variables.keys().chain(scope.functions.keys()))
+			.map(String::as_str)
+	}
 }
 
 impl Default for Context {
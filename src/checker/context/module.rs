@@ -1,8 +1,8 @@
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{checker::types::TypeId, loader::ModId};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Module {
 	pub mod_id: ModId,
 	pub is_entry: bool,
@@ -10,19 +10,21 @@ pub struct Module {
 	pub values: FxHashMap<String, TypeId>,
 	// functions
 	pub functions: FxHashMap<String, TypeId>,
+	// names of the items without `pub`
+	pub private: FxHashSet<String>,
 }
 
 impl Module {
 	pub fn new(mod_id: ModId) -> Self {
 		let values = FxHashMap::default();
 		let functions = FxHashMap::default();
-		Self { mod_id, values, functions, is_entry: false }
+		Self { mod_id, values, functions, private: FxHashSet::default(), is_entry: false }
 	}
 
 	pub fn with_entry(mod_id: ModId) -> Self {
 		let values = FxHashMap::default();
 		let functions = FxHashMap::default();
-		Self { mod_id, values, functions, is_entry: true }
+		Self { mod_id, values, functions, private: FxHashSet::default(), is_entry: true }
 	}
 
 	pub fn add_value(&mut self, name: String, type_id: TypeId) {
@@ -33,6 +35,19 @@ impl Module {
 		self.functions.insert(name, type_id);
 	}
 
+	pub fn add_private(&mut self, name: String) {
+		self.private.insert(name);
+	}
+
+	pub fn is_private(&self, name: &str) -> bool {
+		self.private.contains(name)
+	}
+
+	/// names of the pub functions and values.
+	pub fn pub_names(&self) -> impl Iterator<Item = &str> {
+		self.functions.keys().chain(self.values.keys()).map(String::as_str)
+	}
+
 	pub fn get_value(&self, name: &str) -> Option<&TypeId> {
 		self.values.get(name)
 	}
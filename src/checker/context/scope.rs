@@ -1,22 +1,33 @@
-use crate::checker::types::TypeId;
+use crate::{checker::types::TypeId, lints::LintLevels, range::Range};
 use rustc_hash::FxHashMap;
 
 use super::value::{FunctionValue, Value};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScopeKind {
-	Function { ret_type: TypeId },
-	ConstantFunction { ret_type: TypeId },
+	/// `ret_range` is the return type annotation, if any
+	Function {
+		ret_type: TypeId,
+		ret_range: Option<Range>,
+	},
+	ConstantFunction {
+		ret_type: TypeId,
+	},
 	Loop,
 	Block,
 	Global,
-	Implementation { self_type: TypeId },
-	Accessor { self_type: TypeId, is_associated: bool },
+	Implementation {
+		self_type: TypeId,
+	},
+	Accessor {
+		self_type: TypeId,
+		is_associated: bool,
+	},
 }
 
 impl ScopeKind {
-	pub fn function(ret_type: TypeId) -> Self {
-		Self::Function { ret_type }
+	pub fn function(ret_type: TypeId, ret_range: Option<Range>) -> Self {
+		Self::Function { ret_type, ret_range }
 	}
 
 	pub fn constant_function(ret_type: TypeId) -> Self {
@@ -45,7 +56,14 @@ impl ScopeKind {
 
 	pub fn get_return_type(&self) -> Option<TypeId> {
 		match self {
-			Self::Function { ret_type } | Self::ConstantFunction { ret_type } => Some(*ret_type),
+			Self::Function { ret_type, .. } | Self::ConstantFunction { ret_type } => Some(*ret_type),
+			_ => None,
+		}
+	}
+
+	pub fn get_return_range(&self) -> Option<Range> {
+		match self {
+			Self::Function { ret_range, .. } => *ret_range,
 			_ => None,
 		}
 	}
@@ -81,6 +99,8 @@ pub struct Scope {
 	pub type_definitions: FxHashMap<String, TypeId>,
 	// pub borrow_tracker: BorrowTracker,
 	pub kind: ScopeKind,
+	/// lint levels set by attributes, e.g. `@allow(unused_variable)`
+	pub lints: Option<LintLevels>,
 }
 
 impl Scope {
@@ -91,6 +111,7 @@ impl Scope {
 			type_definitions: FxHashMap::default(),
 			// borrow_tracker: BorrowTracker::default(),
 			kind,
+			lints: None,
 		}
 	}
 
@@ -101,8 +122,9 @@ impl Scope {
 		self.type_definitions.get(name)
 	}
 
-	pub fn add_variable(&mut self, name: String, value: Value) {
-		self.variables.insert(name, value);
+	/// adds a variable, returning the one it shadows in this scope.
+	pub fn add_variable(&mut self, name: String, value: Value) -> Option<Value> {
+		self.variables.insert(name, value)
 	}
 
 	pub fn add_function(&mut self, name: String, value: FunctionValue) {
@@ -1,25 +1,48 @@
-use crate::checker::{typed_value::TypedValue, types::TypeId};
+use crate::{
+	checker::{typed_value::TypedValue, types::TypeId},
+	range::Range,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Value {
 	pub mutable: bool,
 	pub typed_value: TypedValue,
+	/// where it's declared, only local values have it and warn when unused
+	pub range: Option<Range>,
+	pub used: bool,
 }
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionValue {
 	pub type_id: TypeId,
 	pub comptime: bool,
+	/// where it's declared
+	pub range: Option<Range>,
 }
 
 impl Value {
 	pub fn new(typed_value: TypedValue, mutable: bool) -> Self {
-		Self { typed_value, mutable }
+		Self { typed_value, mutable, range: None, used: false }
+	}
+
+	pub fn declared_at(mut self, range: Range) -> Self {
+		self.range = Some(range);
+		self
+	}
+
+	/// declared locally, never used and not silenced with a leading underscore.
+	pub fn is_unused(&self, name: &str) -> bool {
+		self.range.is_some() && !self.used && !name.starts_with('_') && name != "self"
 	}
 }
 
 impl FunctionValue {
 	pub fn new(type_id: TypeId, comptime: bool) -> Self {
-		Self { type_id, comptime }
+		Self { type_id, comptime, range: None }
+	}
+
+	pub fn declared_at(mut self, range: Range) -> Self {
+		self.range = Some(range);
+		self
 	}
 	pub fn new_comptime(type_id: TypeId) -> Self {
 		Self::new(type_id, true)
@@ -1,4 +1,4 @@
-use crate::{ast::Operator, error_type, message::Message, range::Range};
+use crate::{ast::Operator, error_type, message::Message, note, range::Range};
 
 // todo: improve this...
 #[derive(Debug)]
@@ -67,46 +67,71 @@ pub enum SyntaxErr<'tce> {
 	// ModuleRedefined { name: String, range: Range },
 	// ModuleImportFailed { name: String, range: Range },
 	WrongArgCount { expected: usize, found: usize, range: Range },
+
+	// attribute errors
+	UnknownAttribute { name: &'tce str, range: Range },
+	UnknownLint { name: &'tce str, range: Range },
 }
 
 impl<'tce> SyntaxErr<'tce> {
 	#[inline]
 	pub fn const_outside_global_scope(range: Range) -> Message {
-		error_type!("const can only be defined at global scope").range(range)
+		error_type!("const can only be defined at global scope").code(123).range(range)
 	}
 
 	#[inline]
 	pub fn const_redefinition(range: Range) -> Message {
-		error_type!("const already defined").range(range)
+		error_type!("const already defined").code(124).range(range)
+	}
+
+	#[inline]
+	pub fn unsupported(what: &str, range: Range) -> Message {
+		error_type!("{} is not supported yet", what).code(125).range(range)
 	}
 
 	#[inline]
 	pub fn immutable(name: &'tce str, range: Range) -> Message {
-		error_type!("value '{}' is not mutable", name).range(range)
+		error_type!("value '{}' is not mutable", name).code(201).range(range)
 	}
 
 	#[inline]
 	pub fn type_mismatch(expected: String, found: String, range: Range) -> Message {
-		error_type!("expected '{}', found '{}'", expected, found).range(range)
+		error_type!("expected '{}', found '{}'", expected, found).code(101).range(range)
+	}
+
+	#[inline]
+	pub fn return_type_mismatch(
+		expected: String,
+		found: String,
+		range: Range,
+		ret_range: Option<Range>,
+	) -> Message {
+		Self::expected_by_return(Self::type_mismatch(expected, found, range), ret_range)
+	}
+
+	/// points a mismatch at the return type annotation that set the expected type.
+	#[inline]
+	pub fn expected_by_return(message: Message, ret_range: Option<Range>) -> Message {
+		message.label_if_some(ret_range, "expected because of this return type")
 	}
 
 	#[inline]
 	pub fn args_mismatch(expected: usize, found: usize, range: Range) -> Message {
 		if expected > 1 {
-			error_type!("expected {} args, found {}", expected, found).range(range)
+			error_type!("expected {} args, found {}", expected, found).code(102).range(range)
 		} else {
-			error_type!("expected {} arg, found {}", expected, found).range(range)
+			error_type!("expected {} arg, found {}", expected, found).code(102).range(range)
 		}
 	}
 
 	#[inline]
 	pub fn wrong_arg_count(expected: usize, found: usize, range: Range) -> Message {
 		if expected == 0 {
-			error_type!("expected no args, found {}", found).range(range)
+			error_type!("expected no args, found {}", found).code(102).range(range)
 		} else if expected == 1 {
-			error_type!("expected one arg, found {}", found).range(range)
+			error_type!("expected one arg, found {}", found).code(102).range(range)
 		} else {
-			error_type!("expected {} args, found {}", expected, found).range(range)
+			error_type!("expected {} args, found {}", expected, found).code(102).range(range)
 		}
 	}
 	#[inline]
@@ -116,199 +141,232 @@ impl<'tce> SyntaxErr<'tce> {
 			1 => format!("expected a single argument, but found {found}"),
 			n => format!("expected {n} arguments, but found {found}"),
 		};
-		error_type!("{message}").range_if_some(range)
+		error_type!("{message}").code(102).range_if_some(range)
 	}
 
 	#[inline]
 	pub fn not_found_value(name: &'tce str, range: Range) -> Message {
-		error_type!("value '{}' not found", name).range(range)
+		error_type!("value '{}' not found", name).code(103).range(range)
 	}
 
 	#[inline]
 	pub fn bounds_error(value: String, found: String, range: Range) -> Message {
-		error_type!("'{}' out of bounds, expected '{}'", value, found).range(range)
+		error_type!("'{}' out of bounds, expected '{}'", value, found).code(104).range(range)
 	}
 
 	#[inline]
 	pub fn required_type_notation(range: Range) -> Message {
-		error_type!("required type notation, cannot infer type").range(range)
+		error_type!("required type notation, cannot infer type").code(105).range(range)
 	}
 
 	#[inline]
 	pub fn cannot_infer_type(range: Range) -> Message {
-		error_type!("cannot infer type").range(range)
+		error_type!("cannot infer type").code(105).range(range)
 	}
 	#[inline]
 	pub fn value_expected(value: String, range: Range) -> Message {
-		error_type!("expected '{}', found unit", value).range(range)
+		error_type!("expected '{}', found unit", value).code(106).range(range)
 	}
 
 	#[inline]
 	pub fn unexpected_value(value: String, range: Range) -> Message {
-		error_type!("no expected value, found '{}'", value).range(range)
+		error_type!("no expected value, found '{}'", value).code(107).range(range)
 	}
 
 	#[inline]
 	pub fn return_outside_fn(range: Range) -> Message {
-		error_type!("cannot return outside of a fn").range(range)
+		error_type!("cannot return outside of a fn").code(108).range(range)
 	}
 
 	#[inline]
 	pub fn invalid_float(range: Range) -> Message {
 		// Self::InvalidFloat { range }.into()
-		error_type!("floating-point number out of range").range(range)
+		error_type!("floating-point number out of range").code(109).range(range)
 	}
 
 	#[inline]
 	pub fn number_too_large(range: Range) -> Message {
 		// Self::NumberTooLarge { range }.into()
-		error_type!("unsupport number, out of range").range(range)
+		error_type!("unsupport number, out of range").code(110).range(range)
 	}
 
 	#[inline]
 	pub fn expected_number(range: Range) -> Message {
-		error_type!("expected number").range(range)
+		error_type!("expected number").code(111).range(range)
 	}
 
 	#[inline]
 	pub fn return_not_in_fn_scope(range: Range) -> Message {
-		error_type!("cannot return outside of a fn").range(range)
+		error_type!("cannot return outside of a fn").code(108).range(range)
 	}
 	#[inline]
 	pub fn not_found_module(name: &'tce str, range: Range) -> Message {
-		error_type!("module '{}' not found", name).range(range)
+		error_type!("module '{}' not found", name).code(301).range(range)
 	}
 
 	#[inline]
 	pub fn cannot_dereference(type_name: String, range: Range) -> Message {
-		error_type!("cannot dereference '{}'", type_name).range(range)
+		error_type!("cannot dereference '{}'", type_name).code(112).range(range)
 	}
 	#[inline]
 	pub fn not_a_fn(found: String, range: Range) -> Message {
-		error_type!("expected a fn, found '{}'", found).range(range)
+		error_type!("expected a fn, found '{}'", found).code(113).range(range)
 	}
 
 	#[inline]
 	pub fn borrow_conflict(range: Range) -> Message {
-		error_type!("mutable and immutable borrows conflict").range(range)
+		error_type!("mutable and immutable borrows conflict").code(202).range(range)
 	}
 
 	#[inline]
 	pub fn double_mut_borrow(range: Range) -> Message {
-		error_type!("already mutably borrowed").range(range)
+		error_type!("already mutably borrowed").code(203).range(range)
 	}
 
 	#[inline]
 	pub fn invalid_borrow(range: Range) -> Message {
-		error_type!("invalid borrow, value already freed").range(range)
+		error_type!("invalid borrow, value already freed").code(204).range(range)
 	}
 
 	#[inline]
 	pub fn borrowed_value_dropped(range: Range) -> Message {
-		error_type!("borrowed value was dropped before borrow ended").range(range)
+		error_type!("borrowed value was dropped before borrow ended").code(204).range(range)
 	}
 
 	#[inline]
 	pub fn cannot_borrow_as_mutable(name: &str, range: Range) -> Message {
-		error_type!("cannot borrow as mutable '{}'", name).range(range)
+		error_type!("cannot borrow as mutable '{}'", name).code(205).range(range)
 	}
 
 	#[inline]
 	pub fn cannot_borrow_as_mutable_more_than_once(name: &str, range: Range) -> Message {
-		error_type!("cannot borrow as mutable more than once '{}'", name).range(range)
+		error_type!("cannot borrow as mutable more than once '{}'", name).code(203).range(range)
 	}
 	#[inline]
 	pub fn connot_return_local_rerefence(range: Range) -> Message {
-		error_type!("cannot return a reference to a scoped value").range(range)
+		error_type!("cannot return a reference to a scoped value").code(206).range(range)
 	}
 	#[inline]
 	pub fn unsupported_operator(left: String, right: String, operator: &'tce Operator) -> Message {
 		error_type!("cannot {} '{}' to '{}'", operator.display(), left, right)
+			.code(122)
 			.range(operator.get_range())
 	}
 
 	#[inline]
 	pub fn redefine_fn_in_same_scope(name: &str, range: Range) -> Message {
-		error_type!("function '{}' is already defined in this scope", name).range(range)
+		error_type!("function '{}' is already defined in this scope", name).code(114).range(range)
 	}
 
 	#[inline]
 	pub fn return_local_borrow(range: Range) -> Message {
-		error_type!("cannot return a local borrow").range(range)
+		error_type!("cannot return a local borrow").code(206).range(range)
 	}
 
 	#[inline]
 	pub fn borrow_expected(range: Range) -> Message {
-		error_type!("consider adding a borrow").range(range)
+		error_type!("consider adding a borrow").code(207).range(range)
 	}
 
 	#[inline]
 	pub fn cannot_assign_immutable(name: &str, range: Range) -> Message {
-		error_type!("cannot assign immutable '{}'", name).range(range)
+		error_type!("cannot assign immutable '{}'", name).code(201).range(range)
 	}
 
 	#[inline]
 	pub fn not_found_type(name: &'tce str, range: Range) -> Message {
-		error_type!("type '{}' not found in current scope", name).range(range)
+		error_type!("type '{}' not found in current scope", name).code(115).range(range)
 	}
 
 	#[inline]
 	pub fn expect_instaced_type(found: String, range: Range) -> Message {
-		error_type!("expected `struct` or `enum`, found '{}'", found).range(range)
+		error_type!("expected `struct` or `enum`, found '{}'", found).code(116).range(range)
 	}
 
 	#[inline]
 	pub fn not_found_field(name: &'tce str, range: Range) -> Message {
-		error_type!("field '{}' not found", name).range(range)
+		error_type!("field '{}' not found", name).code(117).range(range)
 	}
 
 	#[inline]
 	pub fn not_impl(found: String, range: Range) -> Message {
-		error_type!("'{}' is not implemented", found).range(range)
+		error_type!("'{}' is not implemented", found).code(118).range(range)
 	}
 
 	#[inline]
 	pub fn not_found_method_named(name: String, found: String, range: Range) -> Message {
-		error_type!("'{}' has no method named '{}'", found, name).range(range)
+		error_type!("'{}' has no method named '{}'", found, name).code(119).range(range)
 	}
 	#[inline]
 	pub fn not_found_associate_field(name: String, found: String, range: Range) -> Message {
-		error_type!("'{}' has no associated field named '{}'", found, name).range(range)
+		error_type!("'{}' has no associated field named '{}'", found, name).code(120).range(range)
 	}
 
 	#[inline]
 	pub fn left_hand_cannot_be_assigned(range: Range) -> Message {
-		error_type!("left-hand side can't be assigned").range(range)
+		error_type!("left-hand side can't be assigned").code(121).range(range)
 	}
 
 	// module errors
 	#[inline]
 	pub fn cannot_reassign_module(range: Range) -> Message {
-		error_type!("cannot reassign module").range(range)
+		error_type!("cannot reassign module").code(302).range(range)
 	}
 
 	#[inline]
 	pub fn main_in_non_main_module(range: Range, pathname: String) -> Message {
-		error_type!("non-entry module '{}' contains 'fn main'", pathname).range(range)
+		error_type!("non-entry module '{}' contains 'fn main'", pathname).code(303).range(range)
 	}
 
 	#[inline]
 	pub fn missing_main_in_entry_module(range: Range, pathname: String) -> Message {
-		error_type!("entry module '{}' must contain 'fn main'", pathname).range(range)
+		error_type!("entry module '{}' must contain 'fn main'", pathname).code(304).range(range)
 	}
 
 	#[inline]
 	pub fn type_annotation_not_allowed_for_module(range: Range) -> Message {
-		error_type!("type annotation not allowed for module").range(range)
+		error_type!("type annotation not allowed for module").code(305).range(range)
 	}
 
 	#[inline]
 	pub fn not_found_pub_item(name: String, range: Range) -> Message {
-		error_type!("pub item '{}' not found", name).range(range)
+		error_type!("pub item '{}' not found", name).code(306).range(range)
 	}
 
 	#[inline]
 	pub fn cannot_return_local_reference(range: Range) -> Message {
-		error_type!("cannot return a local reference").range(range)
+		error_type!("cannot return a local reference").code(206).range(range)
+	}
+
+	/// suggests a similar name, e.g. "did you mean 'count'?"
+	#[inline]
+	pub fn did_you_mean(message: Message, similar: Option<&str>) -> Message {
+		match similar {
+			Some(similar) => message.note(note!("did you mean '{}'?", similar)),
+			None => message,
+		}
+	}
+
+	#[inline]
+	pub fn not_pub_item(message: Message, name: &str) -> Message {
+		message
+			.note(note!("'{}' exists but is not pub, mark it `pub` to use it from other modules", name))
+	}
+
+	#[inline]
+	pub fn unknown_attribute(name: &'tce str, range: Range) -> Message {
+		let message = error_type!("unknown attribute '{}'", name).code(401).range(range);
+		message.note(note!("expected 'allow', 'warn', 'deny' or 'test'"))
+	}
+
+	#[inline]
+	pub fn invalid_test_fn(name: &str, reason: &str, range: Range) -> Message {
+		let message = error_type!("invalid test function '{}'", name).code(403).range(range);
+		message.note(note!("{}", reason))
+	}
+
+	#[inline]
+	pub fn unknown_lint(name: &'tce str, range: Range) -> Message {
+		error_type!("unknown lint '{}'", name).code(402).range(range)
 	}
 }
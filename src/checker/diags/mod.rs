@@ -1,6 +1,5 @@
 mod errs;
+pub mod suggest;
 mod warns;
 pub use errs::SyntaxErr;
-
-#[allow(unused_imports)]
 pub use warns::TypeCheckWarn;
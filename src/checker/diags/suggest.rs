@@ -0,0 +1,38 @@
+/// edit distance where a swap of two adjacent chars counts as one edit, e.g. `cuont` → `count`.
+pub fn edit_distance(left: &str, right: &str) -> usize {
+	let (left, right): (Vec<char>, Vec<char>) = (left.chars().collect(), right.chars().collect());
+	// distances[i][j] is the distance between the first i chars of left and the first j of right
+	let mut distances = vec![vec![0; right.len() + 1]; left.len() + 1];
+	for (i, row) in distances.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	distances[0] = (0..=right.len()).collect();
+	for i in 1..=left.len() {
+		for j in 1..=right.len() {
+			let cost = usize::from(left[i - 1] != right[j - 1]);
+			let mut distance =
+				(distances[i - 1][j - 1] + cost).min(distances[i - 1][j] + 1).min(distances[i][j - 1] + 1);
+			if i > 1 && j > 1 && left[i - 1] == right[j - 2] && left[i - 2] == right[j - 1] {
+				distance = distance.min(distances[i - 2][j - 2] + 1);
+			}
+			distances[i][j] = distance;
+		}
+	}
+	distances[left.len()][right.len()]
+}
+
+/// the candidate closest to `name`, if it's close enough to be a typo.
+pub fn similar_name<'a>(
+	name: &str,
+	candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+	// one edit for every three chars
+	let max_distance = (name.chars().count() / 3).max(1);
+	candidates
+		.into_iter()
+		.filter(|candidate| *candidate != name)
+		.map(|candidate| (edit_distance(name, candidate), candidate))
+		.filter(|(distance, _)| *distance <= max_distance)
+		.min()
+		.map(|(_, candidate)| candidate)
+}
@@ -1,33 +1,62 @@
-use crate::range::Range;
+use crate::{lints::Lint, message::Message, note, range::Range};
 
 #[derive(Debug, Clone)]
 pub enum TypeCheckWarn {
 	Unused(String, Range),
-	UnusedBorrow { range: Range }, // Warn
-	Ureachable { range: Range },   // Warn
+	UnusedBorrow { range: Range },                            // Warn
+	Ureachable { range: Range },                              // Warn
+	Shadowed { name: String, range: Range, previous: Range }, // Allow
 }
 
-// impl TypeCheckWarn {
-// 	pub fn unreachable(range: Range) -> Diag {
-// 		Self::Ureachable { range }.into()
-// 	}
-// }
+impl TypeCheckWarn {
+	pub fn unused(name: &str, range: Range) -> Self {
+		Self::Unused(name.to_string(), range)
+	}
 
-// impl From<TypeCheckWarn> for diag::Diag {
-// 	fn from(warn: TypeCheckWarn) -> Self {
-// 		match warn {
-// 			TypeCheckWarn::UnusedBorrow { range } => {
-// 				let text = "value borrowed but never used.".to_string();
-// 				diag::Diag::new(Severity::Warn, text, range)
-// 			}
-// 			TypeCheckWarn::Unused(name, range) => {
-// 				let text = format!("unused value '{}'", name);
-// 				diag::Diag::new(Severity::Warn, text, range)
-// 			}
-// 			TypeCheckWarn::Ureachable { range } => {
-// 				let text = "unreachable code".to_string();
-// 				diag::Diag::new(Severity::Warn, text, range).with_note("consider removing it".to_string())
-// 			}
-// 		}
-// 	}
-// }
+	pub fn unused_borrow(range: Range) -> Self {
+		Self::UnusedBorrow { range }
+	}
+
+	pub fn unreachable(range: Range) -> Self {
+		Self::Ureachable { range }
+	}
+
+	pub fn shadowed(name: &str, range: Range, previous: Range) -> Self {
+		Self::Shadowed { name: name.to_string(), range, previous }
+	}
+
+	pub fn lint(&self) -> Lint {
+		match self {
+			TypeCheckWarn::Unused(..) => Lint::UnusedVariable,
+			TypeCheckWarn::UnusedBorrow { .. } => Lint::UnusedBorrow,
+			TypeCheckWarn::Ureachable { .. } => Lint::UnreachableCode,
+			TypeCheckWarn::Shadowed { .. } => Lint::ShadowedBinding,
+		}
+	}
+}
+
+impl From<TypeCheckWarn> for Message {
+	fn from(warn: TypeCheckWarn) -> Self {
+		match warn {
+			TypeCheckWarn::UnusedBorrow { range } => {
+				let message = Message::warning_type("value borrowed but never used").code(702);
+				message.range(range)
+			}
+			TypeCheckWarn::Unused(name, range) => {
+				let message = Message::warning_type(format!("unused value '{}'", name)).code(701);
+				message
+					.range(range)
+					.note(note!("if this is intentional, prefix it with an underscore: '_{}'", name))
+			}
+			TypeCheckWarn::Ureachable { range } => {
+				let message = Message::warning_type("unreachable code").code(703);
+				message.range(range).note(note!("consider removing it"))
+			}
+			TypeCheckWarn::Shadowed { name, range, previous } => {
+				let message =
+					Message::warning_type(format!("'{}' shadows an earlier binding", name)).code(704);
+				message.range(range).label(previous, "previous binding here")
+			}
+		}
+	}
+}
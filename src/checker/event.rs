@@ -13,6 +13,24 @@ impl Checker<'_> {
 		self.ctx.event.add_multi_type(event_id, type_ids);
 	}
 
+	/// links a use of a name to where it's declared.
+	pub fn register_definition(&mut self, range: Range, declaration: EventId) {
+		let event_id = EventId::new(self.ctx.mod_id, range);
+		self.ctx.event.add_definition(event_id, declaration);
+	}
+
+	/// declares a member of a struct or a pub item of a mod, e.g. the field `x` of `Point`.
+	pub fn register_declaration(&mut self, owner: TypeId, name: impl Into<String>, range: Range) {
+		let event_id = EventId::new(self.ctx.mod_id, range);
+		self.ctx.event.add_declaration(owner, name.into(), event_id);
+	}
+
+	pub fn register_member_use(&mut self, owner: TypeId, name: &str, range: Range) {
+		if let Some(declaration) = self.ctx.event.lookup_declaration(owner, name) {
+			self.register_definition(range, declaration);
+		}
+	}
+
 	pub fn register_drop(&mut self, event_id: EventId, drop: String) {
 		self.ctx.event.add_drop(event_id, drop);
 	}
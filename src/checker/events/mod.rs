@@ -7,17 +7,26 @@ impl EventId {
 	pub fn new(mod_id: ModId, range: Range) -> Self {
 		Self(mod_id, range)
 	}
+
+	pub fn range(&self) -> Range {
+		self.1
+	}
 }
 
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::checker::types::TypeId;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Event {
 	types: FxHashMap<EventId, TypeId>,
 	multi_types: FxHashMap<EventId, Vec<TypeId>>,
 	drops: FxHashMap<EventId, FxHashSet<String>>,
+	/// where each used name is declared, e.g. a use of `x` → `let x`
+	definitions: FxHashMap<EventId, EventId>,
+	/// declarations of struct fields and methods, and of the pub items of a mod.
+	/// the empty name is the struct itself
+	declarations: FxHashMap<(TypeId, String), EventId>,
 }
 
 impl Event {
@@ -25,7 +34,9 @@ impl Event {
 		let types = FxHashMap::default();
 		let multi_types = FxHashMap::default();
 		let drops = FxHashMap::default();
-		Event { types, drops, multi_types }
+		let definitions = FxHashMap::default();
+		let declarations = FxHashMap::default();
+		Event { types, drops, multi_types, definitions, declarations }
 	}
 
 	pub fn add_type(&mut self, event_id: EventId, type_id: TypeId) {
@@ -48,6 +59,34 @@ impl Event {
 		self.types.get(&event_id).cloned()
 	}
 
+	pub fn add_definition(&mut self, event_id: EventId, definition: EventId) {
+		self.definitions.insert(event_id, definition);
+	}
+
+	pub fn add_declaration(&mut self, owner: TypeId, name: String, declaration: EventId) {
+		self.declarations.insert((owner, name), declaration);
+	}
+
+	pub fn lookup_declaration(&self, owner: TypeId, name: &str) -> Option<EventId> {
+		self.declarations.get(&(owner, name.to_owned())).copied()
+	}
+
+	/// the type of the innermost range around an offset, e.g. `x` rather than `x + 1`.
+	pub fn lookup_type_at(&self, mod_id: ModId, offset: usize) -> Option<(Range, TypeId)> {
+		let found = self.types.iter().filter(|(event_id, _)| event_id.0 == mod_id);
+		let found = found.filter(|(event_id, _)| event_id.1.contains(offset));
+		let found =
+			found.min_by_key(|(event_id, _)| (event_id.1.end - event_id.1.start, event_id.1.start));
+		found.map(|(event_id, type_id)| (event_id.1, *type_id))
+	}
+
+	/// the declaration of the name used at an offset.
+	pub fn lookup_definition_at(&self, mod_id: ModId, offset: usize) -> Option<EventId> {
+		let found = self.definitions.iter().filter(|(event_id, _)| event_id.0 == mod_id);
+		let found = found.filter(|(event_id, _)| event_id.1.contains(offset));
+		found.min_by_key(|(event_id, _)| event_id.1.end - event_id.1.start).map(|(_, found)| *found)
+	}
+
 	pub fn lookup_drops(&self, event_id: EventId) -> Option<&FxHashSet<String>> {
 		self.drops.get(&event_id)
 	}
@@ -11,8 +11,8 @@ mod comptime;
 pub mod context;
 pub mod events;
 pub mod types;
-use context::Context;
-use diags::SyntaxErr;
+use context::{scope::ScopeKind, Context};
+use diags::{SyntaxErr, TypeCheckWarn};
 use typed_value::TypedValue;
 use types::{BorrowType, Type, TypeId};
 mod check_assign_expr;
@@ -39,7 +39,7 @@ mod check_ret_stmt;
 mod check_struct_init_expr;
 mod check_type_def_stmt;
 mod check_while_stmt;
-mod diags;
+pub mod diags;
 mod equal_type;
 mod event;
 mod infer;
@@ -85,27 +85,88 @@ impl<'ckr> Checker<'ckr> {
 		Self { ctx, loader }
 	}
 
+	/// checks the program, reporting every error found.
 	pub fn check(&mut self, mod_id: ModId) {
+		self.check_messages(mod_id);
+		self.loader.report_messages();
+	}
+
+	/// checks the program, recording every error and warning in the loader.
+	pub fn check_messages(&mut self, mod_id: ModId) {
+		self.ctx.lints = self.loader.lints().clone();
 		if let Err(message) = self.check_program(mod_id) {
 			let mod_id = self.ctx.mod_id;
-			message.mod_id(mod_id).report(self.loader);
+			self.loader.add_message(message.mod_id(mod_id));
+		}
+		for warning in self.ctx.take_warnings() {
+			self.loader.add_message(warning);
 		}
 	}
 
 	pub fn check_program(&mut self, mod_id: ModId) -> CheckResult {
 		self.ctx.add_entry_mod(mod_id);
-		let mut ast = self.loader.lookup_mod_result(mod_id).cloned().unwrap_or_else(|message| {
-			message.report(self.loader);
-		});
-		for stmt in ast.stmts.iter_mut() {
-			self.check_stmt(stmt)?;
-		}
+		let mut ast = self.loader.lookup_mod_result(mod_id).cloned()?;
+		self.check_mod_stmts(mod_id, &mut ast.stmts);
 		Ok(None)
 	}
 
+	/// checks the top level statements of a mod, a failing one is recorded and
+	/// checking moves on to the next.
+	pub(crate) fn check_mod_stmts(&mut self, mod_id: ModId, stmts: &mut [ast::Stmt]) {
+		for stmt in stmts.iter_mut() {
+			let scope_depth = self.ctx.scopes.len();
+			if let Err(message) = self.check_stmt(stmt) {
+				let mod_id = message.mod_id.unwrap_or(mod_id);
+				self.loader.add_message(message.mod_id(mod_id));
+				self.ctx.exit_scopes_to(scope_depth);
+			}
+		}
+	}
+
+	/// checks the statements of a block, an error is recorded in the loader and
+	/// the statements after it are still checked.
+	pub(crate) fn check_block_stmts(&mut self, stmts: &mut [ast::Stmt]) -> Option<TypedValue> {
+		let mut last = None;
+		for stmt in stmts.iter_mut() {
+			let scope_depth = self.ctx.scopes.len();
+			last = match self.check_stmt(stmt) {
+				Ok(value) => value,
+				Err(message) => {
+					let mod_id = message.mod_id.unwrap_or(self.ctx.mod_id);
+					self.loader.add_message(message.mod_id(mod_id));
+					self.ctx.exit_scopes_to(scope_depth);
+					None
+				}
+			};
+		}
+		last
+	}
+
+	/// checks statements as the body of a function without a return type,
+	/// giving the type of the last one, e.g. what a `lemon repl` input evaluates to.
+	pub(crate) fn check_body_stmts(
+		&mut self,
+		stmts: &mut [ast::Stmt],
+	) -> MessageResult<Option<TypeId>> {
+		let scope_depth = self.ctx.scopes.len();
+		self.ctx.enter_scope(ScopeKind::function(TypeId::VOID, None));
+		let mut last = None;
+		for stmt in stmts.iter_mut() {
+			match self.check_stmt(stmt) {
+				Ok(value) => last = value,
+				Err(message) => {
+					self.ctx.exit_scopes_to(scope_depth);
+					return Err(message);
+				}
+			}
+		}
+		self.ctx.exit_scope();
+		Ok(last.map(|value| value.type_id))
+	}
+
 	pub(crate) fn check_stmt(&mut self, stmt: &mut ast::Stmt) -> CheckResult {
 		match stmt {
-			ast::Stmt::Expr(expr) => self.check_expr(expr),
+			ast::Stmt::Expr(expr) => self.check_expr_stmt(expr),
 			ast::Stmt::Let(let_stmt) => self.check_let_stmt(let_stmt),
 			ast::Stmt::Fn(fn_stmt) => self.check_fn_stmt(fn_stmt),
 			ast::Stmt::While(while_stmt) => self.check_while_stmt(while_stmt),
@@ -121,6 +182,13 @@ impl<'ckr> Checker<'ckr> {
 		}
 	}
 
+	fn check_expr_stmt(&mut self, expr: &mut ast::Expr) -> CheckResult {
+		if let ast::Expr::Borrow(borrow_expr) = expr {
+			self.ctx.add_warning(TypeCheckWarn::unused_borrow(borrow_expr.get_range()));
+		}
+		self.check_expr(expr)
+	}
+
 	fn lookup_stored_type(&self, type_id: TypeId) -> &Type {
 		match self.ctx.type_store.lookup_type(type_id) {
 			Some(type_value) => type_value,
@@ -218,11 +286,11 @@ impl<'ckr> Checker<'ckr> {
 		Ok(())
 	}
 
-	pub fn unwrap_typed_value(&mut self, value: Option<TypedValue>, range: Range) -> TypedValue {
-		if let Some(value) = value {
-			return value;
-		}
-		let message = SyntaxErr::cannot_infer_type(range);
-		message.report(self.loader);
+	pub fn unwrap_typed_value(
+		&self,
+		value: Option<TypedValue>,
+		range: Range,
+	) -> MessageResult<TypedValue> {
+		value.ok_or_else(|| SyntaxErr::cannot_infer_type(range))
 	}
 }
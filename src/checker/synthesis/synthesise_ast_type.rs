@@ -2,6 +2,7 @@ use crate::{
 	ast::{self, AstType},
 	checker::{
 		context::Context,
+		diags::{suggest, SyntaxErr},
 		types::{BorrowType, FnType, TypeId},
 	},
 	error_type,
@@ -19,7 +20,7 @@ pub fn synthesise_ast_type(ast_type: &ast::AstType, ctx: &mut Context) -> Messag
 		AstType::Fn(fn_type) => synthesise_fn_type(fn_type, ctx),
 		AstType::Borrow(borrow) => synthesise_borrow_type(borrow, ctx),
 		AstType::Ident(ident) => synthesise_ident_type(ident, ctx),
-		_ => todo!("code {:?}", ast_type),
+		_ => Err(SyntaxErr::unsupported("this type", ast_type.get_range())),
 	}
 }
 
@@ -35,11 +36,16 @@ fn synthesise_ident_type(ident: &ast::IdentType, ctx: &mut Context) -> MessageRe
 		return Ok(*infer_id);
 	}
 
-	if let Some(type_id) = ctx.type_store.lookup_type_definition(ident.lexeme()) {
+	if let Some(type_id) = ctx.lookup_type_definition(ident.lexeme()) {
 		return Ok(*type_id);
 	}
 
-	todo!("not found type '{}'", ident.lexeme())
+	let similar =
+		suggest::similar_name(ident.lexeme(), ctx.type_store.type_definition_names(ctx.mod_id));
+	Err(SyntaxErr::did_you_mean(
+		SyntaxErr::not_found_type(ident.lexeme(), ident.get_range()),
+		similar,
+	))
 }
 
 fn synthesise_number_type(number: &ast::NumberType, ctx: &mut Context) -> MessageResult<TypeId> {
@@ -2,6 +2,7 @@ use crate::{
 	ast,
 	checker::{
 		context::Context,
+		diags::SyntaxErr,
 		types::{NumRange, TypeId},
 	},
 	message::MessageResult,
@@ -16,7 +17,7 @@ pub(crate) fn synthesise_literal(
 		ast::Literal::String(string) => Ok(TypeId::STR),
 		ast::Literal::Bool(bool) => Ok(TypeId::BOOL),
 		ast::Literal::Char(char) => Ok(TypeId::CHAR),
-		ast::Literal::Null(null) => todo!(),
+		ast::Literal::Null(null) => Err(SyntaxErr::unsupported("null", null.get_range())),
 	}
 }
 
@@ -24,13 +25,16 @@ fn synthesise_number_literal(
 	number: &ast::NumberLiteral,
 	ctx: &mut Context,
 ) -> MessageResult<TypeId> {
+	let range = number.get_range();
 	if number.as_dot() {
-		let bits = synthesise_float_bits(number.text.as_str()).unwrap();
+		let bits =
+			synthesise_float_bits(number.text.as_str()).ok_or_else(|| SyntaxErr::invalid_float(range))?;
 		let float_type = NumRange::new(bits, true);
 		let type_id = ctx.type_store.add_type(float_type.into());
 		return Ok(type_id);
 	}
-	let bits = synthesise_number_bits(&number.text).unwrap();
+	let bits = synthesise_number_bits(&number.text, number.base)
+		.ok_or_else(|| SyntaxErr::number_too_large(range))?;
 	let number = NumRange::new(bits, false);
 	let type_id = ctx.type_store.add_type(number.into());
 	Ok(type_id)
@@ -49,8 +53,8 @@ fn synthesise_float_bits(text: &str) -> Option<u8> {
 	None
 }
 // todo:  improve
-fn synthesise_number_bits(text: &str) -> Option<u8> {
-	let value: u128 = match text.parse::<u128>() {
+fn synthesise_number_bits(text: &str, base: u8) -> Option<u8> {
+	let value: u128 = match u128::from_str_radix(text, base.into()) {
 		Ok(value) => value,
 		Err(_) => return None,
 	};
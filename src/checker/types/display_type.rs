@@ -50,8 +50,9 @@ impl Number {
 
 impl StructType {
 	pub fn display_type(&self, text: &mut String, type_store: &TypeStore, omit: bool) {
+		// ir types name structs by their symbol
 		if omit {
-			*text += &self.name;
+			*text += &self.symbol;
 			return;
 		}
 		*text += "struct ";
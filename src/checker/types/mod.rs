@@ -309,6 +309,8 @@ impl ConstType {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructType {
 	pub name: String,
+	// the name in the ir, e.g. `mod1.Point`
+	pub symbol: String,
 	// hashmap? name -> FieldType
 	pub fields: FxHashMap<String, FieldType>,
 	// hasmap? name -> MethodType
@@ -321,6 +323,7 @@ pub struct StructType {
 impl Hash for StructType {
 	fn hash<H: Hasher>(&self, state: &mut H) {
 		self.name.hash(state);
+		self.symbol.hash(state);
 		// ignore internal hash... is it ok?
 		for (key, value) in &self.fields {
 			key.hash(state);
@@ -334,7 +337,8 @@ impl StructType {
 		let associated = FxHashMap::default();
 		let fields = FxHashMap::default();
 		let fns = FxHashMap::default();
-		Self { name, fields, fns, associated, implemeted: false, mutable: false }
+		let symbol = name.clone();
+		Self { name, symbol, fields, fns, associated, implemeted: false, mutable: false }
 	}
 
 	pub fn has_implemented(&self) -> bool {
@@ -396,6 +400,15 @@ impl StructType {
 		self.get_field(name).map(|field| field.type_id)
 			.or_else(|| self.get_fn(name).copied())
 	}
+
+	pub fn field_names(&self) -> impl Iterator<Item = &str> {
+		self.fields.keys().map(String::as_str)
+	}
+
+	/// names of the fields and methods.
+	pub fn member_names(&self) -> impl Iterator<Item = &str> {
+		self.field_names().chain(self.fns.keys().map(String::as_str))
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
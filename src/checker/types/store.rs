@@ -4,7 +4,7 @@ use rustc_hash::{FxHashMap, FxHasher};
 
 use crate::loader::ModId;
 
-use super::{type_id::TypeId, InferType, Number, Type};
+use super::{type_id::TypeId, InferType, ModuleType, Number, Type};
 
 // #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 // pub enum LookUpGeneric {
@@ -13,13 +13,14 @@ use super::{type_id::TypeId, InferType, Number, Type};
 
 // pub type LookUpGenericMap = FxHashMap<TypeId, LookUpGeneric>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TypeStore {
 	/// Contains all of the types. Indexed by [`TypeId`]
 	types: Vec<Type>,
 
 	// lookup_generic_map: FxHashMap<TypeId, LookUpGenericMap>,
-	type_definitions: FxHashMap<String, TypeId>,
+	// types are scoped by the mod that defines them
+	type_definitions: FxHashMap<(ModId, String), TypeId>,
 	generics: FxHashMap<String, TypeId>,
 	// is good?
 	cache: FxHashMap<u64, TypeId>,
@@ -71,12 +72,17 @@ impl TypeStore {
 		self.generics.get(id)
 	}
 
-	pub fn lookup_type_definition(&self, name: &str) -> Option<&TypeId> {
-		self.type_definitions.get(name)
+	pub fn lookup_type_definition(&self, mod_id: ModId, name: &str) -> Option<&TypeId> {
+		self.type_definitions.get(&(mod_id, name.to_owned()))
 	}
 
-	pub fn add_type_definition(&mut self, name: String, type_id: TypeId) {
-		self.type_definitions.insert(name, type_id);
+	pub fn type_definition_names(&self, mod_id: ModId) -> impl Iterator<Item = &str> {
+		let names = self.type_definitions.keys().filter(move |(found, _)| *found == mod_id);
+		names.map(|(_, name)| name.as_str())
+	}
+
+	pub fn add_type_definition(&mut self, mod_id: ModId, name: String, type_id: TypeId) {
+		self.type_definitions.insert((mod_id, name), type_id);
 	}
 
 	pub fn add_type(&mut self, ty: Type) -> TypeId {
@@ -132,15 +138,26 @@ impl TypeStore {
 		text
 	}
 
-	pub fn lookup_struct_name(&self, type_id: TypeId) -> Option<&str> {
+	/// the name the struct has in the ir, mangled by its mod like fns are.
+	pub fn lookup_struct_symbol(&self, type_id: TypeId) -> Option<&str> {
 		let type_value = self.lookup_type(type_id);
 		if let Some(Type::Struct(struct_type)) = type_value {
-			Some(struct_type.name.as_str())
+			Some(struct_type.symbol.as_str())
 		} else {
 			None
 		}
 	}
 
+	pub fn lookup_module_type(&self, type_id: TypeId) -> Option<&ModuleType> {
+		if type_id.is_builtin_type() {
+			return None;
+		}
+		match self.lookup_type(type_id) {
+			Some(Type::Mod(module_type)) => Some(module_type),
+			_ => None,
+		}
+	}
+
 	pub fn is_borrow(&self, type_id: TypeId) -> bool {
 		if type_id.is_builtin_type() {
 			return false;
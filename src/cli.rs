@@ -7,16 +7,103 @@ pub fn command_line() -> clap::ArgMatches {
 		.author(env!("CARGO_PKG_AUTHORS"))
 		.subcommand_required(true)
 		.arg_required_else_help(true)
-		.subcommand(Command::new("check").about("check lemon.").arg(Arg::new("file").required(false)))
+		.arg(
+			Arg::new("message-format")
+				.help("how diagnostics are printed")
+				.long("message-format")
+				.value_parser(["human", "json"])
+				.default_value("human")
+				.global(true),
+		)
+		.subcommand(
+			Command::new("check")
+				.about("check lemon.")
+				.arg(Arg::new("file").required(false))
+				.arg(update_lock_arg())
+				.args(lint_args()),
+		)
 		.subcommand(
 			Command::new("run")
-				.about("run lemon.")
-				.arg(Arg::new("file").help("path to the lemon file").required(true)),
+				.about("run lemon, in process with the jit.")
+				.arg(Arg::new("file").help("path to the lemon file").required(true))
+				.arg(
+					Arg::new("interp")
+						.help("run with the ir interpreter instead of llvm")
+						.long("interp")
+						.action(ArgAction::SetTrue),
+				)
+				.arg(verify_ir_arg())
+				.arg(
+					Arg::new("args").help("arguments passed to main, after `--`").num_args(0..).last(true),
+				),
+		)
+		.subcommand(
+			Command::new("test")
+				.about("run the @test functions of lemon.")
+				.arg(
+					Arg::new("file")
+						.help("path to the lemon file [default: every mod of the shio.toml package]")
+						.required(false),
+				)
+				.arg(Arg::new("filter").help("run only the tests whose names contain this").long("filter"))
+				.arg(
+					Arg::new("interp")
+						.help("run with the ir interpreter instead of llvm")
+						.long("interp")
+						.action(ArgAction::SetTrue),
+				)
+				.arg(verify_ir_arg())
+				.arg(update_lock_arg())
+				.args(lint_args()),
+		)
+		.subcommand(
+			Command::new("bench")
+				.about("compile the benchmarks and time their runs.")
+				.arg(
+					Arg::new("programs")
+						.help("benchmarks to run, relative to the dir [default: every .ln in it]")
+						.num_args(0..),
+				)
+				.arg(Arg::new("dir").help("dir of the benchmarks").long("dir").default_value("bench"))
+				.arg(
+					Arg::new("opt-level")
+						.help("optimization level")
+						.short('O')
+						.long("opt-level")
+						.value_parser(["0", "1", "2", "3"])
+						.default_value("2"),
+				)
+				.arg(
+					Arg::new("runs")
+						.help("timed runs of each benchmark, after one to warm up")
+						.short('r')
+						.long("runs")
+						.value_parser(clap::builder::RangedU64ValueParser::<usize>::new().range(1..))
+						.default_value("10"),
+				)
+				.arg(
+					Arg::new("output")
+						.help("where the json results go [default: <dir>/results.json]")
+						.short('o')
+						.long("output"),
+				),
+		)
+		.subcommand(
+			Command::new("repl").about("evaluate lemon interactively.").arg(
+				Arg::new("jit")
+					.help("evaluate with the jit instead of the ir interpreter")
+					.long("jit")
+					.action(ArgAction::SetTrue),
+			),
 		)
 		.subcommand(
 			Command::new("compile")
 				.about("compile lemon to machine code.")
-				.arg(Arg::new("file").help("path to the lemon file").required(true))
+				.arg(
+					Arg::new("file")
+						.help("path to the lemon file [default: shio.toml package]")
+						.required(false),
+				)
 				.arg(
 					Arg::new("target")
 						.help("target triple for cross-compilation [default: host target]")
@@ -28,8 +115,14 @@ pub fn command_line() -> clap::ArgMatches {
 						.help("choise linker")
 						.short('l')
 						.long("linker")
-						.value_parser(["mold", "lld", "clang"])
-						.default_value("clang"),
+						.value_parser(["mold", "lld", "clang"]),
+				)
+				.arg(
+					Arg::new("opt-level")
+						.help("optimization level [default: 2]")
+						.short('O')
+						.long("opt-level")
+						.value_parser(["0", "1", "2", "3"]),
 				)
 				.arg(
 					Arg::new("assembly")
@@ -53,10 +146,85 @@ pub fn command_line() -> clap::ArgMatches {
 						.action(ArgAction::SetTrue),
 				)
 				.arg(Arg::new("no-debug").help("disable debug").long("no-debug").action(ArgAction::SetTrue))
-				.arg(Arg::new("output").help("custom output file path").short('o').long("output")),
+				.arg(Arg::new("output").help("custom output file path").short('o').long("output"))
+				.arg(verify_ir_arg())
+				.arg(update_lock_arg())
+				.args(lint_args()),
+		)
+		.subcommand(
+			Command::new("new")
+				.about("create a new lemon package.")
+				.arg(Arg::new("name").help("package name").required(true))
+				.arg(lib_arg()),
+		)
+		.subcommand(
+			Command::new("init")
+				.about("create a lemon package in the current directory.")
+				.arg(Arg::new("name").help("package name [default: directory name]").long("name"))
+				.arg(lib_arg()),
+		)
+		.subcommand(
+			Command::new("publish").about("publish the package to a registry.").arg(
+				Arg::new("registry")
+					.help("registry directory [default: ~/.shio/remote]")
+					.short('r')
+					.long("registry"),
+			),
+		)
+		.subcommand(
+			Command::new("explain")
+				.about("explain an error code, e.g. `lemon explain E0101`.")
+				.arg(Arg::new("code").help("error code").required(true)),
+		)
+		.subcommand(
+			Command::new("fmt")
+				.about("format lemon source files.")
+				.arg(
+					Arg::new("file")
+						.help("path to the lemon file [default: every file of the shio.toml package]")
+						.required(false),
+				)
+				.arg(
+					Arg::new("check")
+						.help("list the files that are not formatted, without changing them")
+						.long("check")
+						.action(ArgAction::SetTrue),
+				),
 		)
+		.subcommand(Command::new("lsp").about("start the language server, over stdin and stdout."))
 		.subcommand(Command::new("token").arg(Arg::new("file").required(true)).hide(true))
 		.subcommand(Command::new("ast").arg(Arg::new("file").required(true)).hide(true))
 		.get_matches();
 	matches
 }
+
+fn update_lock_arg() -> Arg {
+	Arg::new("update-lock")
+		.help("accept dependency changes and rewrite shio.lock")
+		.long("update-lock")
+		.action(ArgAction::SetTrue)
+}
+
+/// debug builds of the compiler always verify.
+fn verify_ir_arg() -> Arg {
+	Arg::new("verify-ir")
+		.help("check the lemon ir before codegen")
+		.long("verify-ir")
+		.action(ArgAction::SetTrue)
+}
+
+/// `-W`, `-D` and `-A`, later flags override earlier ones.
+fn lint_args() -> [Arg; 3] {
+	let lint_arg = |name: &'static str, short: char, help: &'static str| {
+		Arg::new(name).help(help).short(short).value_name("LINT").action(ArgAction::Append)
+	};
+	[
+		lint_arg("warn", 'W', "set a lint to warn"),
+		lint_arg("deny", 'D', "set a lint to deny, failing on it"),
+		lint_arg("allow", 'A', "set a lint to allow, silencing it"),
+	]
+}
+
+fn lib_arg() -> Arg {
+	Arg::new("lib").help("create a library package").long("lib").action(ArgAction::SetTrue)
+}
@@ -1,23 +1,33 @@
-use std::{io, path::Path, time::Instant};
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	time::Instant,
+};
 
 use crate::{
+	assembler::assemble_mod,
 	builder::Builder,
-	checker::{context::Context, Checker},
+	checker::{context::Context, types::TypeStore, Checker},
 	cross::Cross,
 	disassembler::Disassembler,
 	file_system::FileSystem,
+	interp::Interp,
+	ir::IR,
 	linker::Linker,
-	llvm,
-	loader::Loader,
-	parse_mod,
-	shio::ShioConfig,
+	lint_flags, llvm, load_packages,
+	loader::{Loader, ModId},
+	parser::parse_mod,
+	report::{message_format, print_message_without_module, MessageFormat},
+	shio::{ShioBuild, ShioConfig},
 	throw_error,
 	time::format_time,
+	verifier::{verify_ir, verify_ownership},
+	warning,
 };
 
 use clap::ArgMatches;
 use console::{Style, Term};
-use inkwell::targets::FileType;
+use inkwell::{module::Module, targets::FileType, OptimizationLevel};
 use target_lexicon::HOST;
 
 pub fn execute_term(respose: io::Result<()>, text: &str) {
@@ -35,35 +45,97 @@ pub fn write_in_term(term: &Term, text: impl Into<String>, is_clear: bool) {
 	execute_term(term.write_line(text.as_str()), text.as_str());
 }
 
-pub fn compile(path_name: &str, matches: &ArgMatches) {
+pub fn compile(matches: &ArgMatches) {
+	match matches.get_one::<String>("file") {
+		Some(path_name) => {
+			let path = Path::new(path_name);
+			let shio = ShioConfig::with_defaults(path.to_path_buf());
+			let cwd = shio.loader.cwd.clone();
+			let output = PathBuf::from(generate_output_filename(path));
+			compile_package(shio, FileSystem::from_current_dir(cwd), &output, matches);
+		}
+		None => {
+			let packages = load_packages();
+			if packages.len() > 1 && matches.contains_id("output") {
+				throw_error!("'--output' can't be used with a workspace");
+			}
+			for (shio, file_system) in packages {
+				let output = shio.output_path();
+				compile_package(shio, file_system, &output, matches);
+			}
+		}
+	}
+}
+
+/// compile settings, flags take precedence over the shio.toml `[build]` section.
+struct BuildOptions {
+	target: String,
+	linker: String,
+	opt_level: OptimizationLevel,
+	/// the object, the binary is the same path without extension
+	output: PathBuf,
+}
+
+impl BuildOptions {
+	fn new(build: &ShioBuild, output: &Path, matches: &ArgMatches) -> Self {
+		let target = matches.get_one::<String>("target").cloned().or(build.target.clone());
+		let target = target.unwrap_or(HOST.to_string());
+		let linker = matches.get_one::<String>("linker").cloned().or(build.linker.clone());
+		let linker = linker.unwrap_or("clang".to_string());
+		let opt_level = match matches.get_one::<String>("opt-level") {
+			Some(level) => level.parse().ok(),
+			None => build.opt_level,
+		};
+		let opt_level = match opt_level {
+			_ if matches.get_flag("no-optimize") => OptimizationLevel::None,
+			Some(0) => OptimizationLevel::None,
+			Some(1) => OptimizationLevel::Less,
+			Some(3) => OptimizationLevel::Aggressive,
+			_ => OptimizationLevel::Default,
+		};
+		let output = match matches.get_one::<String>("output") {
+			Some(output) => PathBuf::from(output).with_extension("o"),
+			None => output.to_path_buf(),
+		};
+		Self { target, linker, opt_level, output }
+	}
+}
+
+pub fn compile_package(
+	shio: ShioConfig,
+	file_system: FileSystem,
+	output_path: &Path,
+	matches: &ArgMatches,
+) {
+	let options = BuildOptions::new(&shio.build, output_path, matches);
 	let timer = Instant::now();
-	let term = Term::stdout();
+	// stdout only carries messages in json
+	let term = match message_format() {
+		MessageFormat::Json => Term::stderr(),
+		MessageFormat::Human => Term::stdout(),
+	};
 	let style = Style::new();
-	let compile_green_text = style.green().apply_to("compiling...").bold();
+	let compile_text = format!("compiling {}...", shio.package.name);
+	let compile_green_text = style.green().apply_to(compile_text).bold();
 	write_in_term(&term, compile_green_text.to_string(), false);
 
-	let path = Path::new(path_name);
-	let shio = ShioConfig::with_defaults(path.to_path_buf());
-	let cwd = shio.loader.cwd.clone();
-	let file_system = FileSystem::from_current_dir(cwd);
 	let mut loader = Loader::new(shio, file_system);
+	loader.extend_lints(&lint_flags(matches));
 	let mod_id = loader.load_entry().unwrap_or_else(|message| message.report(&loader));
 	let mut ctx = Context::new();
 	let source = loader.lookup_source_unchecked(mod_id).clone();
 
-	parse_mod(mod_id, &mut loader);
-	// check
-	write_in_term(&term, " check...", false);
-	let mut checker = Checker::new(&mut ctx, &mut loader);
-	checker.check(mod_id);
-
-	// emit lnr
-	//
-	write_in_term(&term, " emit lnr...", true);
-	let mut ir_builder = Builder::new(&ctx.type_store, &mut ctx.event, &mut loader);
-	let ir = ir_builder.build(mod_id);
+	let ir = if source.abs_path.extension().is_some_and(|ext| ext == "lnr") {
+		// hand-written ir skips the front end
+		write_in_term(&term, " assemble lnr...", false);
+		let ir = assemble_mod(mod_id, &loader, &mut ctx.type_store);
+		ir.unwrap_or_else(|message| message.report(&loader))
+	} else {
+		build_mod(mod_id, &mut loader, &mut ctx, &term, matches)
+	};
 
 	// optimize::optimize(&mut ir);
+	verify(&ir, &ctx.type_store, &mut loader, matches);
 
 	if matches.get_flag("lnr") {
 		let disassembler = Disassembler::new(&ctx.type_store);
@@ -90,26 +162,219 @@ pub fn compile(path_name: &str, matches: &ArgMatches) {
 
 	// println!("emit object...", HOST.architecture);
 	write_in_term(&term, " emit object...", true);
-	let triple = HOST.to_string();
-	let cross = Cross::new(&triple);
+	let mut cross = Cross::new(&options.target);
+	cross.opt(options.opt_level);
 
-	let output = generate_output_filename(&source.abs_path);
-	let output_path = Path::new(&output);
-	match cross.emit(&llvm.module, FileType::Object, output_path) {
+	if let Some(out_dir) = options.output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+		fs::create_dir_all(out_dir).unwrap_or_else(|err| throw_error!("{}", err));
+	}
+	match cross.emit(&llvm.module, FileType::Object, &options.output) {
 		Ok(_) => {}
 		Err(err) => throw_error!("{}", err),
 	}
 
-	// link
-	write_in_term(&term, " linking...", true);
-	let linker = Linker::new(output_path.to_path_buf());
-	linker.link();
+	// link, a library package (no main) stops at the object
+	if ir.has_function("main") {
+		write_in_term(&term, " linking...", true);
+		let mut linker = Linker::new(options.output.clone());
+		linker.linker(&options.linker);
+		if options.target != HOST.to_string() {
+			linker.target(&options.target);
+		}
+		linker.link();
+	}
 	execute_term(term.clear_last_lines(1), "");
 
 	let text = format!(" finished in {}.", format_time(timer.elapsed(), true));
 	write_in_term(&term, text, false);
 }
 
+/// parses, checks and builds the entry mod and its imports into ir.
+fn build_mod(
+	mod_id: ModId,
+	loader: &mut Loader,
+	ctx: &mut Context,
+	term: &Term,
+	matches: &ArgMatches,
+) -> IR {
+	parse_mod(mod_id, loader);
+	loader.report_messages();
+	let update_lock = matches.get_flag("update-lock");
+	loader.check_versions().unwrap_or_else(|message| message.report(loader));
+	loader.sync_lock(update_lock).unwrap_or_else(|message| message.report(loader));
+	// check
+	write_in_term(term, " check...", false);
+	let mut checker = Checker::new(ctx, loader);
+	checker.check(mod_id);
+
+	// emit lnr
+	//
+	write_in_term(term, " emit lnr...", true);
+	let mut ir_builder = Builder::new(&ctx.type_store, &mut ctx.event, loader);
+	ir_builder.build(mod_id)
+}
+
+/// checks the ir before a backend gets it, always in debug builds of the compiler.
+/// ownership is checked on every build, it's what keeps the heap safe.
+fn verify(ir: &IR, type_store: &TypeStore, loader: &mut Loader, matches: &ArgMatches) {
+	if cfg!(debug_assertions) || matches.get_flag("verify-ir") {
+		for message in verify_ir(ir, type_store) {
+			loader.add_message(message);
+		}
+		loader.report_messages();
+	}
+	for message in verify_ownership(ir, type_store) {
+		loader.add_message(message);
+	}
+	loader.report_messages();
+}
+
+/// `lemon run`, checks and builds the file, then runs its `main` in process with the jit.
+pub fn run(matches: &ArgMatches) {
+	let path_name = matches.get_one::<String>("file").expect("file is required");
+	let path = Path::new(path_name);
+	let shio = ShioConfig::with_defaults(path.to_path_buf());
+	let file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+	let mut loader = Loader::new(shio, file_system);
+	let mod_id = loader.load_entry().unwrap_or_else(|message| message.report(&loader));
+	let mut ctx = Context::new();
+	let source = loader.lookup_source_unchecked(mod_id).clone();
+
+	parse_mod(mod_id, &mut loader);
+	loader.report_messages();
+	loader.check_versions().unwrap_or_else(|message| message.report(&loader));
+	loader.sync_lock(false).unwrap_or_else(|message| message.report(&loader));
+	let mut checker = Checker::new(&mut ctx, &mut loader);
+	checker.check(mod_id);
+
+	let mut ir_builder = Builder::new(&ctx.type_store, &mut ctx.event, &mut loader);
+	let ir = ir_builder.build(mod_id);
+	if !ir.has_function("main") {
+		throw_error!("'{}' has no main function", path_name);
+	}
+	verify(&ir, &ctx.type_store, &mut loader, matches);
+
+	if matches.get_flag("interp") {
+		std::process::exit(interpret(&ir, &ctx.type_store, &loader));
+	}
+
+	let llvm_context = inkwell::context::Context::create();
+	let llvm_module = llvm::create_module_from_source(&llvm_context, &source);
+	let mut llvm = llvm::Llvm::new(&llvm_context, llvm_module, &loader, &ctx.type_store);
+	llvm.compile_ir(&ir);
+
+	// argv[0] is the file, the rest are the args after `--`
+	let mut args = vec![path_name.as_str()];
+	args.extend(matches.get_many::<String>("args").unwrap_or_default().map(String::as_str));
+	let exit_code = run_main(&llvm.module, &args).unwrap_or_else(|err| throw_error!("{}", err));
+	std::process::exit(exit_code);
+}
+
+/// `lemon test`, builds the `@test` fns with a `main` that runs them and exits
+/// with 1 if any failed. without a file, every package is tested.
+pub fn test(matches: &ArgMatches) {
+	let exit_code = match matches.get_one::<String>("file") {
+		Some(path_name) => {
+			let shio = ShioConfig::with_defaults(PathBuf::from(path_name));
+			let cwd = shio.loader.cwd.clone();
+			test_package(shio, FileSystem::from_current_dir(cwd), false, matches)
+		}
+		None => {
+			let packages = load_packages();
+			let exit_codes: Vec<_> = packages
+				.into_iter()
+				.map(|(shio, file_system)| test_package(shio, file_system, true, matches))
+				.collect();
+			exit_codes.into_iter().max().unwrap_or(0)
+		}
+	};
+	std::process::exit(exit_code);
+}
+
+/// `all_mods` tests the mods nothing imports as well, a single file has its imports only.
+fn test_package(
+	shio: ShioConfig,
+	file_system: FileSystem,
+	all_mods: bool,
+	matches: &ArgMatches,
+) -> i32 {
+	let mut loader = Loader::new(shio, file_system);
+	loader.extend_lints(&lint_flags(matches));
+	let mod_id = loader.load_entry().unwrap_or_else(|message| message.report(&loader));
+	let mut ctx = Context::new();
+	let source = loader.lookup_source_unchecked(mod_id).clone();
+
+	parse_mod(mod_id, &mut loader);
+	let package_mods = match all_mods {
+		true => loader.load_package_mods().unwrap_or_else(|message| message.report(&loader)),
+		false => Vec::new(),
+	};
+	for package_mod in package_mods.iter().copied() {
+		if !loader.is_parsed(package_mod) {
+			parse_mod(package_mod, &mut loader);
+		}
+	}
+	loader.report_messages();
+	let update_lock = matches.get_flag("update-lock");
+	loader.check_versions().unwrap_or_else(|message| message.report(&loader));
+	loader.sync_lock(update_lock).unwrap_or_else(|message| message.report(&loader));
+	let mut checker = Checker::new(&mut ctx, &mut loader);
+	checker.check(mod_id);
+	for package_mod in package_mods {
+		checker.check_package_mod(package_mod);
+	}
+
+	let filter = matches.get_one::<String>("filter").cloned();
+	let mut ir_builder =
+		Builder::new(&ctx.type_store, &mut ctx.event, &mut loader).with_tests(filter);
+	let ir = ir_builder.build(mod_id);
+	verify(&ir, &ctx.type_store, &mut loader, matches);
+
+	if matches.get_flag("interp") {
+		return interpret(&ir, &ctx.type_store, &loader);
+	}
+	let llvm_context = inkwell::context::Context::create();
+	let llvm_module = llvm::create_module_from_source(&llvm_context, &source);
+	let mut llvm = llvm::Llvm::new(&llvm_context, llvm_module, &loader, &ctx.type_store);
+	llvm.compile_ir(&ir);
+	let args = [source.pathname.as_str()];
+	run_main(&llvm.module, &args).unwrap_or_else(|err| throw_error!("{}", err))
+}
+
+/// runs `main` without an object file or a linker, externs such as `printf`
+/// resolve against the host libc, which the process already has loaded.
+fn run_main(module: &Module, args: &[&str]) -> Result<i32, String> {
+	let engine = module.create_jit_execution_engine(OptimizationLevel::Default);
+	let engine = engine.map_err(|err| err.to_string())?;
+	let main = module.get_function("main").ok_or("no main function")?;
+	// main is built to return an i32, the jit passes it argc and argv if it takes them
+	let params = main.get_type().get_param_types();
+	let takes_args = match params.as_slice() {
+		[] => true,
+		[argc] => argc.is_int_type(),
+		[argc, argv] => argc.is_int_type() && argv.is_pointer_type(),
+		_ => false,
+	};
+	if !takes_args {
+		return Err("main must take no args, or an argc and an argv".to_owned());
+	}
+	Ok(unsafe { engine.run_function_as_main(main, args) })
+}
+
+/// runs `main` on the ir interpreter, leaked heap allocations are reported as a warning.
+fn interpret(ir: &IR, type_store: &TypeStore, loader: &Loader) -> i32 {
+	let mut interp = Interp::new(ir, type_store);
+	let exit_code = interp.run_main().unwrap_or_else(|message| message.report(loader));
+	let leaks = interp.leaks();
+	if !leaks.is_empty() {
+		let bytes: usize = leaks.iter().map(|(_, size)| size).sum();
+		let message =
+			warning!("{} heap allocations never dropped, {} bytes", leaks.len(), bytes).code(705);
+		print_message_without_module(&message);
+	}
+	exit_code
+}
+
 fn generate_output_filename(path: &Path) -> String {
 	let file_name = path.file_name().unwrap().to_str().unwrap();
 	let file_name_without_ext = file_name.split('.').next().unwrap();
@@ -1,6 +1,5 @@
 #![allow(dead_code)]
 pub(crate) mod diags;
 pub mod engine;
-pub(crate) mod frame;
 pub(crate) mod heap;
 pub(crate) mod stack;
@@ -20,6 +20,11 @@ impl<'ir> Disassembler<'ir> {
 			output.push('\n');
 		});
 
+		program.globals.iter().for_each(|global| {
+			let type_name = self.type_store.lookup_display_ir_type(global.kind);
+			output.push_str(&format!("global {}: {}\n\n", global.name, type_name));
+		});
+
 		program.functions.iter().for_each(|func| {
 			self.disassemble_function(func, output);
 			output.push('\n');
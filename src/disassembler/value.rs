@@ -14,12 +14,11 @@ impl<'ir> Disassembler<'ir> {
 
 	pub fn disassemble_basic_value(&self, ir_value: &'ir ir::IrBasicValue) -> String {
 		match &ir_value.value {
-			// .replace('\t', "\\t").replace('\r', "\\r")
-			ir::BasicValue::String(value) => format!("\"{}\"", value.replace('\n', "\\n")),
+			ir::BasicValue::String(value) => format!("\"{}\"", escape(value, '"')),
 			ir::BasicValue::Register(value) => value.to_string(),
 			ir::BasicValue::Int(value) => format!("{}", value),
 			ir::BasicValue::Float(value) => format!("{}", value),
-			ir::BasicValue::Char(value) => format!("'{}'", value),
+			ir::BasicValue::Char(value) => format!("'{}'", escape(&value.to_string(), '\'')),
 			ir::BasicValue::Bool(value) => format!("{}", value),
 			ir::BasicValue::None => {
 				throw_error!("internal 'none' found in ir, please report bug.")
@@ -27,3 +26,23 @@ impl<'ir> Disassembler<'ir> {
 		}
 	}
 }
+
+/// escapes a literal quoted by `quote`, as lemon strings do, so the assembler can read it back.
+fn escape(text: &str, quote: char) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for char in text.chars() {
+		match char {
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			'\t' => escaped.push_str("\\t"),
+			'\0' => escaped.push_str("\\0"),
+			'\\' => escaped.push_str("\\\\"),
+			char if char == quote => {
+				escaped.push('\\');
+				escaped.push(char);
+			}
+			char => escaped.push(char),
+		}
+	}
+	escaped
+}
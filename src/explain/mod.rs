@@ -0,0 +1,32 @@
+use crate::message::ErrorCode;
+
+macro_rules! explanations {
+	($($code:literal),* $(,)?) => {
+		&[$((ErrorCode($code), include_str!(concat!("codes/E0", stringify!($code), ".md")))),*]
+	};
+}
+
+/// long-form explanations of the error codes, embedded in the binary.
+const EXPLANATIONS: &[(ErrorCode, &str)] = explanations![
+	// type errors
+	101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119,
+	120, 121, 122, 123, 124, 125, //
+	// ownership errors
+	201, 202, 203, 204, 205, 206, 207, 208, 209, 210, 211, //
+	// module errors
+	301, 302, 303, 304, 305, 306, //
+	// attribute errors
+	401, 402, 403, //
+	// syntax errors
+	501, 502, 503, 504, 505, 506, //
+	// resolve errors
+	601, 602, 603, 604, 605, 606, 607, 608, 609, 610, 611, //
+	// warnings
+	701, 702, 703, 704, 705, //
+	// ir errors
+	801, 802, 803, 804, 805, 806, 807,
+];
+
+pub fn explain(code: ErrorCode) -> Option<&'static str> {
+	EXPLANATIONS.iter().find(|(found, _)| *found == code).map(|(_, text)| *text)
+}
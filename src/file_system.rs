@@ -6,6 +6,8 @@ use std::{
 	path::{Path, PathBuf},
 };
 
+use sha2::{Digest, Sha256};
+
 use crate::{error_resolve, message::MessageResult};
 
 /// Represents the file system context for loading mods and dependencies.
@@ -24,6 +26,8 @@ pub struct FileSystem {
 	pub in_memory_cache: FxHashMap<PathBuf, String>,
 	/// Custom dependency mapping (e.g., "math" → "../math")
 	pub custom_dependencies: FxHashMap<String, PathBuf>,
+	/// workspace members by package name (e.g., "math" → ./packages/math)
+	pub workspace_members: FxHashMap<String, PathBuf>,
 }
 
 impl FileSystem {
@@ -36,6 +40,7 @@ impl FileSystem {
 		let current_dir = Self::abs_and_canonical(current_dir.to_str().unwrap());
 		let in_memory_cache = FxHashMap::default();
 		let custom_dependencies = FxHashMap::default();
+		let workspace_members = FxHashMap::default();
 		Self {
 			dependency_dir,
 			cache_dir,
@@ -44,6 +49,7 @@ impl FileSystem {
 			current_dir,
 			in_memory_cache,
 			custom_dependencies,
+			workspace_members,
 		}
 	}
 
@@ -91,10 +97,37 @@ impl FileSystem {
 			let abs = Self::resolve_mod_path(dep_base, key);
 			return self.load_and_cache_file(&abs, key).map(|contents| (contents, abs));
 		}
-		let message = error_resolve!("custom dependency '{}' not found", key);
+		let message = error_resolve!("custom dependency '{}' not found", key).code(602);
 		Err(message)
 	}
 
+	/// finds the package dir of a dependency, first in the custom dependency mapping
+	/// (shio.toml local paths), then in the workspace members, then in the dependency directory.
+	/// on failure, returns every location searched.
+	pub fn find_dependency_dir(&self, name: &str) -> Result<PathBuf, Vec<PathBuf>> {
+		let mut searched = Vec::new();
+		let custom_dir = self.custom_dependencies.get(name).cloned();
+		let member_dir = self.workspace_members.get(name).cloned();
+		let dependency_dir = self.dependency_dir.join(name);
+		for dir in custom_dir.into_iter().chain(member_dir).chain(Some(dependency_dir)) {
+			if dir.join("shio.toml").is_file() {
+				return Ok(Self::to_canonical(dir));
+			}
+			searched.push(dir);
+		}
+		Err(searched)
+	}
+
+	/// maps a dependency name to its package dir, the first mapping wins.
+	pub fn add_custom_dependency(&mut self, name: String, dir: PathBuf) {
+		self.custom_dependencies.entry(name).or_insert(dir);
+	}
+
+	/// maps a workspace member's package name to its package dir.
+	pub fn add_workspace_member(&mut self, name: String, dir: PathBuf) {
+		self.workspace_members.insert(name, dir);
+	}
+
 	/// loads a remote dependency from the remote directory.
 	pub fn load_remote_mod(&mut self, relative: &str) -> MessageResult<(String, PathBuf)> {
 		let abs = Self::resolve_mod_path(&self.remote_dir, relative);
@@ -107,10 +140,71 @@ impl FileSystem {
 			let abs = Self::resolve_mod_path(workspace, relative);
 			return self.load_and_cache_file(&abs, relative).map(|contents| (contents, abs));
 		}
-		let message = error_resolve!("workspace file '{}' not found", relative);
+		let message = error_resolve!("workspace file '{}' not found", relative).code(602);
 		Err(message)
 	}
 
+	/// a dependency fetched into the dependency or remote dir, rather than a path
+	/// dependency or a workspace member that lives next to the package.
+	pub fn is_fetched_dependency(&self, dir: &Path) -> bool {
+		let fetched_dirs = [&self.dependency_dir, &self.remote_dir];
+		fetched_dirs.into_iter().any(|fetched| dir.starts_with(Self::to_canonical(fetched.clone())))
+	}
+
+	/// hashes every `.ln` source under a package dir, e.g. "sha256:3f1a...".
+	pub fn hash_sources(dir: &Path) -> MessageResult<String> {
+		let sources = Self::package_sources(dir)?;
+		let mut hasher = Sha256::new();
+		for source in sources {
+			let relative = source.strip_prefix(dir).unwrap_or(&source);
+			let contents = fs::read(&source)
+				.map_err(|_| error_resolve!("failed to read '{}'", source.display()).code(601))?;
+			hasher.update(relative.to_string_lossy().as_bytes());
+			hasher.update([0]);
+			hasher.update(&contents);
+		}
+		let hash: String = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+		Ok(format!("sha256:{}", hash))
+	}
+
+	/// every `.ln` source under a package dir, sorted.
+	pub fn package_sources(dir: &Path) -> MessageResult<Vec<PathBuf>> {
+		let mut sources = Vec::new();
+		Self::collect_sources(dir, &mut sources)
+			.map_err(|_| error_resolve!("failed to read sources of '{}'", dir.display()).code(601))?;
+		sources.sort();
+		Ok(sources)
+	}
+
+	fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> io::Result<()> {
+		for entry in fs::read_dir(dir)? {
+			let path = entry?.path();
+			if path.is_dir() {
+				Self::collect_sources(&path, sources)?;
+			} else if path.extension().is_some_and(|ext| ext == "ln") {
+				sources.push(path);
+			}
+		}
+		Ok(())
+	}
+
+	/// mods in a dir: its `.ln` files other than `file` and the dirs with a mod.ln.
+	pub fn mods_in_dir(dir: &Path, file: &Path) -> Vec<String> {
+		let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+		let mut mods = Vec::new();
+		for path in entries.flatten().map(|entry| entry.path()) {
+			let is_mod = match path.is_dir() {
+				true => path.join("mod.ln").is_file(),
+				false => path.extension().is_some_and(|ext| ext == "ln") && path != file,
+			};
+			if let Some(name) = path.file_name().and_then(|name| name.to_str()).filter(|_| is_mod) {
+				mods.push(name.to_string());
+			}
+		}
+		mods.sort();
+		mods
+	}
+
 	/// reads a file from disk and caches its contents.
 	fn load_and_cache_file(&mut self, abs: &Path, relative: &str) -> MessageResult<String> {
 		if let Some(contents) = self.in_memory_cache.get(abs) {
@@ -122,7 +216,7 @@ impl FileSystem {
 	}
 
 	fn read_file(file_path: &Path, relative: &str) -> MessageResult<String> {
-		fs::read_to_string(file_path).map_err(|err| match err.kind() {
+		let message = fs::read_to_string(file_path).map_err(|err| match err.kind() {
 			io::ErrorKind::NotFound => error_resolve!("file '{}' not found", relative),
 			io::ErrorKind::PermissionDenied => error_resolve!("permission denied reading '{}'", relative),
 			io::ErrorKind::IsADirectory => {
@@ -130,13 +224,14 @@ impl FileSystem {
 			}
 			io::ErrorKind::InvalidData => error_resolve!("Invalid data encoding in '{}'", relative),
 			_ => error_resolve!("failed to read '{}', unexpected error", relative),
-		})
+		});
+		message.map_err(|message| message.code(601))
 	}
 
 	/// resolves a mod path given a base directory and a mod path string.
 	/// if the resolved path is a directory, "mod.ln" is appended.
 	#[inline(always)]
-	fn resolve_mod_path(base_dir: &Path, mod_path: &str) -> PathBuf {
+	pub fn resolve_mod_path(base_dir: &Path, mod_path: &str) -> PathBuf {
 		let canonical_mod = Self::to_canonical(PathBuf::from(mod_path));
 		let joined = if base_dir.is_file() {
 			base_dir.parent().unwrap().join(mod_path)
@@ -151,7 +246,7 @@ impl FileSystem {
 	}
 
 	/// converts a path string to an absolute, canonical path.
-	fn abs_and_canonical<P: AsRef<str>>(relative: P) -> PathBuf {
+	pub fn abs_and_canonical<P: AsRef<str>>(relative: P) -> PathBuf {
 		let relative = relative.as_ref();
 		let absolute = if Path::new(relative).is_absolute() {
 			PathBuf::from(relative)
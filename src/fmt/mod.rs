@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use crate::{
+	file_system::FileSystem,
+	lexer::Token,
+	loader::{Loader, ModId},
+	parser::Parser,
+	shio::ShioConfig,
+};
+use logos::Logos;
+
+mod printer;
+
+use printer::Printer;
+
+/// parses `text` as the file at `path`, without loading its imports.
+/// syntax errors are left in the loader.
+pub fn parse_source(path: &Path, text: String) -> (Loader, ModId) {
+	let shio = ShioConfig::with_defaults(path.to_path_buf());
+	let mut file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+	let abs_path = FileSystem::abs_and_canonical(path.display().to_string());
+	file_system.in_memory_cache.insert(abs_path, text);
+	let mut loader = Loader::new(shio, file_system);
+	let mod_id = loader.load_entry().unwrap_or_else(|message| message.report(&loader));
+	let source = loader.lookup_source_unchecked(mod_id).clone();
+	let mut lexer = Token::lexer(source.raw.as_str());
+	let mut parser = Parser::new(&mut lexer, mod_id, &mut loader).without_imports();
+	let ast = parser.parse_program().unwrap_or_else(|message| message.report(&loader));
+	loader.add_mod(mod_id, ast);
+	(loader, mod_id)
+}
+
+/// a parsed mod printed back as canonical source, comments included.
+pub fn format_mod(loader: &Loader, mod_id: ModId) -> String {
+	let source = loader.lookup_source_unchecked(mod_id);
+	let program = loader.get_mod(mod_id).expect("mod is parsed");
+	Printer::new(&source.raw, &program.comments).program(program)
+}
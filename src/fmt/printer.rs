@@ -0,0 +1,648 @@
+use crate::{
+	ast::{self, AstType, Comment, Expr, FnBody, Literal, Stmt},
+	range::Range,
+};
+
+const INDENT: &str = "\t";
+
+/// prints an ast back as source, putting the comments back between the nodes.
+///
+/// comments on a line of their own go before the next statement, field or `}`,
+/// comments after code stay at the end of the line they were on.
+pub struct Printer<'a> {
+	raw: &'a str,
+	comments: &'a [Comment],
+	// next comment to print
+	next: usize,
+	output: String,
+	indent: usize,
+	// the furthest source offset printed so far
+	cursor: usize,
+	// nothing printed yet in the current block, no blank line goes here
+	block_start: bool,
+}
+
+impl<'a> Printer<'a> {
+	pub fn new(raw: &'a str, comments: &'a [Comment]) -> Self {
+		let output = String::with_capacity(raw.len());
+		Self { raw, comments, next: 0, output, indent: 0, cursor: 0, block_start: true }
+	}
+
+	pub fn program(mut self, program: &ast::Program) -> String {
+		self.stmts(&program.stmts);
+		self.comments_before(usize::MAX);
+		let trimmed = self.output.trim_end().len();
+		self.output.truncate(trimmed);
+		if !self.output.is_empty() {
+			self.output.push('\n');
+		}
+		self.output
+	}
+
+	// --- lines and comments -----
+
+	fn write(&mut self, text: &str) {
+		self.output.push_str(text);
+	}
+
+	/// writes a token that came from the source at `range`.
+	fn token(&mut self, text: &str, range: Range) {
+		self.cursor = self.cursor.max(range.start);
+		self.write(text);
+	}
+
+	fn slice(&mut self, range: Range) {
+		let text = &self.raw[range.start..range.end];
+		self.token(text, range);
+	}
+
+	fn indent(&mut self) {
+		for _ in 0..self.indent {
+			self.output.push_str(INDENT);
+		}
+	}
+
+	/// ends the line, with the comments that followed the code printed on it.
+	fn newline(&mut self) {
+		let mut line_comment = false;
+		while let Some(comment) = self.comments.get(self.next) {
+			if !self.is_trailing(comment) || self.line_start(comment.range.start) > self.cursor {
+				break;
+			}
+			// a second comment after a `//` one would join it, so it goes on its own line
+			if line_comment {
+				self.output.push('\n');
+				self.indent();
+			} else {
+				self.output.push(' ');
+			}
+			self.output.push_str(&comment.text);
+			line_comment = comment.text.starts_with("//");
+			self.next += 1;
+		}
+		self.output.push('\n');
+	}
+
+	/// prints the comments before `offset`, each on its own line.
+	fn comments_before(&mut self, offset: usize) {
+		while let Some(comment) = self.comments.get(self.next) {
+			if comment.range.start >= offset {
+				break;
+			}
+			self.blank_line_before(comment.range.start);
+			self.indent();
+			self.output.push_str(&comment.text);
+			self.output.push('\n');
+			self.block_start = false;
+			self.next += 1;
+		}
+	}
+
+	/// keeps one blank line where the source had at least one.
+	fn blank_line_before(&mut self, offset: usize) {
+		let before = &self.raw[..offset];
+		let spaces = &before[before.trim_end().len()..];
+		if !self.block_start && spaces.matches('\n').count() > 1 {
+			self.output.push('\n');
+		}
+	}
+
+	/// a statement, field or impl item on its own line.
+	fn line(&mut self, start: usize, print: impl FnOnce(&mut Self)) {
+		self.comments_before(start);
+		self.blank_line_before(start);
+		self.indent();
+		print(self);
+		self.newline();
+		self.block_start = false;
+	}
+
+	/// `{`, the lines printed by `print` and `}`, where `range` spans the braces.
+	fn braces(&mut self, range: Range, empty: bool, print: impl FnOnce(&mut Self)) {
+		let close = range.end.saturating_sub(1);
+		let has_comments = self.comments.get(self.next).is_some_and(|c| c.range.start < close);
+		self.token("{", range);
+		if empty && !has_comments {
+			self.token("}", Range::new(close, range.end));
+			return;
+		}
+		self.newline();
+		self.indent += 1;
+		self.block_start = true;
+		print(self);
+		self.comments_before(close);
+		self.indent -= 1;
+		self.indent();
+		self.token("}", Range::new(close, range.end));
+	}
+
+	fn is_trailing(&self, comment: &Comment) -> bool {
+		let start = self.line_start(comment.range.start);
+		!self.raw[start..comment.range.start].trim().is_empty()
+	}
+
+	fn line_start(&self, offset: usize) -> usize {
+		self.raw[..offset].rfind('\n').map(|at| at + 1).unwrap_or(0)
+	}
+
+	/// moves a statement start back over a `pub` in front of it.
+	fn with_pub(&self, offset: usize) -> usize {
+		let before = self.raw[..offset].trim_end();
+		match before.strip_suffix("pub") {
+			Some(rest) if !rest.ends_with(|c: char| c.is_alphanumeric() || c == '_') => rest.len(),
+			_ => offset,
+		}
+	}
+
+	fn stmt_start(&self, stmt: &Stmt) -> usize {
+		match stmt {
+			Stmt::Fn(fn_stmt) => self.fn_start(fn_stmt),
+			Stmt::ExternFn(extern_fn) => self.with_pub(extern_fn.extern_range.start),
+			Stmt::ConstDel(const_del) => self.with_pub(const_del.range.start),
+			Stmt::ConstFn(const_fn) => self.with_pub(const_fn.range.start),
+			Stmt::TypeDef(type_def) => self.with_pub(type_def.range.start),
+			Stmt::Expr(expr) => Self::expr_start(expr),
+			stmt => stmt.get_range().start,
+		}
+	}
+
+	fn fn_start(&self, fn_stmt: &ast::FnStmt) -> usize {
+		match fn_stmt.attrs.first() {
+			Some(attr) => attr.range.start,
+			None => self.with_pub(fn_stmt.range.start),
+		}
+	}
+
+	fn expr_start(expr: &Expr) -> usize {
+		match expr {
+			Expr::Binary(binary) => Self::expr_start(&binary.left),
+			Expr::Assign(assign) => Self::expr_start(&assign.left),
+			Expr::Pipe(pipe) => Self::expr_start(&pipe.left),
+			Expr::Call(call) => Self::expr_start(&call.callee),
+			Expr::Member(member) => Self::expr_start(&member.left),
+			Expr::StructInit(init) => init.name.range.start,
+			Expr::Associate(associate) => associate.self_name.range.start,
+			expr => expr.get_range().start,
+		}
+	}
+
+	// --- statements -----
+
+	fn stmts(&mut self, stmts: &[Stmt]) {
+		for stmt in stmts {
+			self.line(self.stmt_start(stmt), |printer| {
+				printer.stmt(stmt);
+				printer.write(Self::terminator(stmt));
+			});
+		}
+	}
+
+	/// the `;` a statement ends with, statements ending in a block have none.
+	fn terminator(stmt: &Stmt) -> &'static str {
+		match stmt {
+			Stmt::Let(_) | Stmt::Expr(_) | Stmt::Ret(_) | Stmt::ConstDel(_) | Stmt::ExternFn(_) => ";",
+			Stmt::Fn(fn_stmt) => Self::body_terminator(&fn_stmt.body),
+			Stmt::ConstFn(const_fn) => Self::body_terminator(&const_fn.body),
+			Stmt::TypeDef(ast::TypeDefStmt { kind: ast::TypeDefKind::Alias(_), .. }) => ";",
+			_ => "",
+		}
+	}
+
+	fn body_terminator(body: &FnBody) -> &'static str {
+		match body {
+			FnBody::Block(_) => "",
+			FnBody::Expr(_) => ";",
+		}
+	}
+
+	fn stmt(&mut self, stmt: &Stmt) {
+		match stmt {
+			Stmt::Let(let_stmt) => self.let_stmt(let_stmt),
+			Stmt::Expr(expr) => self.expr(expr),
+			Stmt::Fn(fn_stmt) => self.fn_stmt(fn_stmt),
+			Stmt::ExternFn(extern_fn) => self.extern_fn_stmt(extern_fn),
+			Stmt::Ret(ret_stmt) => self.ret_stmt(ret_stmt),
+			Stmt::TypeDef(type_def) => self.type_def_stmt(type_def),
+			Stmt::ConstDel(const_del) => self.const_del_stmt(const_del),
+			Stmt::ConstFn(const_fn) => self.const_fn_stmt(const_fn),
+			Stmt::Block(block) => self.block(block),
+			Stmt::If(if_stmt) => self.if_stmt(if_stmt),
+			Stmt::While(while_stmt) => self.while_stmt(while_stmt),
+			Stmt::For(for_stmt) => self.for_stmt(for_stmt),
+			Stmt::Impl(impl_stmt) => self.impl_stmt(impl_stmt),
+		}
+	}
+
+	/// a statement in the place of another, e.g. the branch of an `if`.
+	fn nested_stmt(&mut self, stmt: &Stmt) {
+		self.stmt(stmt);
+		self.write(Self::terminator(stmt));
+	}
+
+	fn block(&mut self, block: &ast::BlockStmt) {
+		self.braces(block.get_range(), block.stmts.is_empty(), |printer| printer.stmts(&block.stmts));
+	}
+
+	fn let_stmt(&mut self, let_stmt: &ast::LetStmt) {
+		self.token("let ", let_stmt.range);
+		if let_stmt.mutable.is_some() {
+			self.write("mut ");
+		}
+		self.binding(&let_stmt.bind);
+		self.write(" = ");
+		self.expr(&let_stmt.expr);
+	}
+
+	fn ret_stmt(&mut self, ret_stmt: &ast::RetStmt) {
+		self.token("return", ret_stmt.range);
+		if let Some(expr) = &ret_stmt.expr {
+			self.write(" ");
+			self.expr(expr);
+		}
+	}
+
+	fn if_stmt(&mut self, if_stmt: &ast::IfStmt) {
+		self.token("if (", if_stmt.range);
+		self.expr(&if_stmt.cond);
+		self.write(") ");
+		self.nested_stmt(&if_stmt.then);
+		if let Some(otherwise) = &if_stmt.otherwise {
+			self.write(" else ");
+			self.nested_stmt(otherwise);
+		}
+	}
+
+	fn while_stmt(&mut self, while_stmt: &ast::WhileStmt) {
+		self.token("while (", while_stmt.range);
+		self.expr(&while_stmt.test);
+		self.write(") = ");
+		self.nested_stmt(&while_stmt.body);
+	}
+
+	fn for_stmt(&mut self, for_stmt: &ast::ForStmt) {
+		self.token("for (", for_stmt.range);
+		self.ident(&for_stmt.value);
+		if let Some(index) = &for_stmt.index {
+			self.write(", ");
+			self.ident(index);
+		}
+		self.write(" in ");
+		self.expr(&for_stmt.iterable);
+		self.write(") = ");
+		self.nested_stmt(&for_stmt.body);
+	}
+
+	fn fn_stmt(&mut self, fn_stmt: &ast::FnStmt) {
+		for attr in &fn_stmt.attrs {
+			self.attribute(attr);
+			self.newline();
+			self.indent();
+		}
+		if fn_stmt.is_pub {
+			self.write("pub ");
+		}
+		self.token("fn ", fn_stmt.range);
+		self.ident(&fn_stmt.name);
+		if !fn_stmt.generics.is_empty() {
+			self.write("<");
+			for (index, generic) in fn_stmt.generics.iter().enumerate() {
+				self.separator(index);
+				self.ident(&generic.ident);
+				if let Some(bound) = &generic.bound {
+					self.write(": ");
+					self.ast_type(bound);
+				}
+			}
+			self.write(">");
+		}
+		self.params(&fn_stmt.params, None);
+		self.ret_type(fn_stmt.ret_type.as_ref());
+		self.fn_body(&fn_stmt.body);
+	}
+
+	fn attribute(&mut self, attr: &ast::Attribute) {
+		self.token("@", attr.range);
+		self.ident(&attr.name);
+		if attr.args.is_empty() {
+			return;
+		}
+		self.write("(");
+		for (index, arg) in attr.args.iter().enumerate() {
+			self.separator(index);
+			self.ident(arg);
+		}
+		self.write(")");
+	}
+
+	fn const_fn_stmt(&mut self, const_fn: &ast::ConstFnStmt) {
+		if const_fn.is_pub {
+			self.write("pub ");
+		}
+		self.token("const ", const_fn.range);
+		self.token("fn ", const_fn.fn_range);
+		self.ident(&const_fn.name);
+		self.params(&const_fn.params, None);
+		self.ret_type(const_fn.ret_type.as_ref());
+		self.fn_body(&const_fn.body);
+	}
+
+	fn extern_fn_stmt(&mut self, extern_fn: &ast::ExternFnStmt) {
+		if extern_fn.is_pub {
+			self.write("pub ");
+		}
+		self.token("extern ", extern_fn.extern_range);
+		self.token("fn ", extern_fn.fn_range);
+		self.ident(&extern_fn.name);
+		self.params(&extern_fn.params, extern_fn.var_packed);
+		self.ret_type(extern_fn.ret_type.as_ref());
+		self.write(" = {}");
+	}
+
+	/// `(a: i32, b: i32)`, with `...` after them for variadic externs.
+	fn params(&mut self, params: &[ast::Binding], var_packed: Option<Range>) {
+		self.write("(");
+		for (index, param) in params.iter().enumerate() {
+			self.separator(index);
+			self.binding(param);
+		}
+		if let Some(range) = var_packed {
+			self.separator(params.len());
+			self.token("...", range);
+		}
+		self.write(")");
+	}
+
+	fn ret_type(&mut self, ret_type: Option<&AstType>) {
+		if let Some(ret_type) = ret_type {
+			self.write(": ");
+			self.ast_type(ret_type);
+		}
+	}
+
+	fn fn_body(&mut self, body: &FnBody) {
+		self.write(" = ");
+		match body {
+			FnBody::Block(block) => self.block(block),
+			FnBody::Expr(expr) => self.expr(expr),
+		}
+	}
+
+	fn const_del_stmt(&mut self, const_del: &ast::ConstDelStmt) {
+		if const_del.is_pub {
+			self.write("pub ");
+		}
+		self.token("const ", const_del.range);
+		self.binding(&const_del.name);
+		self.write(" = ");
+		self.expr(&const_del.expr);
+	}
+
+	fn type_def_stmt(&mut self, type_def: &ast::TypeDefStmt) {
+		if type_def.is_pub {
+			self.write("pub ");
+		}
+		self.token("type ", type_def.range);
+		self.ident(&type_def.name);
+		self.write(" = ");
+		match &type_def.kind {
+			ast::TypeDefKind::Struct(struct_type) => self.struct_type(struct_type),
+			ast::TypeDefKind::Alias(alias) => self.ast_type(alias),
+		}
+	}
+
+	fn struct_type(&mut self, struct_type: &ast::StructType) {
+		let fields = &struct_type.fields;
+		self.braces(struct_type.range, fields.is_empty(), |printer| {
+			for field in fields {
+				printer.line(field.ident.range.start, |printer| {
+					printer.ident(&field.ident);
+					printer.write(": ");
+					printer.ast_type(&field.ast_type);
+					printer.write(",");
+				});
+			}
+		});
+	}
+
+	fn impl_stmt(&mut self, impl_stmt: &ast::ImplStmt) {
+		self.token("impl ", impl_stmt.range);
+		self.ident(&impl_stmt.self_name);
+		self.write(" = ");
+		// the impl keeps no range for its braces, they are around the items
+		let open = self.raw[impl_stmt.self_name.range.end..].find('{');
+		let open = open.map(|at| impl_stmt.self_name.range.end + at).unwrap_or(self.cursor);
+		let close = self.impl_end(impl_stmt, open);
+		let items = &impl_stmt.items;
+		self.braces(Range::new(open, close), items.is_empty(), |printer| {
+			for item in items {
+				// impl items take no `;`, not even after an expression body
+				printer.line(printer.fn_start(item), |printer| printer.fn_stmt(item));
+			}
+		});
+	}
+
+	/// the offset just past the `}` that closes an impl opened at `open`.
+	fn impl_end(&self, impl_stmt: &ast::ImplStmt, open: usize) -> usize {
+		let last = impl_stmt.items.last().map(|item| item.get_range().end).unwrap_or(open + 1);
+		let mut close = last;
+		// the first `}` after the last item that is not inside a comment
+		while let Some(at) = self.raw[close..].find('}') {
+			close += at + 1;
+			let in_comment = self.comments.iter().any(|c| c.range.start < close && close <= c.range.end);
+			if !in_comment {
+				return close;
+			}
+		}
+		self.raw.len()
+	}
+
+	// --- expressions -----
+
+	fn expr(&mut self, expr: &Expr) {
+		match expr {
+			Expr::Group(group) => {
+				self.token("(", group.range);
+				self.expr(&group.expr);
+				self.write(")");
+			}
+			Expr::Fn(fn_expr) => self.fn_expr(fn_expr),
+			Expr::If(if_expr) => {
+				self.token("if (", if_expr.range);
+				self.expr(&if_expr.cond);
+				self.write(") { ");
+				self.expr(&if_expr.then);
+				self.write(" } else { ");
+				self.expr(&if_expr.otherwise);
+				self.write(" }");
+			}
+			Expr::Assign(assign) => {
+				self.expr(&assign.left);
+				self.token(" = ", assign.range);
+				self.expr(&assign.right);
+			}
+			Expr::Associate(associate) => {
+				self.ident(&associate.self_name);
+				self.token("::", associate.range);
+				self.ident(&associate.method);
+			}
+			Expr::Member(member) => {
+				self.expr(&member.left);
+				self.token(".", member.range);
+				self.ident(&member.method);
+			}
+			// no parentheses are added, the source had none, as `(` does not start an expression
+			Expr::Binary(binary) => {
+				self.expr(&binary.left);
+				self.write(" ");
+				self.slice(binary.operator.range);
+				self.write(" ");
+				self.expr(&binary.right);
+			}
+			Expr::Break(base) => self.token("break", base.range),
+			Expr::Skip(base) => self.token("skip", base.range),
+			Expr::Pipe(pipe) => {
+				self.expr(&pipe.left);
+				self.token(" |> ", pipe.range);
+				self.expr(&pipe.right);
+			}
+			Expr::Unary(unary) => {
+				self.slice(unary.operator.range);
+				self.expr(&unary.operand);
+			}
+			Expr::Call(call) => {
+				self.expr(&call.callee);
+				self.token("(", call.range);
+				for (index, arg) in call.args.iter().enumerate() {
+					self.separator(index);
+					self.expr(arg);
+				}
+				self.write(")");
+			}
+			Expr::Import(import) => {
+				self.token("import(", import.range);
+				self.slice(import.path.range);
+				self.write(")");
+			}
+			Expr::Ident(ident) => self.ident(ident),
+			Expr::Literal(literal) => self.literal(literal),
+			Expr::Borrow(borrow) => {
+				self.token("&", borrow.range);
+				if borrow.mutable.is_some() {
+					self.write("mut ");
+				}
+				self.expr(&borrow.expr);
+			}
+			Expr::Deref(deref) => {
+				self.token("*", deref.range);
+				self.expr(&deref.expr);
+			}
+			Expr::StructInit(init) => self.struct_init(init),
+		}
+	}
+
+	fn fn_expr(&mut self, fn_expr: &ast::FnExpr) {
+		self.token("fn", fn_expr.range);
+		self.params(&fn_expr.params, None);
+		self.ret_type(fn_expr.ret_type.as_ref());
+		self.write(" = ");
+		// the body takes the `;` of the statement around it
+		self.stmt(&fn_expr.body);
+	}
+
+	fn struct_init(&mut self, init: &ast::StructInitExpr) {
+		self.ident(&init.name);
+		if init.fields.is_empty() {
+			self.token(" {}", init.range);
+			return;
+		}
+		self.token(" { ", init.range);
+		for (index, field) in init.fields.iter().enumerate() {
+			self.separator(index);
+			self.ident(&field.name);
+			// `{ age: age }` is written `{ age }`
+			match &field.value {
+				Expr::Ident(value) if value.text == field.name.text => {}
+				value => {
+					self.write(": ");
+					self.expr(value);
+				}
+			}
+		}
+		self.write(" }");
+	}
+
+	fn literal(&mut self, literal: &Literal) {
+		match literal {
+			// numbers, strings and chars are kept as written, e.g. `1_000`, `0xff` or `"\n"`
+			Literal::Number(number) => self.slice(number.range),
+			Literal::String(string) => self.slice(string.range),
+			Literal::Char(char) => self.slice(char.range),
+			Literal::Bool(bool) => self.token(if bool.value { "true" } else { "false" }, bool.range),
+			Literal::Null(base) => self.token("null", base.range),
+		}
+	}
+
+	fn binding(&mut self, binding: &ast::Binding) {
+		match &binding.ty {
+			// `&self` and `&mut self` are written as their type
+			Some(ty @ AstType::Borrow(_)) if binding.ident.text == "self" => self.ast_type(ty),
+			Some(ty) => {
+				self.ident(&binding.ident);
+				self.write(": ");
+				self.ast_type(ty);
+			}
+			None => self.ident(&binding.ident),
+		}
+	}
+
+	fn ident(&mut self, ident: &ast::Ident) {
+		self.token(&ident.text, ident.range);
+	}
+
+	fn separator(&mut self, index: usize) {
+		if index > 0 {
+			self.write(", ");
+		}
+	}
+
+	// --- types -----
+
+	fn ast_type(&mut self, ast_type: &AstType) {
+		match ast_type {
+			AstType::Number(number) => {
+				let sign = if number.signed { "i" } else { "u" };
+				let text = match number.bits {
+					0 => format!("{}size", sign),
+					bits => format!("{}{}", sign, bits),
+				};
+				self.token(&text, number.range);
+			}
+			AstType::Float(float) => self.token(&format!("f{}", float.bits), float.range),
+			AstType::Bool(base) => self.token("bool", base.range),
+			AstType::String(base) => self.token("string", base.range),
+			AstType::Str(base) => self.token("str", base.range),
+			AstType::Char(base) => self.token("char", base.range),
+			AstType::Void(base) => self.token("void", base.range),
+			AstType::Ident(ident) => self.token(&ident.text, ident.range),
+			AstType::Fn(fn_type) => {
+				self.token("fn(", fn_type.range);
+				for (index, param) in fn_type.params.iter().enumerate() {
+					self.separator(index);
+					self.ast_type(param);
+				}
+				self.write(")");
+				if let Some(ret_type) = &fn_type.ret_type {
+					self.write(" -> ");
+					self.ast_type(ret_type);
+				}
+			}
+			AstType::Borrow(borrow) => {
+				self.token("&", borrow.range);
+				if borrow.mutable {
+					self.write("mut ");
+				}
+				self.ast_type(&borrow.value);
+			}
+		}
+	}
+}
@@ -0,0 +1,205 @@
+use std::io::{self, Write};
+
+use super::{value::Value, Interp};
+
+/// the extern functions the interpreter implements natively.
+pub const NATIVE_EXTERNS: &[&str] = &["printf", "dprintf", "puts", "malloc", "free", "exit"];
+
+impl Interp<'_> {
+	pub(super) fn call_extern(&mut self, name: &str, args: &[Value]) -> Result<Value, String> {
+		match name {
+			"printf" => {
+				let Some((Value::Str(format), args)) = args.split_first() else {
+					return Err("'printf' expects a str format".to_string());
+				};
+				let text = printf(format, args)?;
+				write_stdout(&text)?;
+				Ok(Value::Int(text.len() as i64))
+			}
+			"dprintf" => {
+				let Some((Value::Int(fd), args)) = args.split_first() else {
+					return Err("'dprintf' expects a file descriptor".to_string());
+				};
+				let Some((Value::Str(format), args)) = args.split_first() else {
+					return Err("'dprintf' expects a str format".to_string());
+				};
+				let text = printf(format, args)?;
+				match fd {
+					1 => write_stdout(&text)?,
+					2 => write_stderr(&text)?,
+					_ => return Err(format!("'dprintf' can only write to 1 and 2, found {}", fd)),
+				}
+				Ok(Value::Int(text.len() as i64))
+			}
+			"puts" => {
+				let [Value::Str(text)] = args else {
+					return Err("'puts' expects a single str".to_string());
+				};
+				write_stdout(&format!("{}\n", text))?;
+				Ok(Value::Int(0))
+			}
+			"malloc" => match args {
+				// the type is unknown, but a cell takes at least a byte
+				[Value::Int(size)] if *size >= 0 => {
+					Ok(Value::Ptr(self.memory.halloc(*size as usize, *size as usize)))
+				}
+				_ => Err("'malloc' expects a non-negative size".to_string()),
+			},
+			"free" => match args {
+				[Value::Ptr(ptr)] => self.memory.free(*ptr).map(|_| Value::Unit),
+				_ => Err("'free' expects a pointer".to_string()),
+			},
+			"exit" => match args {
+				[Value::Int(code)] => {
+					self.exit_code = Some(*code as i32);
+					Err(format!("exit with {}", code))
+				}
+				_ => Err("'exit' expects an exit code".to_string()),
+			},
+			_ => {
+				let natives = NATIVE_EXTERNS.join(", ");
+				Err(format!(
+					"extern function '{}' is not supported by the interpreter, only {} are",
+					name, natives
+				))
+			}
+		}
+	}
+}
+
+fn write_stdout(text: &str) -> Result<(), String> {
+	let mut stdout = io::stdout().lock();
+	stdout.write_all(text.as_bytes()).and_then(|_| stdout.flush()).map_err(|err| err.to_string())
+}
+
+fn write_stderr(text: &str) -> Result<(), String> {
+	let mut stderr = io::stderr().lock();
+	stderr.write_all(text.as_bytes()).and_then(|_| stderr.flush()).map_err(|err| err.to_string())
+}
+
+#[derive(Default)]
+struct Spec {
+	left: bool,
+	zero: bool,
+	plus: bool,
+	space: bool,
+	width: usize,
+	precision: Option<usize>,
+}
+
+/// formats like C's printf, for the conversions lemon programs can pass values to.
+fn printf(format: &str, args: &[Value]) -> Result<String, String> {
+	let mut text = String::new();
+	let mut args = args.iter();
+	let mut chars = format.chars().peekable();
+	while let Some(char) = chars.next() {
+		if char != '%' {
+			text.push(char);
+			continue;
+		}
+		let mut spec = Spec::default();
+		while let Some(flag) = chars.peek() {
+			match flag {
+				'-' => spec.left = true,
+				'0' => spec.zero = true,
+				'+' => spec.plus = true,
+				' ' => spec.space = true,
+				_ => break,
+			}
+			chars.next();
+		}
+		while let Some(digit) = chars.peek().and_then(|char| char.to_digit(10)) {
+			spec.width = spec.width * 10 + digit as usize;
+			chars.next();
+		}
+		if chars.next_if_eq(&'.').is_some() {
+			let mut precision = 0;
+			while let Some(digit) = chars.peek().and_then(|char| char.to_digit(10)) {
+				precision = precision * 10 + digit as usize;
+				chars.next();
+			}
+			spec.precision = Some(precision);
+		}
+		// the length is taken from the value
+		while chars.next_if(|char| matches!(char, 'h' | 'l' | 'z' | 'j' | 't' | 'L')).is_some() {}
+
+		let Some(conversion) = chars.next() else {
+			return Err("incomplete conversion at the end of the printf format".to_string());
+		};
+		if conversion == '%' {
+			text.push('%');
+			continue;
+		}
+		let Some(arg) = args.next() else {
+			return Err(format!("printf '%{}' has no argument", conversion));
+		};
+		let mismatch = || format!("printf '%{}' cannot format a {}", conversion, arg.kind());
+		let (sign, body) = match (conversion, arg) {
+			('d' | 'i', Value::Int(value)) => (sign(*value < 0, &spec), value.unsigned_abs().to_string()),
+			('u', Value::Int(value)) => ("", (*value as u64).to_string()),
+			('x', Value::Int(value)) => ("", format!("{:x}", value)),
+			('X', Value::Int(value)) => ("", format!("{:X}", value)),
+			('o', Value::Int(value)) => ("", format!("{:o}", value)),
+			('c', Value::Int(value)) => ("", char::from(*value as u8).to_string()),
+			('s', Value::Str(value)) => match spec.precision {
+				Some(precision) => ("", value.chars().take(precision).collect()),
+				None => ("", value.to_string()),
+			},
+			('f' | 'F', Value::Float(value)) => {
+				let precision = spec.precision.unwrap_or(6);
+				(sign(value.is_sign_negative(), &spec), format!("{:.*}", precision, value.abs()))
+			}
+			('e' | 'E', Value::Float(value)) => {
+				let precision = spec.precision.unwrap_or(6);
+				let body = exponent(value.abs(), precision);
+				let body = if conversion == 'E' { body.to_uppercase() } else { body };
+				(sign(value.is_sign_negative(), &spec), body)
+			}
+			('d' | 'i' | 'u' | 'x' | 'X' | 'o' | 'c' | 's' | 'f' | 'F' | 'e' | 'E', _) => {
+				return Err(mismatch());
+			}
+			_ => return Err(format!("unsupported printf conversion '%{}'", conversion)),
+		};
+		pad(&mut text, sign, &body, &spec, conversion != 's' && conversion != 'c');
+	}
+	if args.next().is_some() {
+		return Err("printf has more arguments than conversions".to_string());
+	}
+	Ok(text)
+}
+
+fn sign(negative: bool, spec: &Spec) -> &'static str {
+	match negative {
+		true => "-",
+		false if spec.plus => "+",
+		false if spec.space => " ",
+		false => "",
+	}
+}
+
+/// `1.500000e+00`, rust prints `1.5e0`
+fn exponent(value: f64, precision: usize) -> String {
+	let text = format!("{:.*e}", precision, value);
+	let (mantissa, exp) = text.split_once('e').unwrap_or((&text, "0"));
+	let exp: i32 = exp.parse().unwrap_or(0);
+	let exp_sign = if exp < 0 { '-' } else { '+' };
+	format!("{}e{}{:02}", mantissa, exp_sign, exp.abs())
+}
+
+fn pad(text: &mut String, sign: &str, body: &str, spec: &Spec, numeric: bool) {
+	let len = sign.len() + body.chars().count();
+	let fill = spec.width.saturating_sub(len);
+	if spec.left {
+		text.push_str(sign);
+		text.push_str(body);
+		text.extend(std::iter::repeat_n(' ', fill));
+	} else if spec.zero && numeric {
+		text.push_str(sign);
+		text.extend(std::iter::repeat_n('0', fill));
+		text.push_str(body);
+	} else {
+		text.extend(std::iter::repeat_n(' ', fill));
+		text.push_str(sign);
+		text.push_str(body);
+	}
+}
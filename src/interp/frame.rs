@@ -0,0 +1,41 @@
+use rustc_hash::FxHashMap;
+
+use super::value::Value;
+
+/// a function being executed, registers are bound once like llvm values,
+/// stack slots hold pointers into the checked memory.
+#[derive(Debug, Clone)]
+pub struct Frame {
+	pub locals: FxHashMap<String, Value>,
+	pub fn_name: String,
+	// index of the function in the ir
+	pub function: usize,
+	pub block: usize,
+	pub index: usize,
+	// stack slots to release on return
+	pub slots: Vec<usize>,
+	// caller register that receives the return value
+	pub ret_dest: Option<String>,
+}
+
+impl Frame {
+	pub fn new(fn_name: &str, function: usize) -> Self {
+		Self {
+			locals: FxHashMap::default(),
+			fn_name: fn_name.to_string(),
+			function,
+			block: 0,
+			index: 0,
+			slots: Vec::new(),
+			ret_dest: None,
+		}
+	}
+
+	pub fn get(&self, name: &str) -> Option<&Value> {
+		self.locals.get(name)
+	}
+
+	pub fn set(&mut self, name: &str, value: Value) {
+		self.locals.insert(name.to_string(), value);
+	}
+}
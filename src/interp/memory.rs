@@ -0,0 +1,134 @@
+use super::value::{Ptr, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+	Stack,
+	Heap,
+}
+
+#[derive(Debug)]
+struct Allocation {
+	region: Region,
+	cells: Vec<Option<Value>>,
+	// bytes asked for, only known for the heap
+	size: usize,
+	live: bool,
+	// bumped each time a released stack slot is reused
+	gen: u32,
+}
+
+impl Allocation {
+	fn describe(&self, ptr: Ptr) -> String {
+		match self.region {
+			Region::Stack => format!("stack slot #{}", ptr.alloc),
+			Region::Heap => format!("heap allocation #{}", ptr.alloc),
+		}
+	}
+}
+
+/// checked memory, heap allocations are never reused and stack slots are
+/// reused with a new generation, so every access through a dangling pointer is caught.
+#[derive(Debug, Default)]
+pub struct Memory {
+	allocs: Vec<Allocation>,
+	released: Vec<usize>,
+}
+
+impl Memory {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// a stack slot of `cells` cells, released when its function returns.
+	pub fn salloc(&mut self, cells: usize) -> Ptr {
+		let Some(slot) = self.released.pop() else {
+			return self.alloc(Region::Stack, vec![None; cells], 0);
+		};
+		let alloc = &mut self.allocs[slot];
+		alloc.cells.clear();
+		alloc.cells.resize(cells, None);
+		alloc.live = true;
+		alloc.gen += 1;
+		Ptr::new(slot, alloc.gen)
+	}
+
+	/// a heap allocation of `size` bytes, with room for `cells` cells.
+	pub fn halloc(&mut self, cells: usize, size: usize) -> Ptr {
+		self.alloc(Region::Heap, vec![None; cells], size)
+	}
+
+	fn alloc(&mut self, region: Region, cells: Vec<Option<Value>>, size: usize) -> Ptr {
+		self.allocs.push(Allocation { region, cells, size, live: true, gen: 0 });
+		Ptr::new(self.allocs.len() - 1, 0)
+	}
+
+	pub fn free(&mut self, ptr: Ptr) -> Result<(), String> {
+		let alloc = self.lookup(ptr)?;
+		if alloc.region == Region::Stack {
+			return Err(format!("cannot free stack slot #{}", ptr.alloc));
+		}
+		if !alloc.live {
+			return Err(format!("double free of heap allocation #{}", ptr.alloc));
+		}
+		if ptr.cell != 0 {
+			return Err(format!(
+				"cannot free a pointer into the middle of heap allocation #{}",
+				ptr.alloc
+			));
+		}
+		self.allocs[ptr.alloc].live = false;
+		Ok(())
+	}
+
+	/// releases the stack slots of a returning function.
+	pub fn release(&mut self, slots: &[usize]) {
+		for slot in slots {
+			self.allocs[*slot].live = false;
+		}
+		self.released.extend(slots);
+	}
+
+	pub fn load(&self, ptr: Ptr) -> Result<Value, String> {
+		match self.peek(ptr)? {
+			Some(value) => Ok(value),
+			None => Err(format!("read of uninitialized memory at cell {} of #{}", ptr.cell, ptr.alloc)),
+		}
+	}
+
+	/// like load, but a cell never stored to is not an error.
+	pub fn peek(&self, ptr: Ptr) -> Result<Option<Value>, String> {
+		let alloc = self.lookup_live(ptr)?;
+		match alloc.cells.get(ptr.cell) {
+			Some(cell) => Ok(cell.clone()),
+			None => Err(format!("out of bounds read at cell {} of {}", ptr.cell, alloc.describe(ptr))),
+		}
+	}
+
+	pub fn store(&mut self, ptr: Ptr, value: Value) -> Result<(), String> {
+		let alloc = self.lookup_live(ptr)?;
+		if ptr.cell >= alloc.cells.len() {
+			return Err(format!("out of bounds write at cell {} of {}", ptr.cell, alloc.describe(ptr)));
+		}
+		self.allocs[ptr.alloc].cells[ptr.cell] = Some(value);
+		Ok(())
+	}
+
+	/// heap allocations still live, as `(id, size)`.
+	pub fn leaks(&self) -> Vec<(usize, usize)> {
+		let heap = self.allocs.iter().enumerate().filter(|(_, alloc)| alloc.region == Region::Heap);
+		heap.filter(|(_, alloc)| alloc.live).map(|(id, alloc)| (id, alloc.size)).collect()
+	}
+
+	fn lookup(&self, ptr: Ptr) -> Result<&Allocation, String> {
+		self.allocs.get(ptr.alloc).ok_or_else(|| format!("invalid pointer #{}", ptr.alloc))
+	}
+
+	fn lookup_live(&self, ptr: Ptr) -> Result<&Allocation, String> {
+		let alloc = self.lookup(ptr)?;
+		match alloc.region {
+			_ if alloc.live && alloc.gen == ptr.gen => Ok(alloc),
+			Region::Heap => Err(format!("use after free of heap allocation #{}", ptr.alloc)),
+			Region::Stack => Err(format!("use of stack slot #{} after its function returned", ptr.alloc)),
+		}
+	}
+}
@@ -0,0 +1,595 @@
+// an interpreter over the lemon ir, an alternative to the llvm backend
+// that needs no toolchain and checks every memory access.
+mod externs;
+mod frame;
+mod memory;
+mod value;
+
+use std::{cmp::Ordering, rc::Rc};
+
+use frame::Frame;
+use memory::Memory;
+use rustc_hash::FxHashMap;
+pub use value::{Ptr, Value};
+
+use crate::{
+	checker::types::{TypeId, TypeStore},
+	ir::{self, BasicValue, IrBasicValue},
+	message::{Message, MessageResult},
+	note,
+};
+
+// deep enough for any sane recursion, deterministic unlike the native stack
+const MAX_FRAMES: usize = 1 << 16;
+
+enum Flow {
+	Next,
+	Return(Value),
+}
+
+#[derive(Clone, Copy)]
+enum BinOp {
+	Add,
+	Sub,
+	Mul,
+	Div,
+	Mod,
+	And,
+	Or,
+	Shl,
+	Shr,
+	CmpEq,
+	CmpNe,
+	CmpLt,
+	CmpGt,
+	CmpLe,
+	CmpGe,
+}
+
+impl BinOp {
+	fn name(self) -> &'static str {
+		match self {
+			BinOp::Add => "add",
+			BinOp::Sub => "sub",
+			BinOp::Mul => "mul",
+			BinOp::Div => "div",
+			BinOp::Mod => "mod",
+			BinOp::And => "and",
+			BinOp::Or => "or",
+			BinOp::Shl => "shl",
+			BinOp::Shr => "shr",
+			BinOp::CmpEq => "cmp_eq",
+			BinOp::CmpNe => "cmp_ne",
+			BinOp::CmpLt => "cmp_lt",
+			BinOp::CmpGt => "cmp_gt",
+			BinOp::CmpLe => "cmp_le",
+			BinOp::CmpGe => "cmp_ge",
+		}
+	}
+}
+
+pub struct Interp<'ir> {
+	ir: &'ir ir::IR,
+	type_store: &'ir TypeStore,
+	functions: FxHashMap<&'ir str, usize>,
+	memory: Memory,
+	frames: Vec<Frame>,
+	// global name -> pointer to its one cell slot, it lives as long as the interpreter
+	globals: FxHashMap<&'ir str, Value>,
+	// set by `exit`, which unwinds every frame
+	exit_code: Option<i32>,
+}
+
+impl<'ir> Interp<'ir> {
+	pub fn new(ir: &'ir ir::IR, type_store: &'ir TypeStore) -> Self {
+		let functions = ir.functions.iter().enumerate();
+		let functions = functions.map(|(index, function)| (function.name.as_str(), index)).collect();
+		let mut memory = Memory::new();
+		let mut globals = FxHashMap::default();
+		for global in &ir.globals {
+			let ptr = memory.salloc(1);
+			// a fresh slot always takes the store
+			let _ = memory.store(ptr, zeroed(global.kind));
+			globals.insert(global.name.as_str(), Value::Ptr(ptr));
+		}
+		Self { ir, type_store, functions, memory, frames: Vec::new(), globals, exit_code: None }
+	}
+
+	/// runs `main`, its return value is the exit code.
+	pub fn run_main(&mut self) -> MessageResult<i32> {
+		match self.call("main", Vec::new())? {
+			Value::Int(code) => Ok(code as i32),
+			_ => Ok(0),
+		}
+	}
+
+	pub fn call(&mut self, name: &str, args: Vec<Value>) -> MessageResult<Value> {
+		let base = self.frames.len();
+		match self.enter(name, args, None) {
+			Ok(Some(value)) => Ok(value),
+			Ok(None) => self.execute(base),
+			Err(text) => self.fault_or_exit(text),
+		}
+	}
+
+	/// heap allocations never dropped, as `(id, size)`.
+	pub fn leaks(&self) -> Vec<(usize, usize)> {
+		self.memory.leaks()
+	}
+
+	fn execute(&mut self, base: usize) -> MessageResult<Value> {
+		let ir = self.ir;
+		loop {
+			let frame = self.frame();
+			let function = &ir.functions[frame.function];
+			let flow = match function.blocks[frame.block].instrs().get(frame.index) {
+				Some(instr) => {
+					self.frame_mut().index += 1;
+					self.step(instr)
+				}
+				// the llvm backend only closes main and void functions
+				None => self.implicit_return(function).map(Flow::Return),
+			};
+			match flow {
+				Ok(Flow::Next) => {}
+				Ok(Flow::Return(value)) => match self.leave(value, base) {
+					Ok(Some(value)) => return Ok(value),
+					Ok(None) => {}
+					Err(text) => return Err(self.fault(text)),
+				},
+				Err(text) => return self.fault_or_exit(text),
+			}
+		}
+	}
+
+	/// runs a native extern right away, or pushes the frame of a lemon function.
+	fn enter(
+		&mut self,
+		name: &str,
+		args: Vec<Value>,
+		ret_dest: Option<String>,
+	) -> Result<Option<Value>, String> {
+		let Some(&index) = self.functions.get(name) else {
+			return Err(format!("function '{}' not found", name));
+		};
+		let function = &self.ir.functions[index];
+		if function.is_extern_function() {
+			return self.call_extern(name, &args).map(Some);
+		}
+		if args.len() != function.args.len() {
+			return Err(format!("'{}' expects {} args, found {}", name, function.args.len(), args.len()));
+		}
+		if self.frames.len() >= MAX_FRAMES {
+			return Err(format!("stack overflow calling '{}'", name));
+		}
+		if function.blocks.is_empty() {
+			return self.implicit_return(function).map(Some);
+		}
+		let mut frame = Frame::new(name, index);
+		for (param, arg) in function.args.iter().zip(args) {
+			frame.set(param.value.as_str(), arg);
+		}
+		frame.ret_dest = ret_dest;
+		self.frames.push(frame);
+		Ok(None)
+	}
+
+	/// pops the frame, the value goes to the caller or out of the interpreter at `base`.
+	fn leave(&mut self, value: Value, base: usize) -> Result<Option<Value>, String> {
+		let frame = self.frames.pop().expect("no frame to leave");
+		self.memory.release(&frame.slots);
+		if self.frames.len() == base {
+			return Ok(Some(value));
+		}
+		if let Some(dest) = frame.ret_dest {
+			self.store_register(&dest, value)?;
+		}
+		Ok(None)
+	}
+
+	fn implicit_return(&self, function: &ir::Function) -> Result<Value, String> {
+		if function.is_main() {
+			return Ok(Value::Int(0));
+		}
+		if function.ret.is_empty_type() {
+			return Ok(Value::Unit);
+		}
+		Err(format!("'{}' reached the end of a block without returning a value", function.name))
+	}
+
+	fn step(&mut self, instr: &ir::Instr) -> Result<Flow, String> {
+		match instr {
+			// math
+			ir::Instr::Add(binary) => self.binary(binary, BinOp::Add)?,
+			ir::Instr::Sub(binary) => self.binary(binary, BinOp::Sub)?,
+			ir::Instr::Mul(binary) => self.binary(binary, BinOp::Mul)?,
+			ir::Instr::Div(binary) => self.binary(binary, BinOp::Div)?,
+			ir::Instr::Mod(binary) => self.binary(binary, BinOp::Mod)?,
+			// logic
+			ir::Instr::Neg(unary) => self.neg(unary)?,
+			ir::Instr::Not(unary) => self.not(unary)?,
+			ir::Instr::And(binary) => self.binary(binary, BinOp::And)?,
+			ir::Instr::Or(binary) => self.binary(binary, BinOp::Or)?,
+			ir::Instr::Shl(binary) => self.binary(binary, BinOp::Shl)?,
+			ir::Instr::Shr(binary) => self.binary(binary, BinOp::Shr)?,
+			// cmp
+			ir::Instr::CmpEq(binary) => self.binary(binary, BinOp::CmpEq)?,
+			ir::Instr::CmpNe(binary) => self.binary(binary, BinOp::CmpNe)?,
+			ir::Instr::CmpLt(binary) => self.binary(binary, BinOp::CmpLt)?,
+			ir::Instr::CmpGt(binary) => self.binary(binary, BinOp::CmpGt)?,
+			ir::Instr::CmpLe(binary) => self.binary(binary, BinOp::CmpLe)?,
+			ir::Instr::CmpGe(binary) => self.binary(binary, BinOp::CmpGe)?,
+			// mem
+			ir::Instr::Load(unary) => self.load(unary)?,
+			ir::Instr::Mov(unary) => self.mov(unary)?,
+			ir::Instr::Set(unary) => self.set(unary)?,
+			ir::Instr::Salloc(instr) => self.salloc(instr),
+			ir::Instr::Halloc(unary) => self.halloc(unary)?,
+			ir::Instr::Drop(value) => self.drop(value)?,
+			ir::Instr::Getptr(instr) => self.getptr(instr)?,
+			// control
+			ir::Instr::Jmp(jump) => self.jump(jump.label)?,
+			ir::Instr::JmpIf(jump_if) => self.jump_if(jump_if)?,
+			// fn
+			ir::Instr::Call(call) => self.call_instr(call)?,
+			ir::Instr::Ret(value) => return self.ret(value).map(Flow::Return),
+		}
+		Ok(Flow::Next)
+	}
+
+	// values
+	//
+	fn eval(&self, value: &IrBasicValue) -> Result<Value, String> {
+		match &value.value {
+			BasicValue::Int(int) => Ok(Value::Int(*int as i64)),
+			BasicValue::Float(float) => Ok(Value::Float(*float)),
+			BasicValue::Bool(bool) => Ok(Value::Bool(*bool)),
+			BasicValue::String(text) => Ok(Value::Str(Rc::from(text.as_str()))),
+			BasicValue::Char(char) => Ok(Value::Int(*char as i64)),
+			BasicValue::Register(name) => self.register(name).cloned(),
+			BasicValue::None => Err("cannot evaluate an empty value".to_string()),
+		}
+	}
+
+	fn register(&self, name: &str) -> Result<&Value, String> {
+		let value = self.frame().get(name).or_else(|| self.globals.get(name));
+		value.ok_or_else(|| format!("register '{}' is not defined", name))
+	}
+
+	fn register_ptr(&self, name: &str) -> Result<Ptr, String> {
+		let value = self.register(name)?;
+		value
+			.as_ptr()
+			.ok_or_else(|| format!("register '{}' holds a {}, not a pointer", name, value.kind()))
+	}
+
+	/// stores into the slot a register points to, like binary and call results.
+	fn store_register(&mut self, name: &str, value: Value) -> Result<(), String> {
+		let ptr = self.register_ptr(name)?;
+		self.store(ptr, value)
+	}
+
+	fn store(&mut self, ptr: Ptr, value: Value) -> Result<(), String> {
+		match value {
+			Value::Struct(cells) => {
+				for (index, cell) in cells.into_iter().enumerate() {
+					if let Some(cell) = cell {
+						self.memory.store(ptr.offset(index), cell)?;
+					}
+				}
+				Ok(())
+			}
+			value => self.memory.store(ptr, value),
+		}
+	}
+
+	/// a struct type is loaded by value, cell by cell.
+	fn load_typed(&self, ptr: Ptr, type_id: TypeId) -> Result<Value, String> {
+		if self.type_store.is_borrow(type_id) || self.struct_of(type_id).is_none() {
+			return self.memory.load(ptr);
+		}
+		let cells = (0..self.cells(type_id)).map(|cell| self.memory.peek(ptr.offset(cell)));
+		Ok(Value::Struct(cells.collect::<Result<_, _>>()?))
+	}
+
+	// types
+	//
+	fn struct_of(&self, type_id: TypeId) -> Option<&'ir ir::Struct> {
+		let name = self.type_store.lookup_struct_symbol(type_id)?;
+		self.lookup_struct(name)
+	}
+
+	fn lookup_struct(&self, name: &str) -> Option<&'ir ir::Struct> {
+		self.ir.structs.iter().find(|found| found.name.as_deref() == Some(name))
+	}
+
+	/// cells a value of the type takes, inline structs are flattened.
+	fn cells(&self, type_id: TypeId) -> usize {
+		if self.type_store.is_borrow(type_id) {
+			return 1;
+		}
+		match self.struct_of(type_id) {
+			Some(found) => found.fields.iter().map(|field| self.cells(*field)).sum::<usize>().max(1),
+			None => 1,
+		}
+	}
+
+	fn bits(&self, type_id: TypeId) -> u32 {
+		match self.type_store.resolve_borrow_type(type_id) {
+			TypeId::I8 | TypeId::U8 | TypeId::CHAR | TypeId::BOOL => 8,
+			TypeId::I16 | TypeId::U16 => 16,
+			TypeId::I32 | TypeId::U32 => 32,
+			_ => 64,
+		}
+	}
+
+	/// wraps an integer result to the width of its type.
+	fn wrap(&self, value: i64, type_id: TypeId) -> i64 {
+		match self.type_store.resolve_borrow_type(type_id) {
+			TypeId::I8 => value as i8 as i64,
+			TypeId::U8 | TypeId::CHAR => value as u8 as i64,
+			TypeId::I16 => value as i16 as i64,
+			TypeId::U16 => value as u16 as i64,
+			TypeId::I32 => value as i32 as i64,
+			TypeId::U32 => value as u32 as i64,
+			_ => value,
+		}
+	}
+
+	// instructions
+	//
+	fn binary(&mut self, binary: &ir::BinInstr, op: BinOp) -> Result<(), String> {
+		let left = self.eval(&binary.left)?;
+		let right = self.eval(&binary.right)?;
+		let value = match (op, &left, &right) {
+			(BinOp::CmpEq | BinOp::CmpNe, _, _) if left.kind() != right.kind() => {
+				return Err(unsupported(op, &left, &right));
+			}
+			(BinOp::CmpEq, _, _) => Value::Bool(left == right),
+			(BinOp::CmpNe, _, _) => Value::Bool(left != right),
+			(BinOp::CmpLt | BinOp::CmpGt | BinOp::CmpLe | BinOp::CmpGe, _, _) => {
+				let ordering = match (&left, &right) {
+					(Value::Int(left), Value::Int(right)) => left.partial_cmp(right),
+					(Value::Float(left), Value::Float(right)) => left.partial_cmp(right),
+					(Value::Bool(left), Value::Bool(right)) => left.partial_cmp(right),
+					_ => return Err(unsupported(op, &left, &right)),
+				};
+				Value::Bool(match (op, ordering) {
+					(_, None) => false,
+					(BinOp::CmpLt, Some(ordering)) => ordering == Ordering::Less,
+					(BinOp::CmpGt, Some(ordering)) => ordering == Ordering::Greater,
+					(BinOp::CmpLe, Some(ordering)) => ordering != Ordering::Greater,
+					(_, Some(ordering)) => ordering != Ordering::Less,
+				})
+			}
+			(_, Value::Int(left), Value::Int(right)) => {
+				let value = self.int_binary(op, *left, *right, binary.dest.type_id)?;
+				Value::Int(self.wrap(value, binary.dest.type_id))
+			}
+			(_, Value::Float(left), Value::Float(right)) => Value::Float(match op {
+				BinOp::Add => left + right,
+				BinOp::Sub => left - right,
+				BinOp::Mul => left * right,
+				BinOp::Div => left / right,
+				BinOp::Mod => left % right,
+				_ => return Err(unsupported(op, &Value::Float(*left), &Value::Float(*right))),
+			}),
+			(BinOp::And, Value::Bool(left), Value::Bool(right)) => Value::Bool(*left && *right),
+			(BinOp::Or, Value::Bool(left), Value::Bool(right)) => Value::Bool(*left || *right),
+			_ => return Err(unsupported(op, &left, &right)),
+		};
+		self.store_register(binary.dest.value.as_str(), value)
+	}
+
+	fn int_binary(&self, op: BinOp, left: i64, right: i64, type_id: TypeId) -> Result<i64, String> {
+		let bits = self.bits(type_id);
+		let shift = || match u32::try_from(right) {
+			Ok(shift) if shift < bits => Ok(shift),
+			_ => Err(format!("shift by {} overflows a {} bit integer", right, bits)),
+		};
+		match op {
+			BinOp::Add => Ok(left.wrapping_add(right)),
+			BinOp::Sub => Ok(left.wrapping_sub(right)),
+			BinOp::Mul => Ok(left.wrapping_mul(right)),
+			BinOp::Div | BinOp::Mod if right == 0 => Err("division by zero".to_string()),
+			BinOp::Div => Ok(left.wrapping_div(right)),
+			BinOp::Mod => Ok(left.wrapping_rem(right)),
+			BinOp::And => Ok(left & right),
+			BinOp::Or => Ok(left | right),
+			BinOp::Shl => Ok(left.wrapping_shl(shift()?)),
+			// logical, the llvm backend doesn't sign extend
+			BinOp::Shr => {
+				let mask = if bits == 64 { u64::MAX } else { (1 << bits) - 1 };
+				Ok(((left as u64 & mask) >> shift()?) as i64)
+			}
+			_ => unreachable!("'{}' is not an integer operation", op.name()),
+		}
+	}
+
+	fn neg(&mut self, unary: &ir::BinInstr) -> Result<(), String> {
+		let value = match self.eval(&unary.left)? {
+			Value::Int(value) => Value::Int(self.wrap(value.wrapping_neg(), unary.dest.type_id)),
+			Value::Float(value) => Value::Float(-value),
+			found => return Err(format!("unsupported 'neg' {}", found.kind())),
+		};
+		self.store_register(unary.dest.value.as_str(), value)
+	}
+
+	fn not(&mut self, unary: &ir::BinInstr) -> Result<(), String> {
+		let value = match self.eval(&unary.left)? {
+			Value::Bool(value) => Value::Bool(!value),
+			Value::Int(value) => Value::Int(self.wrap(!value, unary.dest.type_id)),
+			found => return Err(format!("unsupported 'not' {}", found.kind())),
+		};
+		self.store_register(unary.dest.value.as_str(), value)
+	}
+
+	fn load(&mut self, unary: &ir::UnInstr) -> Result<(), String> {
+		let ptr = match self.eval(&unary.src)? {
+			Value::Ptr(ptr) => ptr,
+			found => return Err(format!("cannot load from non-pointer {}", found.kind())),
+		};
+		let value = self.load_typed(ptr, unary.dest.type_id)?;
+		self.frame_mut().set(unary.dest.value.as_str(), value);
+		Ok(())
+	}
+
+	/// copies the slot of src into the slot of dest.
+	fn mov(&mut self, unary: &ir::UnInstr) -> Result<(), String> {
+		let value = match self.eval(&unary.src)? {
+			Value::Ptr(ptr) => self.load_typed(ptr, unary.src.type_id)?,
+			value => value,
+		};
+		self.store_register(unary.dest.value.as_str(), value)
+	}
+
+	fn set(&mut self, unary: &ir::UnInstr) -> Result<(), String> {
+		let value = self.eval(&unary.src)?;
+		let ptr = match self.eval(&unary.dest)? {
+			Value::Ptr(ptr) => ptr,
+			found => return Err(format!("cannot set through non-pointer {}", found.kind())),
+		};
+		self.store(ptr, value)
+	}
+
+	fn salloc(&mut self, instr: &ir::SallocInstr) {
+		let ptr = self.memory.salloc(self.cells(instr.size));
+		let frame = self.frame_mut();
+		frame.slots.push(ptr.alloc);
+		frame.set(instr.dest.value.as_str(), Value::Ptr(ptr));
+	}
+
+	fn halloc(&mut self, unary: &ir::UnInstr) -> Result<(), String> {
+		let size = match self.eval(&unary.src)? {
+			Value::Int(size) if size >= 0 => size as usize,
+			found => return Err(format!("cannot allocate a size of {}", found)),
+		};
+		// a struct has its cells, anything else is bounded by its bytes as a cell takes at least one
+		let cells = match self.struct_of(unary.dest.type_id) {
+			Some(_) => self.cells(unary.dest.type_id),
+			None => size,
+		};
+		let ptr = self.memory.halloc(cells, size);
+		self.frame_mut().set(unary.dest.value.as_str(), Value::Ptr(ptr));
+		Ok(())
+	}
+
+	fn drop(&mut self, value: &IrBasicValue) -> Result<(), String> {
+		match self.eval(value)? {
+			Value::Ptr(ptr) => self.memory.free(ptr),
+			found => Err(format!("cannot drop a {}", found.kind())),
+		}
+	}
+
+	fn getptr(&mut self, instr: &ir::GetPtrInstr) -> Result<(), String> {
+		let base = match self.eval(&instr.self_base)? {
+			Value::Ptr(ptr) => ptr,
+			found => return Err(format!("cannot get a field pointer of a {}", found.kind())),
+		};
+		let self_name = instr.self_name.as_str();
+		let Some(found) = self.lookup_struct(self_name) else {
+			return Err(format!("struct '{}' not found", self_name));
+		};
+		if instr.offset >= found.fields.len() {
+			let count = found.fields.len();
+			return Err(format!(
+				"field {} is out of bounds for '{}' with {} fields",
+				instr.offset, self_name, count
+			));
+		}
+		let cells = found.fields[..instr.offset].iter().map(|field| self.cells(*field)).sum();
+		self.frame_mut().set(instr.dest.value.as_str(), Value::Ptr(base.offset(cells)));
+		Ok(())
+	}
+
+	fn jump(&mut self, label: usize) -> Result<(), String> {
+		let function = &self.ir.functions[self.frame().function];
+		let Some(block) = function.blocks.iter().position(|block| block.label == label) else {
+			return Err(format!("label blk{} not found", label));
+		};
+		let frame = self.frame_mut();
+		frame.block = block;
+		frame.index = 0;
+		Ok(())
+	}
+
+	fn jump_if(&mut self, jump_if: &ir::JmpIfInstr) -> Result<(), String> {
+		// the condition is left in the slot of the compare
+		let cond = match self.eval(&jump_if.cond)? {
+			Value::Ptr(ptr) => self.memory.load(ptr)?,
+			value => value,
+		};
+		match cond {
+			Value::Bool(true) => self.jump(jump_if.true_label),
+			Value::Bool(false) => self.jump(jump_if.false_label),
+			found => Err(format!("expected a bool condition, found {}", found.kind())),
+		}
+	}
+
+	fn call_instr(&mut self, call: &ir::CallInstr) -> Result<(), String> {
+		let args = call.args.iter().map(|arg| self.eval(arg)).collect::<Result<_, _>>()?;
+		let returns = match self.functions.get(call.callee.as_str()) {
+			Some(index) => !self.ir.functions[*index].ret.is_empty_type(),
+			None => false,
+		};
+		let dest = call.dest.value.as_str();
+		let ret_dest = if returns { Some(dest.to_string()) } else { None };
+		if let Some(value) = self.enter(&call.callee, args, ret_dest)? {
+			if returns {
+				self.store_register(dest, value)?;
+			}
+		}
+		Ok(())
+	}
+
+	fn ret(&self, value: &Option<IrBasicValue>) -> Result<Value, String> {
+		match value {
+			Some(value) if !value.type_id.is_empty_type() => self.eval(value),
+			_ => Ok(Value::Unit),
+		}
+	}
+
+	// frames
+	//
+	fn frame(&self) -> &Frame {
+		self.frames.last().expect("no frame")
+	}
+
+	fn frame_mut(&mut self) -> &mut Frame {
+		self.frames.last_mut().expect("no frame")
+	}
+
+	/// a runtime error, located at the function and block being executed.
+	/// `exit` ends the program wherever it's called, its code comes out as main's.
+	fn fault_or_exit(&mut self, text: String) -> MessageResult<Value> {
+		match self.exit_code.take() {
+			Some(code) => {
+				self.frames.clear();
+				Ok(Value::Int(code as i64))
+			}
+			None => Err(self.fault(text)),
+		}
+	}
+
+	fn fault(&self, text: String) -> Message {
+		let message = Message::error_comptime(text).code(807);
+		let Some(frame) = self.frames.last() else {
+			return message;
+		};
+		let block = &self.ir.functions[frame.function].blocks[frame.block];
+		message.note(note!("in '{}' at {}", frame.fn_name, block.format_label()))
+	}
+}
+
+fn unsupported(op: BinOp, left: &Value, right: &Value) -> String {
+	format!("unsupported '{}' {} to {}", op.name(), left.kind(), right.kind())
+}
+
+/// the value a global starts with, like the zero initializer of an llvm global.
+fn zeroed(type_id: TypeId) -> Value {
+	match type_id {
+		TypeId::BOOL => Value::Bool(false),
+		TypeId::F32 | TypeId::F64 => Value::Float(0.0),
+		_ => Value::Int(0),
+	}
+}
@@ -0,0 +1,80 @@
+use std::{fmt, rc::Rc};
+
+/// a pointer into the checked memory, `cell` is the flattened field index
+/// inside the allocation, e.g. field 1 of a `struct { a: i32, b: i32 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ptr {
+	pub alloc: usize,
+	pub gen: u32,
+	pub cell: usize,
+}
+
+impl Ptr {
+	pub fn new(alloc: usize, gen: u32) -> Self {
+		Self { alloc, gen, cell: 0 }
+	}
+
+	pub fn offset(self, cells: usize) -> Self {
+		Self { cell: self.cell + cells, ..self }
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+	Int(i64),
+	Float(f64),
+	Bool(bool),
+	// string literals live outside the checked memory, like llvm globals
+	Str(Rc<str>),
+	Ptr(Ptr),
+	// a struct loaded by value, one entry per cell
+	Struct(Vec<Option<Value>>),
+	Unit,
+}
+
+impl Value {
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Value::Int(_) => "int",
+			Value::Float(_) => "float",
+			Value::Bool(_) => "bool",
+			Value::Str(_) => "str",
+			Value::Ptr(_) => "pointer",
+			Value::Struct(_) => "struct",
+			Value::Unit => "unit",
+		}
+	}
+
+	pub fn as_ptr(&self) -> Option<Ptr> {
+		match self {
+			Value::Ptr(ptr) => Some(*ptr),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Value::Int(value) => write!(f, "{}", value),
+			Value::Float(value) => write!(f, "{}", value),
+			Value::Bool(value) => write!(f, "{}", value),
+			Value::Str(value) => write!(f, "{:?}", value),
+			Value::Ptr(ptr) => write!(f, "ptr #{}+{}", ptr.alloc, ptr.cell),
+			Value::Struct(cells) => {
+				write!(f, "{{ ")?;
+				for (index, cell) in cells.iter().enumerate() {
+					if index > 0 {
+						write!(f, ", ")?;
+					}
+					match cell {
+						Some(value) => write!(f, "{}", value)?,
+						None => write!(f, "uninit")?,
+					}
+				}
+				write!(f, " }}")
+			}
+			Value::Unit => write!(f, "()"),
+		}
+	}
+}
@@ -2,7 +2,7 @@
 mod value;
 use std::ops::{Rem, Sub};
 
-use crate::checker::types::TypeId;
+use crate::{checker::types::TypeId, loader::ModId, range::Range};
 pub use value::*;
 
 #[derive(Debug, Clone)]
@@ -194,7 +194,10 @@ impl CallInstr {
 #[derive(Debug, Clone)]
 pub struct IrBlock {
 	pub label: usize,
-	pub instrs: Vec<Instr>,
+	instrs: Vec<Instr>,
+	/// the source of each instr, `None` for the ones the builder adds on its own,
+	/// e.g. the drops at the end of a scope. kept in step with `instrs`.
+	ranges: Vec<Option<Range>>,
 }
 
 impl Default for IrBlock {
@@ -204,11 +207,50 @@ impl Default for IrBlock {
 }
 impl IrBlock {
 	pub fn new(label: usize) -> Self {
-		Self { label, instrs: Vec::new() }
+		Self { label, instrs: Vec::new(), ranges: Vec::new() }
 	}
 
 	pub fn append_instr(&mut self, instr: Instr) {
+		self.append_instr_with_range(instr, None);
+	}
+
+	pub fn append_instr_with_range(&mut self, instr: Instr, range: Option<Range>) {
 		self.instrs.push(instr);
+		self.ranges.push(range);
+	}
+
+	pub fn insert_instr(&mut self, index: usize, instr: Instr, range: Option<Range>) {
+		self.instrs.insert(index, instr);
+		self.ranges.insert(index, range);
+	}
+
+	pub fn remove_instr(&mut self, index: usize) -> Instr {
+		self.ranges.remove(index);
+		self.instrs.remove(index)
+	}
+
+	pub fn retain_instrs(&mut self, mut keep: impl FnMut(&Instr) -> bool) {
+		let mut index = 0;
+		while index < self.instrs.len() {
+			if keep(&self.instrs[index]) {
+				index += 1;
+			} else {
+				self.remove_instr(index);
+			}
+		}
+	}
+
+	pub fn instrs(&self) -> &[Instr] {
+		&self.instrs
+	}
+
+	// a slice can't grow or shrink, so the ranges stay in step
+	pub fn instrs_mut(&mut self) -> &mut [Instr] {
+		&mut self.instrs
+	}
+
+	pub fn instr_range(&self, index: usize) -> Option<Range> {
+		self.ranges.get(index).copied().flatten()
 	}
 
 	pub fn format_label(&self) -> String {
@@ -233,6 +275,8 @@ pub struct Function {
 	pub ret: TypeId,
 	pub args: Vec<IrBasicValue>,
 	pub blocks: Vec<IrBlock>,
+	/// the mod the function was built from, hand-written ir has none
+	pub mod_id: Option<ModId>,
 }
 
 impl Function {
@@ -245,6 +289,7 @@ impl Function {
 			ret,
 			variadic_args: false,
 			extern_function: false,
+			mod_id: None,
 		}
 	}
 
@@ -335,6 +380,8 @@ pub struct IR {
 	pub pathnname: String,
 	pub functions: Vec<Function>,
 	pub structs: Vec<Struct>,
+	/// zeroed on start, every function reaches one as a register pointing to it
+	pub globals: Vec<IrBind>,
 }
 
 impl Default for IR {
@@ -345,16 +392,24 @@ impl Default for IR {
 
 impl IR {
 	pub fn new(pathnname: String) -> Self {
-		Self { pathnname, functions: Vec::new(), structs: Vec::new() }
+		Self { pathnname, functions: Vec::new(), structs: Vec::new(), globals: Vec::new() }
 	}
 
 	pub fn add_function(&mut self, function: Function) {
 		self.functions.push(function);
 	}
 
+	pub fn has_function(&self, name: &str) -> bool {
+		self.functions.iter().any(|function| function.name == name)
+	}
+
 	pub fn add_struct(&mut self, struct_: Struct) {
 		self.structs.push(struct_);
 	}
+
+	pub fn add_global(&mut self, global: IrBind) {
+		self.globals.push(global);
+	}
 }
 
 impl From<CallInstr> for Instr {
@@ -227,12 +227,12 @@ pub enum Token {
 
 	#[token("...")]
 	DotDotDot,
-	// Comments //
-	#[regex(r"//[^\n]*", logos::skip)]
-	SkipLine,
+	// comments are kept, the parser collects them as trivia
+	#[regex(r"//[^\n]*")]
+	LineComment,
 	// block comment /*...*/
-	#[regex(r"/\*[^*]*\*+(?:[^/*][^*]*\*+)*/", logos::skip)]
-	SkipBlock,
+	#[regex(r"/\*[^*]*\*+(?:[^/*][^*]*\*+)*/")]
+	BlockComment,
 }
 
 // display
@@ -304,8 +304,8 @@ impl fmt::Display for Token {
 			Token::Decimal => write!(f, "decimal"),
 			Token::True => write!(f, "true"),
 			Token::False => write!(f, "false"),
-			Token::SkipLine => write!(f, "skip line"),
-			Token::SkipBlock => write!(f, "skip block"),
+			Token::LineComment => write!(f, "line comment"),
+			Token::BlockComment => write!(f, "block comment"),
 			Token::UsizeType => write!(f, "usize"),
 			Token::IsizeType => write!(f, "isize"),
 			Token::BoolType => write!(f, "bool"),
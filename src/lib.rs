@@ -1,14 +1,25 @@
+pub mod assembler;
 pub mod ast;
+pub mod bench_report;
 pub mod builder;
 pub mod checker;
+pub mod disassembler;
+pub mod explain;
 pub mod file_system;
+pub mod fmt;
+pub mod interp;
 pub mod ir;
 pub mod lexer;
+pub mod lints;
 pub mod loader;
+pub mod lsp;
 pub mod message;
 pub mod optimize;
 pub mod parser;
 pub mod range;
 pub mod report;
+pub mod session;
 pub mod shio;
 pub mod source;
+pub mod time;
+pub mod verifier;
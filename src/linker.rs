@@ -6,6 +6,9 @@ pub struct Linker {
 	pub input: PathBuf,
 	pub output: PathBuf,
 	bin_path: PathBuf,
+	/// "mold" or "lld", clang's own linker if none
+	fuse_ld: Option<String>,
+	target: Option<String>,
 }
 
 impl Linker {
@@ -15,29 +18,50 @@ impl Linker {
 			Err(_) => throw_error!("not found 'clang' binary"),
 		};
 		let output = input.with_extension("");
-		Self { input, output, bin_path: PathBuf::from(bin_path) }
+		Self { input, output, bin_path: PathBuf::from(bin_path), fuse_ld: None, target: None }
+	}
+
+	pub fn linker(&mut self, linker: &str) -> &mut Self {
+		self.fuse_ld = match linker {
+			"clang" => None,
+			linker => Some(linker.to_string()),
+		};
+		self
+	}
+
+	pub fn target(&mut self, target: &str) -> &mut Self {
+		self.target = Some(target.to_string());
+		self
 	}
 
 	pub fn link(&self) -> String {
+		self.try_link().unwrap_or_else(|text| throw_error!("{}", text))
+	}
+
+	/// links the object into a binary and returns its path, or why it failed.
+	pub fn try_link(&self) -> Result<String, String> {
 		let mut command = Command::new(&self.bin_path);
 		let input = self.input.to_str().unwrap();
 		let output_path = self.output.to_str().unwrap();
 		command.arg(input).arg("-o").arg(output_path);
-		let output = match command.output() {
-			Ok(output) => output,
-			Err(err) => throw_error!("failed to link: {}", err),
-		};
+		if let Some(fuse_ld) = &self.fuse_ld {
+			command.arg(format!("-fuse-ld={}", fuse_ld));
+		}
+		if let Some(target) = &self.target {
+			command.arg("-target").arg(target);
+		}
+		let output = command.output().map_err(|err| format!("failed to link: {}", err))?;
 		if !output.status.success() {
 			let string = String::from_utf8_lossy(&output.stderr);
 			//
 			if string.contains("symbol(s) not found") {
 				// assume that is not found main function
-				throw_error!("not found main function");
+				return Err("not found main function".to_owned());
 			}
-			throw_error!("{}", string);
+			return Err(string.to_string());
 		}
 		// remove object file
-		std::fs::remove_file(input).unwrap_or_else(|err| throw_error!("{}", err));
-		output_path.to_string()
+		std::fs::remove_file(input).map_err(|err| err.to_string())?;
+		Ok(output_path.to_string())
 	}
 }
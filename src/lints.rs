@@ -0,0 +1,103 @@
+use crate::message::Severity;
+use rustc_hash::FxHashMap;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Lint {
+	UnusedVariable,
+	UnusedBorrow,
+	UnreachableCode,
+	ShadowedBinding,
+}
+
+impl Lint {
+	pub const ALL: [Lint; 4] =
+		[Lint::UnusedVariable, Lint::UnusedBorrow, Lint::UnreachableCode, Lint::ShadowedBinding];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Lint::UnusedVariable => "unused_variable",
+			Lint::UnusedBorrow => "unused_borrow",
+			Lint::UnreachableCode => "unreachable_code",
+			Lint::ShadowedBinding => "shadowed_binding",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|lint| lint.name() == name)
+	}
+
+	pub fn default_level(self) -> Severity {
+		match self {
+			Lint::ShadowedBinding => Severity::Allow,
+			_ => Severity::Warning,
+		}
+	}
+}
+
+impl Display for Lint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.name())
+	}
+}
+
+/// parses a lint level, e.g. "warn" in `[lints] unused_variable = "warn"`.
+pub fn parse_level(level: &str) -> Option<Severity> {
+	match level {
+		"allow" => Some(Severity::Allow),
+		"warn" => Some(Severity::Warning),
+		"deny" => Some(Severity::Error),
+		_ => None,
+	}
+}
+
+/// levels of `-W`, `-D` and `-A` flags given with their position, in command line order,
+/// e.g. `-D unused_variable -A unused_variable` ends with allow.
+pub fn from_flags<'a>(
+	flags: impl IntoIterator<Item = (usize, &'a str, Severity)>,
+) -> Result<Vec<(Lint, Severity)>, String> {
+	let mut flags: Vec<_> = flags.into_iter().collect();
+	flags.sort_by_key(|(index, ..)| *index);
+	let into_level = |(_, name, level)| match Lint::from_name(name) {
+		Some(lint) => Ok((lint, level)),
+		None => Err(format!("unknown lint '{}'", name)),
+	};
+	flags.into_iter().map(into_level).collect()
+}
+
+/// levels of every lint, from lowest to highest precedence:
+/// defaults, shio.toml `[lints]`, `-W`/`-D`/`-A` flags and item attributes.
+#[derive(Debug, Clone, Default)]
+pub struct LintLevels {
+	levels: FxHashMap<Lint, Severity>,
+	/// `[loader] strict`, every warning is an error
+	strict: bool,
+}
+
+impl LintLevels {
+	pub fn new(strict: bool) -> Self {
+		Self { levels: FxHashMap::default(), strict }
+	}
+
+	pub fn set(&mut self, lint: Lint, level: Severity) {
+		self.levels.insert(lint, level);
+	}
+
+	pub fn extend(&mut self, levels: &[(Lint, Severity)]) {
+		for (lint, level) in levels {
+			self.set(*lint, *level);
+		}
+	}
+
+	pub fn level(&self, lint: Lint) -> Severity {
+		let level = self.levels.get(&lint).copied().unwrap_or(lint.default_level());
+		match level {
+			Severity::Warning if self.strict => Severity::Error,
+			level => level,
+		}
+	}
+
+	pub fn is_strict(&self) -> bool {
+		self.strict
+	}
+}
@@ -10,15 +10,25 @@ mod scope;
 
 pub struct Env<'ll> {
 	pub function_scope: Vec<Scope<'ll>>,
+	// bound in every function scope
+	pub globals: Vec<(String, BasicValueEnum<'ll>)>,
 }
 
 impl<'ll> Env<'ll> {
 	pub fn new() -> Self {
-		Self { function_scope: Vec::new() }
+		Self { function_scope: Vec::new(), globals: Vec::new() }
 	}
 
 	pub fn enter_function_scope(&mut self) {
-		self.function_scope.push(Scope::new());
+		let mut scope = Scope::new();
+		for (name, value) in &self.globals {
+			scope.set_value(name, *value);
+		}
+		self.function_scope.push(scope);
+	}
+
+	pub fn set_global(&mut self, name: &str, value: BasicValueEnum<'ll>) {
+		self.globals.push((name.to_owned(), value));
 	}
 
 	pub fn exit_function_scope(&mut self) {
@@ -11,7 +11,7 @@ impl<'ll> Llvm<'ll> {
 		};
 		self.builder.position_at_end(*llvm_block);
 
-		block.instrs.iter().for_each(|instr| {
+		block.instrs().iter().for_each(|instr| {
 			self.llvm_compile_instr(instr);
 		});
 	}
@@ -32,7 +32,14 @@ impl<'ll> Llvm<'ll> {
 		self.env.exit_function_scope();
 	}
 
+	pub fn llvm_declare_function(&mut self, function: &ir::Function) {
+		self.create_llvm_function_value(function);
+	}
+
 	fn create_llvm_function_value(&mut self, function: &ir::Function) -> FunctionValue<'ll> {
+		if let Some(function_value) = self.module.get_function(function.name.as_str()) {
+			return function_value;
+		}
 		let function_type = self.create_llvm_function_type(function);
 		let linkage = None;
 		self.module.add_function(function.name.as_str(), function_type, linkage)
@@ -71,6 +78,11 @@ impl<'ll> Llvm<'ll> {
 	}
 	#[inline(always)]
 	fn build_llvm_return_function(&mut self, function: &ir::Function) {
+		// the last block already returns, e.g. with `ret`
+		let last_block = self.builder.get_insert_block();
+		if last_block.is_some_and(|block| block.get_terminator().is_some()) {
+			return;
+		}
 		if function.is_main() {
 			let sucess = self.ctx.i32_type().const_int(0, false);
 			if let Err(err) = self.builder.build_return(Some(&sucess)) {
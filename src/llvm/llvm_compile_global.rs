@@ -0,0 +1,12 @@
+use crate::ir;
+
+use super::Llvm;
+
+impl Llvm<'_> {
+	pub fn llvm_compile_global(&mut self, global: &ir::IrBind) {
+		let basic_type = self.compile_type_to_basic_type(global.kind);
+		let value = self.module.add_global(basic_type, None, &global.name);
+		value.set_initializer(&basic_type.const_zero());
+		self.env.set_global(&global.name, value.as_pointer_value().into());
+	}
+}
@@ -1,23 +1,14 @@
+use inkwell::types::BasicTypeEnum;
+
 use crate::{error_codegen, ir};
 
 use super::Llvm;
 
 impl Llvm<'_> {
 	pub fn llvm_compile_ret(&mut self, ret_value: &Option<ir::IrBasicValue>) {
-		if ret_value.is_none() {
-			match self.builder.build_return(None) {
-				Ok(_) => {}
-				Err(err) => error_codegen!("while return void, resson '{}'", err).report(self.loader),
-			}
-			return;
-		}
-		let ret_value = ret_value.as_ref().unwrap();
-		if ret_value.type_id.is_empty_type() {
-			self.builder.build_return(None).unwrap_or_else(|err| {
-				error_codegen!("while return void, resson '{}'", err).report(self.loader)
-			});
-			return;
-		}
+		let Some(ret_value) = ret_value.as_ref().filter(|value| !value.type_id.is_empty_type()) else {
+			return self.llvm_compile_void_ret();
+		};
 
 		let value = self.llvm_compile_value(ret_value);
 		self.builder.build_return(Some(&value)).unwrap_or_else(|err| {
@@ -25,4 +16,19 @@ impl Llvm<'_> {
 			message.report(self.loader);
 		});
 	}
+
+	/// `ret` without a value, `main` is built returning an exit code, 0 then.
+	fn llvm_compile_void_ret(&mut self) {
+		let function = self.builder.get_insert_block().and_then(|block| block.get_parent());
+		let ret_type = function.and_then(|function| function.get_type().get_return_type());
+		let result = match ret_type {
+			Some(BasicTypeEnum::IntType(int_type)) => {
+				self.builder.build_return(Some(&int_type.const_zero()))
+			}
+			_ => self.builder.build_return(None),
+		};
+		result.unwrap_or_else(|err| {
+			error_codegen!("while return void, resson '{}'", err).report(self.loader)
+		});
+	}
 }
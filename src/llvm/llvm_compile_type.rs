@@ -31,7 +31,7 @@ impl<'ll> Llvm<'ll> {
 			TypeId::F64    => Some(self.ctx.f64_type().into()),
 			TypeId::BOOL   => Some(self.ctx.bool_type().into()),
 			found => {
-				let struct_name = self.type_store.lookup_struct_name(found)?;
+				let struct_name = self.type_store.lookup_struct_symbol(found)?;
 				self.ctx.get_struct_type(struct_name).map(|t| t.into())
 			}
 		}
@@ -39,6 +39,7 @@ mod llvm_compile_jmp_if;
 // mem
 mod llvm_compile_drop;
 mod llvm_compile_getptr;
+mod llvm_compile_global;
 mod llvm_compile_halloc;
 mod llvm_compile_load;
 mod llvm_compile_ret;
@@ -88,6 +89,19 @@ impl<'ll> Llvm<'ll> {
 			self.llvm_compile_struct(struct_def);
 		});
 
+		// globals
+		//
+		root.globals.iter().for_each(|global| {
+			self.llvm_compile_global(global);
+		});
+
+		// declare every function first, so calls don't depend on the definition order
+		// e.g. a mod calling into a mod that was built after it
+		//
+		root.functions.iter().for_each(|function| {
+			self.llvm_declare_function(function);
+		});
+
 		// functions
 		//
 		root.functions.iter().for_each(|function| {
@@ -1,25 +1,65 @@
 #![allow(dead_code)]
 mod mod_id;
+mod versions;
 use std::path::{Path, PathBuf};
 
 pub use mod_id::*;
 use rustc_hash::FxHashMap;
+pub use versions::*;
 
 use crate::{
-	ast, error_resolve, file_system::FileSystem, message::MessageResult, shio::ShioConfig,
+	ast,
+	checker::diags::suggest::similar_name,
+	error_resolve,
+	file_system::FileSystem,
+	lints::{Lint, LintLevels},
+	message::{Message, MessageResult, RootMessages, Severity},
+	note,
+	report::print_messages,
+	shio::{LockedDependency, ShioConfig, ShioLock},
 	source::Source,
 };
 
+/// a dependency resolved through `import("name")`.
+pub struct Dependency {
+	pub name: String,
+	pub dir: PathBuf,
+	pub version: String,
+}
+
+impl Dependency {
+	pub fn new(name: String, dir: PathBuf, version: String) -> Self {
+		Self { name, dir, version }
+	}
+}
+
 pub struct Loader {
 	shio: ShioConfig,
 	file_system: FileSystem,
 	root: FxHashMap<ModId, Source>,
 	mods: FxHashMap<ModId, ast::Program>,
+	/// mods being parsed, each one imported by the one before it
+	parsing: Vec<ModId>,
+	dependencies: Vec<Dependency>,
+	requirements: Vec<Requirement>,
+	messages: RootMessages,
+	lints: LintLevels,
 }
 
 impl Loader {
-	pub fn new(shio: ShioConfig, file_system: FileSystem) -> Self {
-		Self { shio, file_system, root: FxHashMap::default(), mods: FxHashMap::default() }
+	pub fn new(shio: ShioConfig, mut file_system: FileSystem) -> Self {
+		if let Some(registry) = &shio.registry {
+			file_system.remote_dir = registry.clone();
+		}
+		let requirements = Self::register_dependencies(&mut file_system, &shio);
+		let root = FxHashMap::default();
+		let mods = FxHashMap::default();
+		let messages = RootMessages::new();
+		let mut lints = LintLevels::new(shio.loader.strict);
+		lints.extend(&shio.lints.levels);
+		let dependencies = Vec::new();
+		let parsing = Vec::new();
+		Self { shio, file_system, root, mods, parsing, dependencies, requirements, messages, lints }
 	}
 
 	pub fn load_entry(&mut self) -> MessageResult<ModId> {
@@ -29,13 +69,180 @@ impl Loader {
 		Ok(self.register_source(source))
 	}
 
+	/// registers the sources of the package that aren't loaded yet, the ones nothing
+	/// imports, e.g. a mod of tests. dependencies inside the package are left out.
+	pub fn load_package_mods(&mut self) -> MessageResult<Vec<ModId>> {
+		let root = std::fs::canonicalize(&self.shio.root).unwrap_or(self.shio.root.clone());
+		let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or(path.to_path_buf());
+		let loaded: Vec<PathBuf> =
+			self.root.values().map(|source| canonical(&source.abs_path)).collect();
+		let mut mod_ids = Vec::new();
+		for path in FileSystem::package_sources(&root)? {
+			let path = canonical(&path);
+			let in_dependency =
+				self.dependencies.iter().any(|dependency| path.starts_with(&dependency.dir));
+			if loaded.contains(&path) || in_dependency {
+				continue;
+			}
+			let pathname = path.strip_prefix(&root).unwrap_or(&path).display().to_string();
+			let (raw, abs_path) = self.file_system.load_mod_entry(&path.display().to_string())?;
+			mod_ids.push(self.register_source(Source::new(raw, abs_path, pathname)));
+		}
+		Ok(mod_ids)
+	}
+
 	pub fn load_source(&mut self, path: &str, base_mod_id: ModId) -> MessageResult<ModId> {
-		let mod_path = self.lookup_source_unchecked(base_mod_id).abs_path.clone();
-		let (raw, abs_mod_path) = self.file_system.load_mod_from_base(mod_path, path)?;
+		if Self::is_dependency_name(path) {
+			return self.load_dependency(path, base_mod_id);
+		}
+		self.load_source_from_base(path, base_mod_id)
+	}
+
+	fn load_source_from_base(&mut self, path: &str, base_mod_id: ModId) -> MessageResult<ModId> {
+		let mod_path = self.lookup_source_result(base_mod_id)?.abs_path.clone();
+		let (raw, abs_mod_path) = match self.file_system.load_mod_from_base(mod_path, path) {
+			Ok(loaded) => loaded,
+			Err(message) => return Err(self.suggest_mod(message, path, base_mod_id)),
+		};
+		if let Some(mod_id) = self.lookup_mod_id_by_path(&abs_mod_path) {
+			return Ok(mod_id);
+		}
 		let path = self.resolve_path(path);
 		let source = Source::new(raw, abs_mod_path, path.display().to_string());
 		Ok(self.register_source(source))
 	}
+
+	/// loads a bare `import("name")`, searching the shio.toml local paths, then the
+	/// dependency dir (e.g., ~/.shio/deps/name), then a mod next to the importer (e.g.,
+	/// name.ln), then the registry. a registry that can't be read only fails the last step.
+	fn load_dependency(&mut self, name: &str, base_mod_id: ModId) -> MessageResult<ModId> {
+		let mut searched = match self.file_system.find_dependency_dir(name) {
+			Ok(dependency_dir) => return self.load_dependency_entry(name, &dependency_dir),
+			Err(searched) => searched,
+		};
+		let base_path = &self.lookup_source_result(base_mod_id)?.abs_path;
+		for local in [name.to_string(), format!("{}.ln", name)] {
+			let local_path = FileSystem::resolve_mod_path(base_path, &local);
+			if local_path.is_file() {
+				return self.load_source_from_base(&local, base_mod_id);
+			}
+			searched.push(local_path);
+		}
+		if let Some(dependency_dir) = self.find_in_registry(name)? {
+			return self.load_dependency_entry(name, &dependency_dir);
+		}
+		searched.push(self.file_system.remote_dir.join("index.toml"));
+		let mut message = error_resolve!("dependency '{}' not found", name).code(602);
+		for path in searched {
+			message = message.note(note!("searched '{}'", path.display()));
+		}
+		Err(self.suggest_mod(message, name, base_mod_id))
+	}
+
+	/// adds a "did you mean" note for a mod that is not found, e.g. `./mth.ln` → `./math.ln`,
+	/// from the dependencies and the mods in the dir the import points to.
+	pub fn suggest_mod(&self, message: Message, path: &str, base_mod_id: ModId) -> Message {
+		let Ok(base) = self.lookup_source_result(base_mod_id) else { return message };
+		let base_path = &base.abs_path;
+		let base_dir = base_path.parent().unwrap_or(Path::new("."));
+		let (dir, file_name) = path.rsplit_once('/').unwrap_or(("", path));
+		let mods_dir = base_dir.join(dir);
+		let mods_dir = std::fs::canonicalize(&mods_dir).unwrap_or(mods_dir);
+		let mut candidates = FileSystem::mods_in_dir(&mods_dir, base_path);
+		if Self::is_dependency_name(path) {
+			// a bare name, so compare without the `.ln`
+			for candidate in candidates.iter_mut() {
+				if let Some(stem) = candidate.strip_suffix(".ln") {
+					*candidate = stem.to_string();
+				}
+			}
+			let dependencies = &self.shio.dependencies;
+			candidates.extend(dependencies.iter_local().map(|(name, _)| name.clone()));
+			candidates.extend(dependencies.iter_versions().map(|(name, _)| name.clone()));
+			candidates.extend(self.file_system.workspace_members.keys().cloned());
+		}
+		let Some(similar) = similar_name(file_name, candidates.iter().map(String::as_str)) else {
+			return message;
+		};
+		match dir.is_empty() {
+			true => message.note(note!("did you mean '{}'?", similar)),
+			false => message.note(note!("did you mean '{}/{}'?", dir, similar)),
+		}
+	}
+
+	/// loads the entry of a dependency, given by the `main` of its own shio.toml.
+	fn load_dependency_entry(&mut self, name: &str, dependency_dir: &Path) -> MessageResult<ModId> {
+		let shio = ShioConfig::load_from_dir(dependency_dir)
+			.map_err(|err| error_resolve!("dependency '{}', {}", name, err).code(603))?;
+		let pathname = shio.loader.main.display().to_string();
+		let (raw, abs_path) = self.file_system.load_mod_entry(&pathname)?;
+		if let Some(mod_id) = self.lookup_mod_id_by_path(&abs_path) {
+			return Ok(mod_id);
+		}
+		let requirements = Self::register_dependencies(&mut self.file_system, &shio);
+		self.requirements.extend(requirements);
+		let dependency_dir =
+			std::fs::canonicalize(dependency_dir).unwrap_or(dependency_dir.to_path_buf());
+		self.dependencies.push(Dependency::new(name.to_string(), dependency_dir, shio.package.version));
+		let source = Source::new(raw, abs_path, pathname);
+		Ok(self.register_source(source))
+	}
+
+	/// checks the fetched dependencies against shio.lock and writes it back, path
+	/// dependencies and workspace members are edited in place so they aren't locked.
+	/// a hash that no longer matches is an error, unless `update` is set.
+	pub fn sync_lock(&self, update: bool) -> MessageResult<()> {
+		// a single file has no shio.toml, so no lock either
+		if !self.shio.root.join("shio.toml").is_file() {
+			return Ok(());
+		}
+		let lock_path = self.shio.root.join("shio.lock");
+		let locked = ShioLock::load(&lock_path).map_err(|err| error_resolve!("{}", err).code(605))?;
+		let mut dependencies = Vec::with_capacity(self.dependencies.len());
+		let fetched =
+			|dependency: &&Dependency| self.file_system.is_fetched_dependency(&dependency.dir);
+		for dependency in self.dependencies.iter().filter(fetched) {
+			let hash = FileSystem::hash_sources(&dependency.dir)?;
+			if let Some(locked) = locked.get(&dependency.name) {
+				if locked.hash != hash && !update {
+					let message =
+						error_resolve!("dependency '{}' changed since it was locked", dependency.name)
+							.code(604);
+					let message = message.note(note!("locked '{}', found '{}'", locked.hash, hash));
+					return Err(message.note(note!("use `--update-lock` to accept the change")));
+				}
+			}
+			let path = dependency.dir.display().to_string();
+			let version = dependency.version.clone();
+			dependencies.push(LockedDependency::new(dependency.name.clone(), path, version, hash));
+		}
+		let lock = ShioLock::new(dependencies);
+		if lock != locked {
+			lock.save(&lock_path).map_err(|err| error_resolve!("{}", err).code(605))?;
+		}
+		Ok(())
+	}
+
+	/// maps the dependencies of a package and returns its version requirements.
+	fn register_dependencies(file_system: &mut FileSystem, shio: &ShioConfig) -> Vec<Requirement> {
+		for (name, path) in shio.dependencies.iter_local() {
+			file_system.add_custom_dependency(name.clone(), shio.root.join(path));
+		}
+		let package = &shio.package.name;
+		let into_requirement = |(name, version): (&String, &String)| {
+			Requirement::new(name.clone(), package.clone(), version.clone())
+		};
+		shio.dependencies.iter_versions().map(into_requirement).collect()
+	}
+
+	/// a bare name, e.g. `import("math")`, rather than a path like `import("./math.ln")`.
+	fn is_dependency_name(path: &str) -> bool {
+		let given_path = Path::new(path);
+		given_path.extension().is_none()
+			&& given_path.components().count() == 1
+			&& !path.starts_with('.')
+	}
+
 	fn register_source(&mut self, source: Source) -> ModId {
 		let mod_id = ModId::new(self.root.len() as u64);
 		self.root.insert(mod_id, source);
@@ -50,6 +257,85 @@ impl Loader {
 		given_path.to_path_buf()
 	}
 
+	/// records a message, to be reported with the others by `report_messages`.
+	pub fn add_message(&mut self, message: Message) {
+		self.messages.message(message);
+	}
+
+	pub fn lints(&self) -> &LintLevels {
+		&self.lints
+	}
+
+	/// overrides the shio.toml lint levels, e.g. with `-D unused_variable`.
+	pub fn extend_lints(&mut self, levels: &[(Lint, Severity)]) {
+		self.lints.extend(levels);
+	}
+
+	/// the messages recorded so far, without reporting them.
+	pub fn take_messages(&mut self) -> RootMessages {
+		std::mem::take(&mut self.messages)
+	}
+
+	pub fn any_errors(&self) -> bool {
+		self.messages.any_errors()
+	}
+
+	/// prints the messages recorded so far, exiting if any of them is an error.
+	pub fn report_messages(&mut self) {
+		let mut messages = std::mem::take(&mut self.messages);
+		print_messages(&mut messages, self);
+		if let Some(exit_code) = messages.exit_code() {
+			std::process::exit(exit_code);
+		}
+	}
+
+	pub fn lookup_mod_id_by_path(&self, abs_path: &Path) -> Option<ModId> {
+		self.root.iter().find(|(_, source)| source.abs_path == abs_path).map(|(mod_id, _)| *mod_id)
+	}
+
+	/// all registered mods, ordered by registration.
+	pub fn mod_ids(&self) -> Vec<ModId> {
+		let mut mod_ids: Vec<ModId> = self.root.keys().copied().collect();
+		mod_ids.sort_by_key(|mod_id| u64::from(*mod_id));
+		mod_ids
+	}
+
+	/// a mod loaded from inside a dependency's dir, not part of the package itself.
+	pub fn is_dependency_mod(&self, mod_id: ModId) -> bool {
+		let Some(source) = self.root.get(&mod_id) else {
+			return false;
+		};
+		self.dependencies.iter().any(|dependency| source.abs_path.starts_with(&dependency.dir))
+	}
+
+	pub fn is_parsed(&self, mod_id: ModId) -> bool {
+		self.mods.contains_key(&mod_id)
+	}
+
+	/// marks a mod as being parsed, an import of it before `finish_parsing` is a cycle.
+	pub fn start_parsing(&mut self, mod_id: ModId) {
+		self.parsing.push(mod_id);
+	}
+
+	pub fn finish_parsing(&mut self, mod_id: ModId) {
+		self.parsing.retain(|parsing| *parsing != mod_id);
+	}
+
+	/// errors if `mod_id` is still being parsed, e.g. `a.ln` imports `b.ln` which imports `a.ln`.
+	pub fn check_import_cycle(&self, mod_id: ModId) -> MessageResult<()> {
+		let Some(start) = self.parsing.iter().position(|parsing| *parsing == mod_id) else {
+			return Ok(());
+		};
+		let pathname = |mod_id: &ModId| self.lookup_source_unchecked(*mod_id).pathname.clone();
+		let mut message = error_resolve!("import cycle, '{}' imports itself", pathname(&mod_id));
+		message = message.code(610);
+		let cycle = &self.parsing[start..];
+		for (importer, imported) in cycle.iter().zip(cycle.iter().skip(1).chain(Some(&mod_id))) {
+			message = message.note(note!("'{}' imports '{}'", pathname(importer), pathname(imported)));
+		}
+		Err(message)
+	}
+
 	pub fn lookup_source(&self, mod_id: ModId) -> Option<&Source> {
 		self.root.get(&mod_id)
 	}
@@ -65,6 +351,13 @@ impl Loader {
 		}
 	}
 
+	/// swaps the text of a registered mod, e.g. the growing buffer of `lemon repl`.
+	pub fn replace_source(&mut self, mod_id: ModId, raw: String) {
+		if let Some(source) = self.root.get_mut(&mod_id) {
+			*source = Source::new(raw, source.abs_path.clone(), source.pathname.clone());
+		}
+	}
+
 	pub fn add_mod(&mut self, mod_id: ModId, ast: ast::Program) {
 		self.mods.insert(mod_id, ast);
 	}
@@ -2,13 +2,18 @@ use std::fmt::{Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ModId(u64);
 
 impl ModId {
 	pub fn new(id: u64) -> Self {
 		Self(id)
 	}
+
+	/// mangles a symbol name so items from different mods don't clash.
+	pub fn mangle(&self, name: &str) -> String {
+		format!("mod{}.{}", self.0, name)
+	}
 }
 
 impl Default for ModId {
@@ -0,0 +1,132 @@
+use semver::{Version, VersionReq};
+use std::path::PathBuf;
+
+use super::{Dependency, Loader};
+use crate::{
+	error_resolve,
+	file_system::FileSystem,
+	message::{Message, MessageResult},
+	note,
+	shio::ShioRegistry,
+};
+
+/// a version requirement a package puts on a dependency, e.g. `math = { version = "^1.2" }`.
+pub struct Requirement {
+	pub dependency: String,
+	/// the package asking for it
+	pub package: String,
+	pub version: String,
+}
+
+impl Requirement {
+	pub fn new(dependency: String, package: String, version: String) -> Self {
+		Self { dependency, package, version }
+	}
+
+	pub fn parse(&self) -> MessageResult<VersionReq> {
+		VersionReq::parse(&self.version).map_err(|_| {
+			let message = error_resolve!(
+				"invalid version requirement '{}' in package '{}'",
+				self.version,
+				self.package
+			);
+			message.code(606)
+		})
+	}
+}
+
+impl Loader {
+	/// checks every resolved dependency against the versions its packages asked for.
+	pub fn check_versions(&self) -> MessageResult<()> {
+		for dependency in self.dependencies.iter() {
+			let requirements: Vec<&Requirement> = self.lookup_requirements(&dependency.name).collect();
+			if requirements.is_empty() {
+				continue;
+			}
+			let version = Version::parse(&dependency.version).map_err(|_| {
+				error_resolve!(
+					"invalid version '{}' of dependency '{}'",
+					dependency.version,
+					dependency.name
+				)
+				.code(606)
+			})?;
+			let mut incompatible = Vec::new();
+			for requirement in requirements.iter() {
+				let version_req = requirement.parse()?;
+				if !version_req.matches(&version) {
+					incompatible.push(*requirement);
+				}
+			}
+			if incompatible.is_empty() {
+				continue;
+			}
+			return Err(self.version_mismatch(dependency, &requirements, &incompatible));
+		}
+		Ok(())
+	}
+
+	fn version_mismatch(
+		&self,
+		dependency: &Dependency,
+		requirements: &[&Requirement],
+		incompatible: &[&Requirement],
+	) -> Message {
+		let found =
+			note!("found '{}' {} in '{}'", dependency.name, dependency.version, dependency.dir.display());
+		// one package asking for something else than what's there
+		if requirements.len() == 1 {
+			let requirement = incompatible[0];
+			let (name, version) = (&dependency.name, &dependency.version);
+			let message = error_resolve!(
+				"dependency '{}' {} does not match '{}'",
+				name,
+				version,
+				requirement.version
+			)
+			.code(607);
+			let message = message.note(note!("required by package '{}'", requirement.package));
+			return message.note(found);
+		}
+		// packages asking for versions that can't all be met by the one resolved
+		let mut message =
+			error_resolve!("conflicting versions of dependency '{}'", dependency.name).code(608);
+		for requirement in requirements.iter() {
+			let status =
+				if incompatible.iter().any(|r| std::ptr::eq(*r, *requirement)) { ", not met" } else { "" };
+			let text =
+				note!("package '{}' requires '{}'{}", requirement.package, requirement.version, status);
+			message = message.note(text);
+		}
+		message.note(found)
+	}
+
+	/// finds the highest registry version that meets every requirement on a dependency,
+	/// its sources have to match the hash they were published with.
+	pub(super) fn find_in_registry(&self, name: &str) -> MessageResult<Option<PathBuf>> {
+		let remote_dir = &self.file_system.remote_dir;
+		let registry =
+			ShioRegistry::open(remote_dir).map_err(|err| error_resolve!("{}", err).code(609))?;
+		let mut version_reqs = Vec::new();
+		for requirement in self.lookup_requirements(name) {
+			let version_req = requirement.parse()?;
+			version_reqs.push(version_req);
+		}
+		let Some(entry) = registry.find(name, &version_reqs) else {
+			return Ok(None);
+		};
+		let package_dir = registry.package_dir(entry);
+		let hash = FileSystem::hash_sources(&package_dir)?;
+		if hash != entry.hash {
+			let message =
+				error_resolve!("'{}' {} changed since it was published", name, entry.version).code(611);
+			let message = message.note(note!("published '{}', found '{}'", entry.hash, hash));
+			return Err(message.note(note!("publish it again as a new version")));
+		}
+		Ok(Some(package_dir))
+	}
+
+	fn lookup_requirements<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Requirement> {
+		self.requirements.iter().filter(move |requirement| requirement.dependency == name)
+	}
+}
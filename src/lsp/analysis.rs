@@ -0,0 +1,80 @@
+use rustc_hash::FxHashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::{
+	checker::{context::Context, types::TypeId, Checker},
+	file_system::FileSystem,
+	loader::{Loader, ModId},
+	message::{Message, RootMessages},
+	parser::try_parse_mod,
+	shio::ShioConfig,
+	source::Source,
+};
+
+/// a file checked as an entry, with the open buffers in place of the files on disk.
+pub struct Analysis {
+	pub loader: Loader,
+	pub ctx: Context,
+	pub messages: RootMessages,
+}
+
+impl Analysis {
+	pub fn check(path: &Path, buffers: &FxHashMap<PathBuf, String>) -> Self {
+		let shio = Self::package_of(path);
+		let mut file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+		file_system.in_memory_cache.extend(buffers.clone());
+		let mut loader = Loader::new(shio, file_system);
+		let mut ctx = Context::new();
+		let mod_id = match loader.load_entry() {
+			Ok(mod_id) => mod_id,
+			Err(message) => {
+				loader.add_message(message);
+				let messages = loader.take_messages();
+				return Self { loader, ctx, messages };
+			}
+		};
+		if let Err(message) = try_parse_mod(mod_id, &mut loader) {
+			loader.add_message(message.mod_id(mod_id));
+		}
+		// like `lemon check`, a mod with syntax errors is not type checked
+		if !loader.any_errors() {
+			let mut checker = Checker::new(&mut ctx, &mut loader);
+			// a last resort for checker bugs, though the release profile has
+			// `panic = "abort"`, so this only catches them in debug builds
+			let checked = panic::catch_unwind(AssertUnwindSafe(|| checker.check_messages(mod_id)));
+			if checked.is_err() {
+				let message = Message::error("internal error: the checker crashed on this file");
+				loader.add_message(message.mod_id(mod_id));
+			}
+		}
+		let messages = loader.take_messages();
+		Self { loader, ctx, messages }
+	}
+
+	/// the file's package, if it has a shio.toml, checked from the file rather than its `main`.
+	fn package_of(path: &Path) -> ShioConfig {
+		let package_dir = path.ancestors().skip(1).find(|dir| dir.join("shio.toml").is_file());
+		match package_dir.map(ShioConfig::load_from_dir) {
+			Some(Ok(mut shio)) => {
+				shio.loader.main = path.to_path_buf();
+				shio
+			}
+			_ => ShioConfig::with_defaults(path.to_path_buf()),
+		}
+	}
+
+	pub fn mod_id(&self, path: &Path) -> Option<ModId> {
+		self.loader.lookup_mod_id_by_path(path)
+	}
+
+	pub fn source(&self, mod_id: ModId) -> Option<&Source> {
+		self.loader.lookup_source(mod_id)
+	}
+
+	pub fn display_type(&self, type_id: TypeId) -> String {
+		let mut text = String::new();
+		type_id.display_type(&mut text, &self.ctx.type_store, false);
+		text
+	}
+}
@@ -0,0 +1,354 @@
+// `lemon lsp`, a language server over stdin/stdout.
+mod analysis;
+mod position;
+mod transport;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+use std::{
+	fs,
+	io::{self, BufRead, Write},
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	checker::types::{Type, TypeId},
+	message::{Message, Severity, EXIT_FAILURE},
+	range::Range,
+	source::Source,
+};
+use analysis::Analysis;
+use position::{offset_at, path_to_uri, range_of, uri_to_path};
+
+// json-rpc error codes
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// lsp enums
+const SYNC_FULL: u64 = 1;
+const COMPLETION_METHOD: u64 = 2;
+const COMPLETION_FUNCTION: u64 = 3;
+const COMPLETION_FIELD: u64 = 5;
+const COMPLETION_VARIABLE: u64 = 6;
+
+pub fn run() -> i32 {
+	let mut input = io::stdin().lock();
+	let mut output = io::stdout().lock();
+	serve(&mut input, &mut output)
+}
+
+/// serves requests until `exit`, returning the exit code.
+pub fn serve(input: &mut impl BufRead, output: &mut impl Write) -> i32 {
+	let mut server = Server::default();
+	loop {
+		let message = match transport::read_message(input) {
+			Ok(Some(message)) => message,
+			// the client is gone without an `exit`
+			Ok(None) => return EXIT_FAILURE,
+			Err(err) => {
+				eprintln!("lemon lsp: {}", err);
+				return EXIT_FAILURE;
+			}
+		};
+		if message["method"] == "exit" {
+			return if server.shutdown { 0 } else { EXIT_FAILURE };
+		}
+		for outgoing in server.handle(&message) {
+			if let Err(err) = transport::write_message(output, &outgoing) {
+				eprintln!("lemon lsp: {}", err);
+				return EXIT_FAILURE;
+			}
+		}
+	}
+}
+
+#[derive(Default)]
+struct Server {
+	/// unsaved contents of the open files
+	buffers: FxHashMap<PathBuf, String>,
+	/// last analysis of every open file
+	analyses: FxHashMap<PathBuf, Analysis>,
+	/// files with diagnostics published from an open file, to clear them once fixed
+	published: FxHashMap<PathBuf, FxHashSet<PathBuf>>,
+	shutdown: bool,
+}
+
+impl Server {
+	fn handle(&mut self, message: &Value) -> Vec<Value> {
+		let method = message["method"].as_str().unwrap_or_default();
+		let params = &message["params"];
+		let Some(id) = message.get("id").cloned() else {
+			return self.handle_notification(method, params);
+		};
+		if self.shutdown {
+			return vec![error_response(id, INVALID_REQUEST, "the server is shut down")];
+		}
+		let result = match method {
+			"initialize" => Ok(capabilities()),
+			"shutdown" => {
+				self.shutdown = true;
+				Ok(Value::Null)
+			}
+			"textDocument/hover" => self.hover(params),
+			"textDocument/definition" => self.definition(params),
+			"textDocument/completion" => self.completion(params),
+			method => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+		};
+		match result {
+			Ok(result) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+			Err((code, text)) => vec![error_response(id, code, &text)],
+		}
+	}
+
+	fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+		let Some(path) = document_path(params) else { return vec![] };
+		match method {
+			"textDocument/didOpen" => {
+				let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+				self.buffers.insert(path.clone(), text.to_owned());
+				self.publish(&path)
+			}
+			"textDocument/didChange" => {
+				// full sync, the last change is the whole document
+				let changes = params["contentChanges"].as_array();
+				if let Some(text) = changes.and_then(|changes| changes.last()?["text"].as_str()) {
+					self.buffers.insert(path.clone(), text.to_owned());
+				}
+				self.publish(&path)
+			}
+			"textDocument/didSave" => self.publish(&path),
+			"textDocument/didClose" => {
+				self.buffers.remove(&path);
+				self.analyses.remove(&path);
+				let published = self.published.remove(&path).unwrap_or_default();
+				published.iter().map(|path| publish_diagnostics(path, vec![])).collect()
+			}
+			_ => vec![],
+		}
+	}
+
+	/// checks an open file and publishes the diagnostics of it and of the mods it imports.
+	fn publish(&mut self, path: &Path) -> Vec<Value> {
+		let mut analysis = Analysis::check(path, &self.buffers);
+		let mut diagnostics: FxHashMap<PathBuf, Vec<Value>> = FxHashMap::default();
+		diagnostics.insert(path.to_path_buf(), vec![]);
+		for message in analysis.messages.sorted() {
+			let source = message.mod_id.and_then(|mod_id| analysis.loader.lookup_source(mod_id));
+			let file = source.map_or(path.to_path_buf(), |source| source.abs_path.clone());
+			diagnostics.entry(file).or_default().push(diagnostic(message, source));
+		}
+		let published: FxHashSet<PathBuf> = diagnostics.keys().cloned().collect();
+		let previous = self.published.insert(path.to_path_buf(), published).unwrap_or_default();
+		let fixed = previous.into_iter().filter(|file| !diagnostics.contains_key(file));
+		let mut notifications: Vec<Value> =
+			fixed.map(|file| publish_diagnostics(&file, vec![])).collect();
+		for (file, diagnostics) in diagnostics {
+			notifications.push(publish_diagnostics(&file, diagnostics));
+		}
+		self.analyses.insert(path.to_path_buf(), analysis);
+		notifications
+	}
+
+	fn analysis(&mut self, path: &Path) -> &Analysis {
+		if !self.analyses.contains_key(path) {
+			let analysis = Analysis::check(path, &self.buffers);
+			self.analyses.insert(path.to_path_buf(), analysis);
+		}
+		&self.analyses[path]
+	}
+
+	fn hover(&mut self, params: &Value) -> Result<Value, (i64, String)> {
+		let path = document_path(params).ok_or_else(invalid_params)?;
+		let analysis = self.analysis(&path);
+		let Some((mod_id, source)) = lookup_source(analysis, &path) else { return Ok(Value::Null) };
+		let offset = offset_at(source, &params["position"]);
+		let Some((range, type_id)) = analysis.ctx.event.lookup_type_at(mod_id, offset) else {
+			return Ok(Value::Null);
+		};
+		let value = format!("```lemon\n{}\n```", analysis.display_type(type_id));
+		let contents = json!({ "kind": "markdown", "value": value });
+		Ok(json!({ "contents": contents, "range": range_of(source, range) }))
+	}
+
+	fn definition(&mut self, params: &Value) -> Result<Value, (i64, String)> {
+		let path = document_path(params).ok_or_else(invalid_params)?;
+		let analysis = self.analysis(&path);
+		let Some((mod_id, source)) = lookup_source(analysis, &path) else { return Ok(Value::Null) };
+		let offset = offset_at(source, &params["position"]);
+		let Some(declaration) = analysis.ctx.event.lookup_definition_at(mod_id, offset) else {
+			return Ok(Value::Null);
+		};
+		let Some(declared_in) = analysis.source(declaration.0) else { return Ok(Value::Null) };
+		let uri = path_to_uri(&declared_in.abs_path);
+		Ok(json!({ "uri": uri, "range": range_of(declared_in, declaration.range()) }))
+	}
+
+	/// completes the fields and methods of a struct, or the pub items of a mod,
+	/// after `value.`, `Type::` or `mod::`.
+	fn completion(&mut self, params: &Value) -> Result<Value, (i64, String)> {
+		let path = document_path(params).ok_or_else(invalid_params)?;
+		let text = match self.buffers.get(&path) {
+			Some(text) => text.clone(),
+			None => fs::read_to_string(&path).map_err(|err| (INVALID_PARAMS, err.to_string()))?,
+		};
+		let source = Source::new(text, path.clone(), path.display().to_string());
+		let offset = offset_at(&source, &params["position"]);
+		let Some(member) = MemberAccess::before(&source.raw, offset) else {
+			return Ok(json!([]));
+		};
+		// `p.` alone doesn't parse, so the receiver is checked without the access
+		let mut buffers = self.buffers.clone();
+		let patched =
+			format!("{}{}", &source.raw[..member.separator.start], &source.raw[member.name_end..]);
+		buffers.insert(path.clone(), patched);
+		let analysis = Analysis::check(&path, &buffers);
+		let Some(mod_id) = analysis.mod_id(&path) else { return Ok(json!([])) };
+		let receiver = &source.raw[member.receiver.start..member.receiver.end];
+		let found = analysis.ctx.event.lookup_type_at(mod_id, member.receiver.start);
+		let found = found.filter(|(range, _)| *range == member.receiver).map(|(_, type_id)| type_id);
+		let type_id = match (found, member.is_path()) {
+			(Some(type_id), _) => type_id,
+			(None, true) => match analysis.ctx.type_store.lookup_type_definition(mod_id, receiver) {
+				Some(type_id) => *type_id,
+				None => return Ok(json!([])),
+			},
+			(None, false) => return Ok(json!([])),
+		};
+		Ok(Value::Array(completion_items(&analysis, type_id, member.is_path())))
+	}
+}
+
+/// `receiver.` or `receiver::`, right before the name being completed.
+struct MemberAccess {
+	receiver: Range,
+	separator: Range,
+	/// the end of the name around the cursor, e.g. `p.x|y` ends after `y`
+	name_end: usize,
+}
+
+impl MemberAccess {
+	fn before(text: &str, offset: usize) -> Option<Self> {
+		let name_start = ident_start(text, offset);
+		let before_name = &text[..name_start];
+		let separator = match before_name {
+			text if text.ends_with("::") => Range::new(name_start - 2, name_start),
+			text if text.ends_with('.') => Range::new(name_start - 1, name_start),
+			_ => return None,
+		};
+		let receiver = Range::new(ident_start(text, separator.start), separator.start);
+		let name_end = text.len() - text[offset..].trim_start_matches(is_ident_char).len();
+		(!receiver.is_empty()).then_some(Self { receiver, separator, name_end })
+	}
+
+	fn is_path(&self) -> bool {
+		self.separator.end - self.separator.start == 2
+	}
+}
+
+fn ident_start(text: &str, offset: usize) -> usize {
+	text[..offset].trim_end_matches(is_ident_char).len()
+}
+
+fn is_ident_char(char: char) -> bool {
+	char.is_alphanumeric() || char == '_'
+}
+
+fn completion_items(analysis: &Analysis, type_id: TypeId, is_path: bool) -> Vec<Value> {
+	let type_store = &analysis.ctx.type_store;
+	let item = |label: &str, kind: u64, type_id: &TypeId| json!({ "label": label, "kind": kind, "detail": analysis.display_type(*type_id) });
+	let mut items: Vec<Value> = match type_store.lookup_type(type_store.resolve_borrow_type(type_id))
+	{
+		Some(Type::Struct(struct_type)) if is_path => {
+			let associated = struct_type.associated.iter();
+			associated.map(|(name, type_id)| item(name, COMPLETION_FUNCTION, type_id)).collect()
+		}
+		Some(Type::Struct(struct_type)) => {
+			let fields = struct_type.fields.values();
+			let fields = fields.map(|field| item(&field.name, COMPLETION_FIELD, &field.type_id));
+			let methods = struct_type.fns.iter();
+			fields.chain(methods.map(|(name, type_id)| item(name, COMPLETION_METHOD, type_id))).collect()
+		}
+		Some(Type::Mod(mod_type)) => {
+			let Some(module) = analysis.ctx.get_module(mod_type.mod_id) else { return vec![] };
+			let functions = module.functions.iter();
+			let functions = functions.map(|(name, type_id)| item(name, COMPLETION_FUNCTION, type_id));
+			let values = module.values.iter();
+			functions
+				.chain(values.map(|(name, type_id)| item(name, COMPLETION_VARIABLE, type_id)))
+				.collect()
+		}
+		_ => vec![],
+	};
+	items.sort_by(|a, b| a["label"].as_str().cmp(&b["label"].as_str()));
+	items
+}
+
+fn lookup_source<'a>(
+	analysis: &'a Analysis,
+	path: &Path,
+) -> Option<(crate::loader::ModId, &'a Source)> {
+	let mod_id = analysis.mod_id(path)?;
+	Some((mod_id, analysis.source(mod_id)?))
+}
+
+fn diagnostic(message: &Message, source: Option<&Source>) -> Value {
+	let range = match (source, message.range) {
+		(Some(source), Some(range)) => range_of(source, range),
+		_ => json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } }),
+	};
+	let severity = match message.severity {
+		Severity::Error => 1,
+		Severity::Warning => 2,
+		Severity::Allow => 4,
+	};
+	let mut text = message.text.clone();
+	for note in message.notes.iter() {
+		text.push_str(&format!("\nhelp: {}", note.text));
+	}
+	let mut diagnostic =
+		json!({ "range": range, "severity": severity, "source": "lemon", "message": text });
+	if let Some(code) = message.code {
+		diagnostic["code"] = json!(code.to_string());
+	}
+	if let Some(source) = source {
+		let uri = path_to_uri(&source.abs_path);
+		let related = message.labels.iter().map(|label| {
+			let location = json!({ "uri": uri, "range": range_of(source, label.range) });
+			json!({ "location": location, "message": label.text })
+		});
+		diagnostic["relatedInformation"] = Value::Array(related.collect());
+	}
+	diagnostic
+}
+
+fn capabilities() -> Value {
+	json!({
+		"capabilities": {
+			"textDocumentSync": SYNC_FULL,
+			"hoverProvider": true,
+			"definitionProvider": true,
+			"completionProvider": { "triggerCharacters": [".", ":"] },
+		},
+		"serverInfo": { "name": "lemon", "version": env!("CARGO_PKG_VERSION") },
+	})
+}
+
+fn publish_diagnostics(path: &Path, diagnostics: Vec<Value>) -> Value {
+	let params = json!({ "uri": path_to_uri(path), "diagnostics": diagnostics });
+	json!({ "jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": params })
+}
+
+fn error_response(id: Value, code: i64, text: &str) -> Value {
+	json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": text } })
+}
+
+fn invalid_params() -> (i64, String) {
+	(INVALID_PARAMS, "expected a text document".to_string())
+}
+
+/// the document of the params, canonical so it matches the paths of the loader.
+fn document_path(params: &Value) -> Option<PathBuf> {
+	let path = uri_to_path(params["textDocument"]["uri"].as_str()?)?;
+	Some(fs::canonicalize(&path).unwrap_or(path))
+}
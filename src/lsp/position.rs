@@ -0,0 +1,70 @@
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::{range::Range, source::Source};
+
+// lsp positions are 0-based lines and utf-16 columns, lemon ranges are byte offsets.
+
+/// byte offset of an lsp position, clamped to the end of its line.
+pub fn offset_at(source: &Source, position: &Value) -> usize {
+	let line = position["line"].as_u64().unwrap_or(0) as usize;
+	let character = position["character"].as_u64().unwrap_or(0) as usize;
+	let Some(line_start) = source.lines.line_start(line + 1) else {
+		return source.raw.len();
+	};
+	let mut units = 0;
+	for (at, char) in source.raw[line_start..].char_indices() {
+		if units >= character || char == '\n' {
+			return line_start + at;
+		}
+		units += char.len_utf16();
+	}
+	source.raw.len()
+}
+
+pub fn position_of(source: &Source, offset: usize) -> Value {
+	let offset = offset.min(source.raw.len());
+	let (line, _) = source.line_column(offset);
+	let line_start = source.lines.line_start(line).unwrap_or(0);
+	let text = source.raw.get(line_start..offset).unwrap_or("");
+	let character: usize = text.chars().map(char::len_utf16).sum();
+	json!({ "line": line - 1, "character": character })
+}
+
+pub fn range_of(source: &Source, range: Range) -> Value {
+	json!({ "start": position_of(source, range.start), "end": position_of(source, range.end) })
+}
+
+/// e.g. "file:///home/lemon/main.ln" → "/home/lemon/main.ln"
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+	let path = uri.strip_prefix("file://")?;
+	let mut bytes = Vec::with_capacity(path.len());
+	let mut rest = path.as_bytes();
+	while let Some((&byte, tail)) = rest.split_first() {
+		let escaped = tail.get(..2).and_then(|hex| std::str::from_utf8(hex).ok());
+		match escaped.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+			Some(decoded) if byte == b'%' => {
+				bytes.push(decoded);
+				rest = &tail[2..];
+			}
+			_ => {
+				bytes.push(byte);
+				rest = tail;
+			}
+		}
+	}
+	String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+	let mut uri = String::from("file://");
+	// e.g. "/home/lemon/./math.ln" → "/home/lemon/math.ln"
+	let path: PathBuf = path.components().collect();
+	for char in path.display().to_string().chars() {
+		match char {
+			' ' | '%' | '#' | '?' => uri.push_str(&format!("%{:02X}", char as u32)),
+			char => uri.push(char),
+		}
+	}
+	uri
+}
@@ -0,0 +1,33 @@
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// reads a `Content-Length` framed message, `None` once the input is closed.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+	let mut content_length = None;
+	loop {
+		let mut header = String::new();
+		if input.read_line(&mut header)? == 0 {
+			return Ok(None);
+		}
+		let header = header.trim_end();
+		if header.is_empty() {
+			break;
+		}
+		// other headers, e.g. `Content-Type`, are ignored
+		if let Some(length) = header.strip_prefix("Content-Length:") {
+			content_length = length.trim().parse::<usize>().ok();
+		}
+	}
+	let Some(content_length) = content_length else {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"));
+	};
+	let mut content = vec![0; content_length];
+	input.read_exact(&mut content)?;
+	serde_json::from_slice(&content).map(Some).map_err(io::Error::from)
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+	let content = message.to_string();
+	write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+	output.flush()
+}
@@ -1,4 +1,7 @@
+mod assembler;
 mod ast;
+mod bench;
+mod bench_report;
 mod builder;
 mod checker;
 mod cli;
@@ -6,59 +9,189 @@ mod compiler;
 // mod comptime;
 mod cross;
 mod disassembler;
+mod explain;
 mod file_system;
+mod fmt;
+mod interp;
 mod ir;
 mod lexer;
 mod linker;
+mod lints;
 mod llvm;
 mod loader;
+mod lsp;
 mod message;
 mod time;
 // mod optimize;
 mod parser;
 mod range;
+mod repl;
 mod report;
+mod session;
 mod shio;
 mod source;
+mod verifier;
 
-use std::{path::Path, time::Instant};
+use std::{
+	path::{Path, PathBuf},
+	time::Instant,
+};
 
 use checker::{context::Context, Checker};
-use compiler::compile;
+use compiler::{compile, run, test};
 use file_system::FileSystem;
 use lexer::Token;
-use loader::{Loader, ModId};
+use lints::Lint;
+use loader::Loader;
 use logos::Logos;
-use parser::Parser;
-use report::throw_error;
-use shio::ShioConfig;
+use message::{ErrorCode, Severity};
+use parser::{parse_mod, Parser};
+use report::{
+	message_format, report_message_without_module, set_message_format, throw_error, MessageFormat,
+};
+use shio::{ShioConfig, ShioRegistry};
 use time::format_time;
 
-pub fn parse_mod(mod_id: ModId, loader: &mut Loader) {
-	let source = loader.lookup_source_unchecked(mod_id).clone();
-	let mut lexer = Token::lexer(source.raw.as_str());
-	let mut parser = Parser::new(&mut lexer, mod_id, loader);
-	let ast = parser.parse_program().unwrap_or_else(|message| message.report(loader));
-	loader.add_mod(mod_id, ast);
-}
 fn check(matches: &clap::ArgMatches) {
 	let timer = Instant::now();
-	let shio = match matches.get_one::<String>("file") {
+	match matches.get_one::<String>("file") {
 		Some(path_name) => {
 			let path = Path::new(path_name);
-			ShioConfig::with_defaults(path.to_path_buf())
+			let shio = ShioConfig::with_defaults(path.to_path_buf());
+			let cwd = shio.loader.cwd.clone();
+			check_package(shio, FileSystem::from_current_dir(cwd), matches);
 		}
-		None => ShioConfig::load_from_toml(None).unwrap_or_else(|err| throw_error(err)),
-	};
-	let cwd = shio.loader.cwd.clone();
-	let file_system = FileSystem::from_current_dir(cwd);
+		None => {
+			for (shio, file_system) in load_packages() {
+				check_package(shio, file_system, matches);
+			}
+		}
+	}
+	let ok_text = format!("ok in {}.", format_time(timer.elapsed(), true));
+	// stdout only carries messages in json
+	match message_format() {
+		MessageFormat::Json => eprintln!("{}", ok_text),
+		MessageFormat::Human => println!("{}", ok_text),
+	}
+}
+
+fn check_package(shio: ShioConfig, file_system: FileSystem, matches: &clap::ArgMatches) {
+	let update_lock = matches.get_flag("update-lock");
 	let mut loader = Loader::new(shio, file_system);
+	loader.extend_lints(&lint_flags(matches));
 	let mut ctx = Context::new();
 	let mod_id = loader.load_entry().unwrap_or_else(|message| message.report(&loader));
 	parse_mod(mod_id, &mut loader);
+	loader.report_messages();
+	loader.check_versions().unwrap_or_else(|message| message.report(&loader));
+	loader.sync_lock(update_lock).unwrap_or_else(|message| message.report(&loader));
 	let mut checker = Checker::new(&mut ctx, &mut loader);
 	checker.check(mod_id);
-	println!("ok in {}.", format_time(timer.elapsed(), true));
+}
+
+fn fmt(matches: &clap::ArgMatches) {
+	let check = matches.get_flag("check");
+	let paths = match matches.get_one::<String>("file") {
+		Some(path_name) => vec![PathBuf::from(path_name)],
+		None => load_packages()
+			.iter()
+			.flat_map(|(shio, _)| {
+				FileSystem::package_sources(&shio.root)
+					.unwrap_or_else(|message| report_message_without_module(&message))
+			})
+			.collect(),
+	};
+	// syntax errors win over unformatted files
+	let mut exit_code = None;
+	for path in paths {
+		let text = std::fs::read_to_string(&path)
+			.unwrap_or_else(|err| throw_error(format!("failed to read '{}': {}", path.display(), err)));
+		let (mut loader, mod_id) = fmt::parse_source(&path, text.clone());
+		// a file with syntax errors is left as it is
+		let mut messages = loader.take_messages();
+		if messages.any_errors() {
+			report::print_messages(&mut messages, &loader);
+			exit_code = messages.exit_code();
+			continue;
+		}
+		let formatted = fmt::format_mod(&loader, mod_id);
+		if formatted == text {
+			continue;
+		}
+		if check {
+			println!("'{}' is not formatted.", path.display());
+			exit_code = exit_code.or(Some(1));
+			continue;
+		}
+		std::fs::write(&path, formatted)
+			.unwrap_or_else(|err| throw_error(format!("failed to write '{}': {}", path.display(), err)));
+	}
+	if let Some(exit_code) = exit_code {
+		std::process::exit(exit_code);
+	}
+}
+
+fn explain(matches: &clap::ArgMatches) {
+	let code_text = matches.get_one::<String>("code").expect("code is required");
+	let Some(code) = ErrorCode::parse(code_text) else {
+		throw_error(format!("invalid error code '{}', expected e.g. 'E0101'", code_text));
+	};
+	match explain::explain(code) {
+		Some(text) => print!("{}", text),
+		None => throw_error(format!("no explanation for '{}'", code)),
+	}
+}
+
+/// lint levels from `-W`, `-D` and `-A`, in command line order.
+pub fn lint_flags(matches: &clap::ArgMatches) -> Vec<(Lint, Severity)> {
+	let mut flags = vec![];
+	for (id, level) in
+		[("warn", Severity::Warning), ("deny", Severity::Error), ("allow", Severity::Allow)]
+	{
+		let (Some(names), Some(indices)) = (matches.get_many::<String>(id), matches.indices_of(id))
+		else {
+			continue;
+		};
+		flags.extend(names.zip(indices).map(|(name, index)| (index, name.as_str(), level)));
+	}
+	lints::from_flags(flags).unwrap_or_else(|err| throw_error(err))
+}
+
+/// loads the packages of ./shio.toml, every member if it's a workspace.
+pub fn load_packages() -> Vec<(ShioConfig, FileSystem)> {
+	shio::load_packages(None).unwrap_or_else(|err| throw_error(err))
+}
+
+fn new(matches: &clap::ArgMatches) {
+	let name = matches.get_one::<String>("name").unwrap();
+	shio::new_package(Path::new("."), name, matches.get_flag("lib"))
+		.unwrap_or_else(|err| throw_error(err));
+	println!("created package '{}'.", name);
+}
+
+fn init(matches: &clap::ArgMatches) {
+	let cwd = std::env::current_dir().unwrap_or_else(|err| throw_error(err.to_string()));
+	let name = matches.get_one::<String>("name").map(String::as_str);
+	let name =
+		shio::init_package(&cwd, name, matches.get_flag("lib")).unwrap_or_else(|err| throw_error(err));
+	println!("created package '{}'.", name);
+}
+
+fn publish(matches: &clap::ArgMatches) {
+	let shio = ShioConfig::load_from_toml(None).unwrap_or_else(|err| throw_error(err));
+	let file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+	let registry_dir = match matches.get_one::<String>("registry") {
+		Some(dir) => PathBuf::from(dir),
+		None => shio.registry.clone().unwrap_or(file_system.remote_dir.clone()),
+	};
+	let sources = FileSystem::package_sources(&shio.root)
+		.unwrap_or_else(|message| report_message_without_module(&message));
+	let hash = FileSystem::hash_sources(&shio.root)
+		.unwrap_or_else(|message| report_message_without_module(&message));
+	let mut registry = ShioRegistry::open(&registry_dir).unwrap_or_else(|err| throw_error(err));
+	registry.publish(&shio, &sources, hash).unwrap_or_else(|err| throw_error(err));
+	let (name, version) = (&shio.package.name, &shio.package.version);
+	println!("published {} {} to '{}'.", name, version, registry_dir.display());
 }
 
 fn lex(path_name: &str) {
@@ -102,11 +235,15 @@ fn ast(path_name: &str) {
 		Ok(ast) => ast,
 		Err(message) => message.report(&loader),
 	};
+	loader.report_messages();
 	println!("{:#?}", ast);
 }
 
 fn main() {
 	let matches = cli::command_line();
+	if let Some(format) = matches.get_one::<String>("message-format") {
+		set_message_format(MessageFormat::from_name(format).unwrap_or_default());
+	}
 	match matches.subcommand() {
 		Some(("check", matches)) => {
 			// let path_name = matches.get_one::<String>("file").expect("file is required");
@@ -114,17 +251,42 @@ fn main() {
 		}
 
 		Some(("compile", matches)) => {
-			let path_name = matches.get_one::<String>("file").unwrap();
-			compile(path_name, matches);
+			compile(matches);
+		}
+		Some(("new", matches)) => {
+			new(matches);
+		}
+		Some(("init", matches)) => {
+			init(matches);
+		}
+		Some(("publish", matches)) => {
+			publish(matches);
+		}
+		Some(("explain", matches)) => {
+			explain(matches);
+		}
+		Some(("fmt", matches)) => {
+			fmt(matches);
+		}
+		Some(("lsp", _matches)) => {
+			std::process::exit(lsp::run());
 		}
 		Some(("lex", matches)) => {
 			let path_name = matches.get_one::<String>("file").unwrap();
 			lex(path_name);
 		}
-		Some(("run", _matches)) => {
-			// let path_name = matches.get_one::<String>("file").unwrap();
-			todo!("run command is not implemented yet");
-			// run(path_name);
+		Some(("run", matches)) => {
+			run(matches);
+		}
+		Some(("test", matches)) => {
+			test(matches);
+		}
+
+		Some(("bench", matches)) => {
+			bench::run(matches);
+		}
+		Some(("repl", matches)) => {
+			repl::run(matches);
 		}
 
 		Some(("token", matches)) => {
@@ -1,11 +1,11 @@
 #![allow(unused_variables, dead_code)]
 
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display};
 
 use crate::{
 	loader::{Loader, ModId},
 	range::Range,
-	report::{report_message, report_message_without_module, text_red},
+	report::{report_message, report_message_without_module, text_red, text_yellow},
 };
 
 #[macro_export]
@@ -92,10 +92,27 @@ impl Messages {
 		!self.messages.is_empty()
 	}
 
+	pub fn any_errors(&self) -> bool {
+		self.any_errors
+	}
+
 	pub fn message(&mut self, message: Message) {
 		self.any_errors |= message.severity == Severity::Error;
 		self.messages.push(message);
 	}
+
+	pub fn count(&self, severity: Severity) -> usize {
+		self.messages.iter().filter(|message| message.severity == severity).count()
+	}
+
+	/// sorts by position, messages without a range go first.
+	pub fn sort(&mut self) {
+		self.messages.sort_by_key(|message| message.range.map(|range| range.start));
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Message> {
+		self.messages.iter()
+	}
 }
 
 impl Default for Messages {
@@ -104,14 +121,64 @@ impl Default for Messages {
 	}
 }
 
+/// every message of a run, grouped by mod.
 #[derive(Debug)]
 pub struct RootMessages {
-	messages: Vec<Messages>,
+	/// messages without a mod (e.g., shio.toml errors) are under `None`
+	messages: BTreeMap<Option<ModId>, Messages>,
 }
 
 impl RootMessages {
 	pub fn new() -> Self {
-		RootMessages { messages: Vec::new() }
+		RootMessages { messages: BTreeMap::new() }
+	}
+
+	pub fn message(&mut self, message: Message) {
+		self.messages.entry(message.mod_id).or_default().message(message);
+	}
+
+	pub fn any_messages(&self) -> bool {
+		self.messages.values().any(Messages::any_messages)
+	}
+
+	pub fn any_errors(&self) -> bool {
+		self.messages.values().any(Messages::any_errors)
+	}
+
+	pub fn count(&self, severity: Severity) -> usize {
+		self.messages.values().map(|messages| messages.count(severity)).sum()
+	}
+
+	/// all messages, sorted by mod and position.
+	pub fn sorted(&mut self) -> impl Iterator<Item = &Message> {
+		self.messages.values_mut().for_each(Messages::sort);
+		self.messages.values().flat_map(Messages::iter)
+	}
+
+	/// e.g. "3 errors, 2 warnings"
+	pub fn summary(&self) -> String {
+		let plural = |count: usize, name: &str| match count {
+			1 => format!("1 {}", name),
+			count => format!("{} {}s", count, name),
+		};
+		let errors = self.count(Severity::Error);
+		let warnings = self.count(Severity::Warning);
+		match (errors, warnings) {
+			(0, warnings) => plural(warnings, "warning"),
+			(errors, 0) => plural(errors, "error"),
+			(errors, warnings) => format!("{}, {}", plural(errors, "error"), plural(warnings, "warning")),
+		}
+	}
+
+	/// exit code of the earliest stage that failed, later stages fail because of it.
+	pub fn exit_code(&self) -> Option<i32> {
+		let errors = self.messages.values().flat_map(Messages::iter);
+		let errors = errors.filter(|message| message.severity == Severity::Error);
+		errors.map(Message::exit_code).min()
+	}
+
+	pub fn clear(&mut self) {
+		self.messages.clear();
 	}
 }
 
@@ -121,7 +188,10 @@ impl Default for RootMessages {
 	}
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// exit code of failures without a stage, e.g. a missing shio.toml.
+pub const EXIT_FAILURE: i32 = 1;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Stage {
 	Syntax,    // parser + lexer
 	Resolve,   // loader
@@ -136,7 +206,20 @@ impl Stage {
 	pub fn new() -> Self {
 		Self::Syntax
 	}
-	fn name(self) -> &'static str {
+	/// stable exit codes, scripts and CI rely on them.
+	pub fn exit_code(self) -> i32 {
+		match self {
+			Stage::Syntax => 2,
+			Stage::Resolve => 3,
+			Stage::Type => 4,
+			Stage::Ownership => 5,
+			Stage::Build => 6,
+			Stage::Codegen => 7,
+			Stage::Comptime => 8,
+		}
+	}
+
+	pub fn name(self) -> &'static str {
 		match self {
 			Stage::Syntax => "syntax",
 			Stage::Resolve => "resolve",
@@ -166,7 +249,7 @@ impl Severity {
 	pub fn new() -> Self {
 		Self::Error
 	}
-	fn name(self) -> &'static str {
+	pub fn name(self) -> &'static str {
 		match self {
 			Severity::Error => "error",
 			Severity::Warning => "warning",
@@ -181,7 +264,7 @@ impl Default for Severity {
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Message {
 	pub severity: Severity,
 	pub text: String,
@@ -189,6 +272,10 @@ pub struct Message {
 	pub range: Option<Range>,
 	pub mod_id: Option<ModId>,
 	pub notes: Vec<Note>,
+	/// secondary spans, e.g. the first borrow of a borrow conflict
+	pub labels: Vec<Label>,
+	/// stable code, see `lemon explain`
+	pub code: Option<ErrorCode>,
 }
 
 impl Message {
@@ -199,6 +286,8 @@ impl Message {
 			stage: None,
 			range: None,
 			notes: Vec::new(),
+			labels: Vec::new(),
+			code: None,
 			mod_id: None,
 		}
 	}
@@ -215,6 +304,8 @@ impl Message {
 			stage: None,
 			range: None,
 			notes: Vec::new(),
+			labels: Vec::new(),
+			code: None,
 			mod_id: None,
 		}
 	}
@@ -236,8 +327,7 @@ impl Message {
 
 	pub fn error_ownership(text: impl Into<String>) -> Message {
 		let message = Message::error(text);
-		// message.stage(Stage::Ownership)
-		message.stage(Stage::Type)
+		message.stage(Stage::Ownership)
 	}
 
 	pub fn error_build(text: impl Into<String>) -> Message {
@@ -291,6 +381,12 @@ impl Message {
 		self.range = Some(range);
 		self
 	}
+	/// e.g. `.code(101)` for E0101
+	pub fn code(mut self, code: u16) -> Message {
+		self.code = Some(ErrorCode(code));
+		self
+	}
+
 	pub fn mod_id(mut self, mod_id: ModId) -> Message {
 		self.mod_id = Some(mod_id);
 		self
@@ -311,6 +407,18 @@ impl Message {
 		self
 	}
 
+	pub fn label(mut self, range: Range, text: impl Into<String>) -> Message {
+		self.labels.push(Label::new(text, range));
+		self
+	}
+
+	pub fn label_if_some(self, range: Option<Range>, text: impl Into<String>) -> Message {
+		match range {
+			Some(range) => self.label(range, text),
+			None => self,
+		}
+	}
+
 	pub fn note_if_some(mut self, range: Option<Range>, text: impl Into<String>) -> Message {
 		if let Some(note) = Note::maybe_new(text, range) {
 			self.notes.push(note);
@@ -328,6 +436,10 @@ impl Message {
 			None => report_message_without_module(self),
 		}
 	}
+	pub fn exit_code(&self) -> i32 {
+		self.stage.map(Stage::exit_code).unwrap_or(EXIT_FAILURE)
+	}
+
 	// ============ Reports ============
 	pub fn report(&self, loader: &Loader) -> ! {
 		let mod_id = self._lookup_mod_id();
@@ -336,7 +448,40 @@ impl Message {
 	}
 }
 
-#[derive(Debug)]
+/// printed as "E" and four digits, e.g. E0101
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ErrorCode(pub u16);
+
+impl ErrorCode {
+	/// parses "E0101", the "E" is case insensitive.
+	pub fn parse(code: &str) -> Option<Self> {
+		let digits = code.strip_prefix(['E', 'e'])?;
+		if digits.len() != 4 {
+			return None;
+		}
+		digits.parse().ok().map(ErrorCode)
+	}
+}
+
+impl Display for ErrorCode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "E{:04}", self.0)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+	pub text: String,
+	pub range: Range,
+}
+
+impl Label {
+	pub fn new(text: impl Into<String>, range: Range) -> Label {
+		Label { text: text.into(), range }
+	}
+}
+
+#[derive(Debug, Clone)]
 pub struct Note {
 	pub text: String,
 	pub range: Option<Range>,
@@ -362,7 +507,15 @@ impl Display for Message {
 		let severity = self.severity;
 		let text = self.text.as_str();
 		let stage = self.stage.unwrap_or_default();
-		let slug = text_red(format!("{} {}", stage, severity).as_str());
+		let slug = match self.code {
+			// e.g. "type error[E0101]"
+			Some(code) => format!("{} {}[{}]", stage, severity, code),
+			None => format!("{} {}", stage, severity),
+		};
+		let slug = match self.severity {
+			Severity::Warning => text_yellow(&slug),
+			_ => text_red(&slug),
+		};
 		write!(f, "{}: {}", slug, text)
 	}
 }
@@ -22,7 +22,7 @@ impl<'opt> Optimizer<'opt> {
 	}
 
 	fn optimize_block(&mut self, block: &mut ir::Block) {
-		for instr in block.instrs.iter_mut() {
+		for instr in block.instrs_mut().iter_mut() {
 			self.optimize_instr(instr);
 		}
 	}
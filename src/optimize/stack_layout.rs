@@ -1,5 +1,3 @@
-use std::mem;
-
 // todo: is really necessary? NB: this code is only draft...
 use rustc_hash::FxHashMap;
 
@@ -42,30 +40,27 @@ impl StackLayout {
 		function.blocks.iter_mut().for_each(|block| self.optimize_block(block));
 		self.create_stack_layout();
 		if let Some(block) = function.blocks.first_mut() {
-			let layout_fmt = format!("layout [{} x i32]", block.instrs.len());
+			let layout_fmt = format!("layout [{} x i32]", block.instrs().len());
 			let layout_value = ir::IrValue::new(layout_fmt, TypeId::I32);
 			let alloc_layout = SallocInstr::new(layout_value, TypeId::I32);
-			block.instrs.insert(0, alloc_layout.into());
+			block.insert_instr(0, alloc_layout.into(), None);
 		}
 
 		function.blocks.iter_mut().for_each(|block| self.optimize_gen_block(block));
 	}
 
 	fn optimize_block(&mut self, block: &mut ir::IrBlock) {
-		let taked = mem::take(block);
-		let instrs = taked.instrs.into_iter().filter(|instr| {
+		block.retain_instrs(|instr| {
 			if let ir::Instr::Salloc(instr) = instr {
 				self.stack_grup.insert(instr.dest.value.clone(), instr.dest.kind);
 				return false;
 			}
 			true
 		});
-		let instrs = instrs.collect::<Vec<_>>();
-		block.instrs.extend(instrs);
 	}
 
 	fn optimize_gen_block(&mut self, block: &mut ir::IrBlock) {
-		block.instrs.iter_mut().for_each(|instr| self.optimize_gen_instr(instr));
+		block.instrs_mut().iter_mut().for_each(|instr| self.optimize_gen_instr(instr));
 	}
 
 	fn optimize_gen_instr(&mut self, instr: &mut ir::Instr) {
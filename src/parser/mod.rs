@@ -1,19 +1,27 @@
 use logos::{Lexer, Logos};
 
 use crate::ast::{self, AstType, OperatorKind, BASE_BIN, BASE_DECIMAL, BASE_HEX, MIN_PDE};
+use crate::error_syntax;
 use crate::lexer::Token;
 use crate::loader::{Loader, ModId};
 use crate::message::{Message, MessageResult};
 use crate::range::Range;
-use crate::{error_syntax, throw_error};
 mod parse_type;
 
 pub fn parse_mod(mod_id: ModId, loader: &mut Loader) {
-	let source = loader.lookup_source_unchecked(mod_id).clone();
+	try_parse_mod(mod_id, loader).unwrap_or_else(|message| message.report(loader));
+}
+
+/// parses a mod and the mods it imports, the syntax errors are recorded in the loader.
+pub fn try_parse_mod(mod_id: ModId, loader: &mut Loader) -> MessageResult<()> {
+	let source = loader.lookup_source_result(mod_id)?.clone();
 	let mut lexer = Token::lexer(source.raw.as_str());
+	loader.start_parsing(mod_id);
 	let mut parser = Parser::new(&mut lexer, mod_id, loader);
-	let ast = parser.parse_program().unwrap_or_else(|message| message.report(loader));
-	loader.add_mod(mod_id, ast);
+	let ast = parser.parse_program();
+	loader.finish_parsing(mod_id);
+	loader.add_mod(mod_id, ast?);
+	Ok(())
 }
 // --- pde utils -----
 
@@ -25,6 +33,8 @@ pub struct Parser<'p> {
 	mod_id: ModId,
 	lexer: &'p mut Lexer<'p, Token>,
 	loader: &'p mut Loader,
+	comments: Vec<ast::Comment>,
+	load_imports: bool,
 }
 // --- parser  -----
 
@@ -32,24 +42,71 @@ pub struct Parser<'p> {
 
 impl<'p> Parser<'p> {
 	pub fn new(lexer: &'p mut Lexer<'p, Token>, mod_id: ModId, loader: &'p mut Loader) -> Self {
-		let token = lexer.next().map(|t| t.unwrap_or_else(|_| throw_error!("unexpected end of file")));
-		let range = Range::from_span(lexer.span());
-		Self { lexer, token, range, mod_id, loader }
+		let range = Range::default();
+		let mut parser =
+			Self { lexer, token: None, range, mod_id, loader, comments: vec![], load_imports: true };
+		// a first token that doesn't lex is reported like the others
+		while let Err(message) = parser.next() {
+			parser.loader.add_message(message.mod_id(mod_id));
+		}
+		parser
+	}
+
+	/// parses `import(...)` without loading the imported mod, e.g. for `lemon fmt`.
+	pub fn without_imports(mut self) -> Self {
+		self.load_imports = false;
+		self
 	}
 	pub fn parse_program(&mut self) -> MessageResult<ast::Program> {
 		let mut stmts = vec![];
 		while !self.is_end() {
+			let start = self.range;
 			match self.parse_stmt() {
 				Ok(stmt) => stmts.push(stmt),
-				Err(message) => return Err(message.mod_id(self.mod_id)),
+				Err(message) => self.recover(message, start),
 			};
 		}
-		Ok(ast::Program { stmts })
+		let comments = std::mem::take(&mut self.comments);
+		Ok(ast::Program { stmts, comments })
+	}
+
+	/// records the message and skips to the next statement.
+	fn recover(&mut self, message: Message, start: Range) {
+		self.loader.add_message(message.mod_id(self.mod_id));
+		// always move on, a statement failing on its first token would loop forever
+		if self.range == start {
+			let _ = self.next();
+		}
+		let mut depth = 0;
+		while let Some(token) = self.token {
+			match token {
+				Token::LBrace => depth += 1,
+				// the end of the enclosing block
+				Token::RBrace if depth == 0 => return,
+				Token::RBrace => depth -= 1,
+				Token::Semi if depth == 0 => {
+					let _ = self.next();
+					return;
+				}
+				token if depth == 0 && Self::is_stmt_start(token) => return,
+				_ => {}
+			}
+			// a token that doesn't lex is reported and skipped like the rest
+			while let Err(message) = self.next() {
+				self.loader.add_message(message.mod_id(self.mod_id));
+			}
+		}
+	}
+
+	fn is_stmt_start(token: Token) -> bool {
+		use Token::*;
+		matches!(token, Pub | Let | Const | Fn | Ret | If | Extern | While | For | Type | Impl | At)
 	}
 
 	fn parse_stmt(&mut self) -> MessageResult<ast::Stmt> {
 		let stmt = match self.token {
 			Some(Token::Pub) => self.parse_pub_stmt(),
+			Some(Token::At) => self.parse_attributed_stmt(),
 			Some(Token::Let) => self.parse_let_stmt().map(ast::Stmt::Let),
 			Some(Token::Const) => self.parse_const_stmt(),
 			Some(Token::Fn) => self.parse_fn_stmt().map(ast::Stmt::Fn),
@@ -74,6 +131,7 @@ impl<'p> Parser<'p> {
 		self.expect(Token::LBrace)?;
 		let mut items = vec![];
 		while !self.match_token(Token::RBrace) {
+			let attrs = self.parse_attributes()?;
 			let mut is_pub = false;
 			if self.match_token(Token::Pub) {
 				is_pub = true;
@@ -81,12 +139,52 @@ impl<'p> Parser<'p> {
 			}
 			let mut item = self.parse_fn_stmt()?;
 			item.set_is_pub(is_pub);
+			item.set_attrs(attrs);
 			items.push(item);
 		}
 		self.expect(Token::RBrace)?;
 		Ok(ast::ImplStmt { range, self_name, items })
 	}
 
+	// @allow(unused_variable) fn ..., @test fn ...
+	fn parse_attributed_stmt(&mut self) -> MessageResult<ast::Stmt> {
+		let attrs = self.parse_attributes()?;
+		let range =
+			attrs.iter().fold(attrs[0].get_range(), |range, attr| range.merged_with(&attr.range));
+		match self.parse_stmt()? {
+			ast::Stmt::Fn(mut fn_stmt) => {
+				fn_stmt.set_attrs(attrs);
+				Ok(ast::Stmt::Fn(fn_stmt))
+			}
+			_ => Err(error_syntax!("attributes are only allowed on functions").code(505).range(range)),
+		}
+	}
+
+	fn parse_attributes(&mut self) -> MessageResult<Vec<ast::Attribute>> {
+		let mut attrs = vec![];
+		while self.match_token(Token::At) {
+			let start = self.expect(Token::At)?;
+			let name = self.parse_ident()?;
+			let mut args = vec![];
+			// `@test` has no args
+			if !self.match_token(Token::LParen) {
+				let range = start.merged_with(&name.get_range());
+				attrs.push(ast::Attribute { name, args, range });
+				continue;
+			}
+			self.expect(Token::LParen)?;
+			while !self.match_token(Token::RParen) {
+				args.push(self.parse_ident()?);
+				if !self.match_token(Token::RParen) {
+					self.expect(Token::Comma)?;
+				}
+			}
+			let end = self.expect(Token::RParen)?;
+			attrs.push(ast::Attribute { name, args, range: start.merged_with(&end) });
+		}
+		Ok(attrs)
+	}
+
 	fn parse_pub_stmt(&mut self) -> MessageResult<ast::Stmt> {
 		self.expect(Token::Pub)?;
 		match self.token {
@@ -115,7 +213,7 @@ impl<'p> Parser<'p> {
 				Ok(ast::Stmt::TypeDef(type_def_stmt))
 			}
 			_ => {
-				let message = error_syntax!("expected 'const', 'fn' or 'type'");
+				let message = error_syntax!("expected 'const', 'fn' or 'type'").code(506);
 				Err(message.range(self.range))
 			}
 		}
@@ -248,7 +346,8 @@ impl<'p> Parser<'p> {
 		}
 		self.expect(Token::Assign)?; // take '='
 		let body = self.parse_fn_body()?;
-		Ok(ast::FnStmt { is_pub: false, name, generics, params, ret_type, body, range })
+		let attrs = vec![];
+		Ok(ast::FnStmt { is_pub: false, name, generics, params, ret_type, body, range, attrs })
 	}
 
 	// <T, U: Eq>
@@ -292,9 +391,12 @@ impl<'p> Parser<'p> {
 	fn parse_block_stmt(&mut self) -> MessageResult<ast::BlockStmt> {
 		let mut range = self.expect(Token::LBrace)?;
 		let mut stmts = vec![];
-		while !self.match_token(Token::RBrace) {
-			let stmt = self.parse_stmt()?;
-			stmts.push(stmt);
+		while !self.match_token(Token::RBrace) && !self.is_end() {
+			let start = self.range;
+			match self.parse_stmt() {
+				Ok(stmt) => stmts.push(stmt),
+				Err(message) => self.recover(message, start),
+			};
 		}
 		range.merge(&self.expect(Token::RBrace)?);
 
@@ -475,7 +577,7 @@ impl<'p> Parser<'p> {
 			return Ok(ast::Expr::StructInit(init));
 		}
 		//
-		let message = error_syntax!("expected struct name");
+		let message = error_syntax!("expected struct name").code(502);
 		Err(message.range(expr.get_range()))
 	}
 
@@ -486,7 +588,7 @@ impl<'p> Parser<'p> {
 			let method = self.parse_ident()?;
 			Ok(ast::Expr::Associate(ast::AssociateExpr { self_name, method, range }))
 		} else {
-			let message = error_syntax!("expected identifier");
+			let message = error_syntax!("expected identifier").code(502);
 			Err(message.range(expr.get_range()))
 		}
 	}
@@ -516,12 +618,18 @@ impl<'p> Parser<'p> {
 		};
 		let end = self.expect(Token::RParen)?;
 		let max_range = range.merged_with(&end);
+		if !self.load_imports {
+			return Ok(ast::ImportExpr { path, range: max_range, mod_id: None });
+		}
 
 		let mod_id = match self.loader.load_source(&path.text, self.mod_id) {
 			Ok(mod_id) => mod_id,
 			Err(message) => return Err(message.range(max_range)),
 		};
-		parse_mod(mod_id, self.loader);
+		self.loader.check_import_cycle(mod_id).map_err(|message| message.range(max_range))?;
+		if !self.loader.is_parsed(mod_id) {
+			try_parse_mod(mod_id, self.loader).map_err(|message| message.range(max_range))?;
+		}
 		Ok(ast::ImportExpr { path, range: max_range, mod_id: Some(mod_id) })
 	}
 
@@ -553,11 +661,11 @@ impl<'p> Parser<'p> {
 		let mut chars = value.chars();
 		let value = match chars.next() {
 			Some(char) => char,
-			None => return Err(error_syntax!("expected char literal").range(range)),
+			None => return Err(error_syntax!("expected char literal").code(503).range(range)),
 		};
 
 		if chars.next().is_some() {
-			return Err(error_syntax!("expected char literal").range(range));
+			return Err(error_syntax!("expected char literal").code(503).range(range));
 		}
 
 		let char = ast::CharLiteral { value, range };
@@ -591,7 +699,7 @@ impl<'p> Parser<'p> {
 				Some('t') => str.push('\t'),
 				Some('0') => str.push('\0'),
 				_ => {
-					let message = error_syntax!("unknown escape sequence '\\{}'", char);
+					let message = error_syntax!("unknown escape sequence '\\{}'", char).code(504);
 					return Err(message.range(*range));
 				}
 			}
@@ -604,7 +712,8 @@ impl<'p> Parser<'p> {
 		let range = self.take_range();
 		let text = self.take_text_and_next()?;
 		let (base, cleaned_text) = self.detect_numb_base(&text);
-		let as_dot = text.contains('.');
+		// `1e10` is a float too, `0xe` is not
+		let as_dot = base == BASE_DECIMAL && text.contains(['.', 'e', 'E']);
 		let text = self.normalize_number(&cleaned_text);
 		let number = ast::NumberLiteral { base, as_dot, text, range };
 		Ok(ast::Literal::Number(number))
@@ -702,7 +811,7 @@ impl<'p> Parser<'p> {
 
 		// include "'"
 		if self.take_text().len() > 3 {
-			let message = error_syntax!("expected char literal");
+			let message = error_syntax!("expected char literal").code(503);
 			return Err(message.range(self.range));
 		}
 		Ok(())
@@ -724,7 +833,7 @@ impl<'p> Parser<'p> {
 		// suport &self or &mut self
 		if self.match_token(Token::And) {
 			if !with_self {
-				let message = error_syntax!("unexpected token '&'");
+				let message = error_syntax!("unexpected token '&'").code(501);
 				return Err(message.range(self.range));
 			}
 
@@ -735,7 +844,7 @@ impl<'p> Parser<'p> {
 					return Ok(ast::Binding { ident, ty: Some(ast_type) });
 				}
 			}
-			let message = error_syntax!("expected ident");
+			let message = error_syntax!("expected ident").code(502);
 			return Err(message.range(self.range));
 		}
 
@@ -780,11 +889,28 @@ impl<'p> Parser<'p> {
 
 	fn next(&mut self) -> MessageResult<Option<Token>> {
 		let temp = self.token.take();
-		self.token = self.lexer.next().transpose().map_err(|_| self.unexpected_token())?;
+		let token = Self::lex_next(self.lexer, &mut self.comments);
 		self.range = Range::from_span(self.lexer.span());
+		self.token = token.transpose().map_err(|_| self.unexpected_token())?;
 		Ok(temp)
 	}
 
+	/// the next token that is not a comment, comments are collected on the way.
+	fn lex_next(
+		lexer: &mut Lexer<'p, Token>,
+		comments: &mut Vec<ast::Comment>,
+	) -> Option<Result<Token, ()>> {
+		loop {
+			match lexer.next() {
+				Some(Ok(Token::LineComment | Token::BlockComment)) => {
+					let range = Range::from_span(lexer.span());
+					comments.push(ast::Comment { text: lexer.slice().to_string(), range });
+				}
+				token => return token,
+			}
+		}
+	}
+
 	#[inline(always)]
 	fn take_range(&mut self) -> Range {
 		self.range
@@ -814,7 +940,7 @@ impl<'p> Parser<'p> {
 		if !self.match_token(token) {
 			// todo: add error message
 			let peeked = self.token.map(|t| t.to_string()).unwrap_or_else(|| "unkown".to_string());
-			let message = error_syntax!("expected {} but got {}", token, peeked);
+			let message = error_syntax!("expected {} but got {}", token, peeked).code(501);
 			return Err(message.range(self.range));
 		}
 		let range = self.range;
@@ -828,9 +954,9 @@ impl<'p> Parser<'p> {
 
 	fn unexpected_token(&mut self) -> Message {
 		if let Some(token) = self.token {
-			let message = error_syntax!("unexpected token '{}'", token);
+			let message = error_syntax!("unexpected token '{}'", token).code(501);
 			return message.range(self.range);
 		}
-		error_syntax!("unsupported token").range(self.range)
+		error_syntax!("unsupported token").code(501).range(self.range)
 	}
 }
@@ -27,6 +27,11 @@ impl Range {
 	pub fn from_span(span: Span) -> Range {
 		Range::new(span.start, span.end)
 	}
+	/// the offset is inside the range, or right at its end.
+	pub fn contains(&self, offset: usize) -> bool {
+		self.start <= offset && offset <= self.end
+	}
+
 	pub fn is_empty(&self) -> bool {
 		self.start == self.end
 	}
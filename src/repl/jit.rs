@@ -0,0 +1,61 @@
+use inkwell::{context::Context, module::Module, values::AnyValue, OptimizationLevel};
+
+use crate::{
+	checker::types::TypeId,
+	ir::IR,
+	llvm,
+	session::{Compiled, Session},
+};
+
+fn compile_module<'ll>(llvm_context: &'ll Context, session: &'ll Session, ir: &IR) -> Module<'ll> {
+	let source = session.loader.lookup_source_unchecked(session.mod_id);
+	let module = llvm::create_module_from_source(llvm_context, source);
+	let mut llvm = llvm::Llvm::new(llvm_context, module, &session.loader, &session.ctx.type_store);
+	llvm.compile_ir(ir);
+	llvm.module
+}
+
+/// the llvm ir of the function an input was built into.
+pub fn llr(session: &Session, compiled: &Compiled) -> String {
+	let llvm_context = Context::create();
+	let module = compile_module(&llvm_context, session, &compiled.ir);
+	match module.get_function(&compiled.name) {
+		Some(function) => function.print_to_string().to_string(),
+		None => String::new(),
+	}
+}
+
+/// runs an input with the jit, externs such as `printf` resolve against the host libc.
+pub fn run(session: &Session, compiled: &Compiled) -> Result<Option<String>, String> {
+	// mcjit only calls a function without params that returns an int, a float, a pointer or nothing
+	if let Some(type_id) = compiled.ret.filter(|type_id| !shows_value(*type_id)) {
+		let type_name = session.display_type(type_id);
+		return Err(format!("the jit can't show a value of '{}', try without --jit", type_name));
+	}
+	let llvm_context = Context::create();
+	let module = compile_module(&llvm_context, session, &compiled.ir);
+	let engine = module.create_jit_execution_engine(OptimizationLevel::None);
+	let engine = engine.map_err(|err| err.to_string())?;
+	let function = module.get_function(&compiled.name).ok_or("the input was not built")?;
+	if function.count_params() != 0 {
+		return Err(format!("the jit can't call '{}', it takes params", compiled.name));
+	}
+	let value = unsafe { engine.run_function(function, &[]) };
+	let Some(type_id) = compiled.ret else {
+		return Ok(None);
+	};
+	let text = match type_id {
+		TypeId::BOOL => (value.as_int(false) != 0).to_string(),
+		TypeId::CHAR => format!("{:?}", char::from(value.as_int(false) as u8)),
+		TypeId::F32 => format!("{:?}", value.as_float(&llvm_context.f32_type())),
+		TypeId::F64 => format!("{:?}", value.as_float(&llvm_context.f64_type())),
+		type_id if type_id.is_int_type() => (value.as_int(true) as i64).to_string(),
+		_ => value.as_int(false).to_string(),
+	};
+	Ok(Some(text))
+}
+
+fn shows_value(type_id: TypeId) -> bool {
+	matches!(type_id, TypeId::BOOL | TypeId::CHAR | TypeId::F32 | TypeId::F64)
+		|| type_id.is_number_type()
+}
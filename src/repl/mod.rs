@@ -0,0 +1,159 @@
+use std::io::{self, BufRead, Write};
+
+use clap::ArgMatches;
+
+use crate::{
+	disassembler::Disassembler,
+	error_codegen, error_syntax,
+	interp::{Interp, Value},
+	report::{print_message_without_module, text_cyan},
+	session::{open_delimiters, Checked, Compiled, Session},
+};
+
+mod jit;
+
+const HELP: &str = "\
+<expr>          evaluate an expression and show its value and type
+let x = <expr>  bind a value, replayed before the next inputs
+fn, type, impl  define functions and types for the next inputs
+:type <expr>    show the checked type of an expression
+:lnr <expr>     show the lemon ir of an expression
+:llr <expr>     show the llvm ir of an expression
+:help           show this help
+:quit           exit, as does ctrl-d
+";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Backend {
+	Interp,
+	Jit,
+}
+
+/// `lemon repl`, reads inputs until `:quit` or the end of stdin.
+pub fn run(matches: &ArgMatches) {
+	let backend = if matches.get_flag("jit") { Backend::Jit } else { Backend::Interp };
+	let mut session = Session::new();
+	println!("lemon {} repl, :help for the commands.", env!("CARGO_PKG_VERSION"));
+	let mut stdin = io::stdin().lock();
+	while let Some(input) = read_input(&mut stdin) {
+		let input = input.trim();
+		let (command, rest) = match input.strip_prefix(':') {
+			Some(command) => command.split_once(char::is_whitespace).unwrap_or((command, "")),
+			None => ("", input),
+		};
+		match command {
+			"" if input.is_empty() => {}
+			"" => evaluate(&mut session, input, backend),
+			"type" | "t" => show_type(&mut session, rest.trim()),
+			"lnr" => show_lnr(&mut session, rest.trim()),
+			"llr" => show_llr(&mut session, rest.trim()),
+			"help" | "h" => print!("{}", HELP),
+			"quit" | "q" => break,
+			_ => {
+				print_message_without_module(&error_syntax!("unknown command ':{}', try :help", command))
+			}
+		}
+	}
+}
+
+fn evaluate(session: &mut Session, input: &str, backend: Backend) {
+	let Some(checked) = session.check(input) else {
+		return;
+	};
+	// definitions and `let`s run with the inputs using them
+	if matches!(checked, Checked::Items | Checked::Let) {
+		return;
+	}
+	let Some(compiled) = session.compile(input, &checked) else {
+		return;
+	};
+	let value = match backend {
+		Backend::Interp => interpret(session, &compiled),
+		Backend::Jit => jit::run(session, &compiled).map_err(|err| {
+			print_message_without_module(&error_codegen!("{}", err));
+		}),
+	};
+	let Ok(value) = value else {
+		return;
+	};
+	if let Checked::Expr { type_id, .. } = checked {
+		// a literal shows the type it defaults to
+		let type_id = compiled.ret.unwrap_or(type_id);
+		let type_name = text_cyan(&session.display_type(type_id));
+		match value {
+			Some(value) => println!("{}: {}", value, type_name),
+			None => println!("{}", type_name),
+		}
+	}
+}
+
+fn interpret(session: &Session, compiled: &Compiled) -> Result<Option<String>, ()> {
+	let mut interp = Interp::new(&compiled.ir, &session.ctx.type_store);
+	let value = interp.call(&compiled.name, Vec::new()).map_err(|message| {
+		print_message_without_module(&message);
+	})?;
+	Ok(compiled.ret.map(|_| show_value(value)))
+}
+
+fn show_value(value: Value) -> String {
+	match value {
+		// `3.0`, not `3`
+		Value::Float(value) => format!("{:?}", value),
+		value => value.to_string(),
+	}
+}
+
+fn show_type(session: &mut Session, input: &str) {
+	match session.check(input) {
+		Some(Checked::Expr { type_id, .. }) => {
+			println!("{}", text_cyan(&session.display_type(type_id)))
+		}
+		Some(_) => print_message_without_module(&error_syntax!("':type' expects an expression")),
+		None => {}
+	}
+}
+
+fn show_lnr(session: &mut Session, input: &str) {
+	let Some(compiled) = compile(session, input) else {
+		return;
+	};
+	let Some(function) = compiled.ir.functions.iter().find(|function| function.name == compiled.name)
+	else {
+		return;
+	};
+	let mut text = String::new();
+	Disassembler::new(&session.ctx.type_store).disassemble_function(function, &mut text);
+	println!("{}", text.trim_end());
+}
+
+fn show_llr(session: &mut Session, input: &str) {
+	if let Some(compiled) = compile(session, input) {
+		println!("{}", jit::llr(session, &compiled).trim_end());
+	}
+}
+
+fn compile(session: &mut Session, input: &str) -> Option<Compiled> {
+	match session.check(input)? {
+		Checked::Items | Checked::Let => {
+			print_message_without_module(&error_syntax!("expected an expression or statements"));
+			None
+		}
+		checked => session.compile(input, &checked),
+	}
+}
+
+/// reads one input, more lines are read while a brace or a paren is left open.
+fn read_input(stdin: &mut impl BufRead) -> Option<String> {
+	let mut input = String::new();
+	loop {
+		print!("{}", if input.is_empty() { "> " } else { ". " });
+		io::stdout().flush().ok()?;
+		if stdin.read_line(&mut input).ok()? == 0 {
+			// ctrl-d on an empty prompt ends the session
+			return if input.is_empty() { None } else { Some(input) };
+		}
+		if open_delimiters(&input) <= 0 {
+			return Some(input);
+		}
+	}
+}
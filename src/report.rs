@@ -1,13 +1,50 @@
 use console::Style;
+use serde::Serialize;
+use std::sync::OnceLock;
 
 use crate::{
-	message::{Message, Severity},
+	loader::Loader,
+	message::{Label, Message, RootMessages, Severity, EXIT_FAILURE},
+	range::Range,
 	source::Source,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageFormat {
+	#[default]
+	Human,
+	/// one json object per message and line, for editors and CI
+	Json,
+}
+
+impl MessageFormat {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"human" => Some(Self::Human),
+			"json" => Some(Self::Json),
+			_ => None,
+		}
+	}
+}
+
+static MESSAGE_FORMAT: OnceLock<MessageFormat> = OnceLock::new();
+
+/// sets the format once, before anything is reported.
+pub fn set_message_format(format: MessageFormat) {
+	let _ = MESSAGE_FORMAT.set(format);
+}
+
+pub fn message_format() -> MessageFormat {
+	MESSAGE_FORMAT.get().copied().unwrap_or_default()
+}
+
 pub fn throw_error(text: impl Into<String>) -> ! {
-	println!("{} {}", text_red("error:"), text_white(text.into().as_str()));
-	std::process::exit(1);
+	let text = text.into();
+	match message_format() {
+		MessageFormat::Json => print_json_message(&Message::error(text), None),
+		MessageFormat::Human => println!("{} {}", text_red("error:"), text_white(text.as_str())),
+	}
+	std::process::exit(EXIT_FAILURE);
 }
 
 pub fn text_red(text: &str) -> String {
@@ -20,18 +57,35 @@ pub fn text_cyan(text: &str) -> String {
 	cyan.apply_to(text).bold().to_string()
 }
 
+pub fn text_yellow(text: &str) -> String {
+	let yellow = Style::new().color256(11);
+	yellow.apply_to(text).bold().to_string()
+}
+
 pub fn text_white(text: &str) -> String {
 	let white = Style::new().white();
 	white.apply_to(text).bold().to_string()
 }
 
 pub fn report_message(message: &Message, source: &Source) -> ! {
+	print_message(message, source);
+	std::process::exit(message.exit_code());
+}
+
+pub fn report_message_without_module(message: &Message) -> ! {
+	print_message_without_module(message);
+	std::process::exit(message.exit_code());
+}
+
+pub fn print_message(message: &Message, source: &Source) {
+	if message_format() == MessageFormat::Json {
+		return print_json_message(message, Some(source));
+	}
 	let Some(range) = message.range else {
-		report_message_without_module(message);
+		return print_message_without_module(message);
 	};
-	let pathname = source.pathname.as_str();
 	println!("{}", message);
-	println!("---> {}", text_white(pathname));
+	println!("---> {}", text_white(&source.location(range.start)));
 
 	let start = range.start;
 	let end = range.end;
@@ -42,18 +96,143 @@ pub fn report_message(message: &Message, source: &Source) -> ! {
 	};
 	println!("{}", code);
 
+	for label in &message.labels {
+		println!("{}", render_label(label, source));
+	}
+
 	for note in &message.notes {
 		println!("{}: {}", text_cyan("help"), text_white(note.text.as_str()));
 	}
-	std::process::exit(1);
 }
 
-pub fn report_message_without_module(message: &Message) -> ! {
+/// a secondary span: its text, where it is and the code it points at.
+fn render_label(label: &Label, source: &Source) -> String {
+	let text = format!("{}: {}", text_cyan("note"), text_white(label.text.as_str()));
+	let location = format!("---> {}", text_white(&source.location(label.range.start)));
+	let code = codelighter::highlight_note(label.range.start, label.range.end, &source.raw);
+	format!("{}\n{}\n{}", text, location, code)
+}
+
+pub fn print_message_without_module(message: &Message) {
+	if message_format() == MessageFormat::Json {
+		return print_json_message(message, None);
+	}
 	// let pathname = source.pathname.as_str();
 	println!("  {}", message);
 	// println!("---> {}", text_white(pathname));
 	for note in &message.notes {
 		println!("  {}: {}", text_cyan("help"), text_white(note.text.as_str()));
 	}
-	std::process::exit(1);
+}
+
+/// prints every message sorted by mod and position, followed by a summary.
+pub fn print_messages(messages: &mut RootMessages, loader: &Loader) {
+	if !messages.any_messages() {
+		return;
+	}
+	for message in messages.sorted() {
+		match message.mod_id.and_then(|mod_id| loader.lookup_source(mod_id)) {
+			Some(source) => print_message(message, source),
+			None => print_message_without_module(message),
+		}
+	}
+	if message_format() == MessageFormat::Json {
+		return;
+	}
+	let summary = messages.summary();
+	match messages.any_errors() {
+		true => println!("{} {}", text_red("failed:"), text_white(&summary)),
+		false => println!("{} {}", text_yellow("finished with"), text_white(&summary)),
+	}
+}
+
+#[derive(Serialize)]
+struct JsonMessage<'m> {
+	severity: &'static str,
+	stage: Option<&'static str>,
+	code: Option<String>,
+	text: &'m str,
+	file: Option<&'m str>,
+	range: Option<JsonRange>,
+	notes: Vec<JsonNote<'m>>,
+	labels: Vec<JsonNote<'m>>,
+}
+
+#[derive(Serialize)]
+struct JsonNote<'m> {
+	text: &'m str,
+	range: Option<JsonRange>,
+}
+
+/// byte offsets plus 1-based lines and columns, the end is exclusive.
+#[derive(Serialize)]
+struct JsonRange {
+	start: usize,
+	end: usize,
+	line: usize,
+	column: usize,
+	end_line: usize,
+	end_column: usize,
+}
+
+impl JsonRange {
+	fn new(range: Range, source: &Source) -> Self {
+		let (line, column) = source.line_column(range.start);
+		let (end_line, end_column) = source.line_column(range.end);
+		Self { start: range.start, end: range.end, line, column, end_line, end_column }
+	}
+}
+
+pub fn print_json_message(message: &Message, source: Option<&Source>) {
+	match json_message(message, source) {
+		Ok(line) => println!("{}", line),
+		Err(err) => eprintln!("failed to serialize message: {}", err),
+	}
+}
+
+/// the line `--message-format json` prints for a message.
+pub fn json_message(message: &Message, source: Option<&Source>) -> serde_json::Result<String> {
+	// a range means nothing without the source it points into
+	let json_range = |range: Option<Range>| Some(JsonRange::new(range?, source?));
+	let json_note = |text, range| JsonNote { text, range: json_range(range) };
+	let json = JsonMessage {
+		severity: message.severity.name(),
+		stage: message.stage.map(|stage| stage.name()),
+		code: message.code.map(|code| code.to_string()),
+		text: message.text.as_str(),
+		file: source.map(|source| source.pathname.as_str()),
+		range: json_range(message.range),
+		notes: message.notes.iter().map(|note| json_note(&note.text, note.range)).collect(),
+		labels: message.labels.iter().map(|label| json_note(&label.text, Some(label.range))).collect(),
+	};
+	serde_json::to_string(&json)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	fn render(raw: &str, start: usize, end: usize) -> String {
+		let source = Source::new(raw.to_string(), PathBuf::from("/main.ln"), "main.ln".to_string());
+		let label = Label::new("first borrow here", Range::new(start, end));
+		console::strip_ansi_codes(&render_label(&label, &source)).to_string()
+	}
+
+	#[test]
+	fn renders_a_label_with_its_location_and_code() {
+		let rendered = render("let a = 1;\nlet b = &a;\n", 19, 21);
+		let mut lines = rendered.lines();
+		assert_eq!(lines.next(), Some("note: first borrow here"));
+		assert_eq!(lines.next(), Some("---> main.ln:2:9"));
+		assert!(rendered.contains("2 | let b = &a;"), "{}", rendered);
+	}
+
+	#[test]
+	fn label_location_counts_chars_across_crlf() {
+		// 'é' is two bytes, the label is on `&é` of the second line
+		let rendered = render("let é = 1;\r\nlet b = &é;\r\n", 21, 24);
+		assert_eq!(rendered.lines().nth(1), Some("---> main.ln:2:9"));
+		assert!(rendered.contains("let b = &é;"), "{}", rendered);
+	}
 }
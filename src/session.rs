@@ -0,0 +1,286 @@
+// the state of `lemon repl`, kept across its inputs.
+use std::path::Path;
+
+use logos::Logos;
+
+use crate::{
+	ast,
+	builder::Builder,
+	checker::{
+		context::Context,
+		types::{Number, Type, TypeId},
+		Checker,
+	},
+	error_syntax, fmt,
+	ir::IR,
+	lexer::Token,
+	loader::{Loader, ModId},
+	parser::Parser,
+	range::Range,
+	report::{print_message_without_module, print_messages},
+};
+
+/// what an input turned out to be, once checked.
+pub enum Checked {
+	/// functions, types and the like, kept for the next inputs
+	Items,
+	/// a `let`, replayed before the next inputs
+	Let,
+	/// statements without a value, or ending with `;`
+	Stmt,
+	/// ends with an expression, `range` is where it is in the input
+	Expr { type_id: TypeId, range: Range },
+}
+
+/// an input built into the function `name`.
+pub struct Compiled {
+	pub ir: IR,
+	pub name: String,
+	/// the type of the returned value, `None` if nothing is returned
+	pub ret: Option<TypeId>,
+}
+
+/// the state kept across inputs. every input is appended to one buffer, so
+/// the ranges the checker recorded for the earlier ones stay valid.
+pub struct Session {
+	pub loader: Loader,
+	pub ctx: Context,
+	pub mod_id: ModId,
+	source: String,
+	/// the checked definitions, built again with every input
+	items: Vec<ast::Stmt>,
+	lets: String,
+	count: usize,
+}
+
+impl Session {
+	pub fn new() -> Self {
+		let (loader, mod_id) = fmt::parse_source(Path::new("repl.ln"), String::new());
+		let mut ctx = Context::new();
+		ctx.add_entry_mod(mod_id);
+		ctx.mod_id = mod_id;
+		ctx.lints = loader.lints().clone();
+		let items = Vec::new();
+		Self { loader, ctx, mod_id, source: String::new(), items, lets: String::new(), count: 0 }
+	}
+
+	/// checks an input, definitions are kept and anything else is checked as
+	/// the body of a function, after the `let`s of the earlier inputs.
+	/// errors are printed and leave the session as it was.
+	pub fn check(&mut self, input: &str) -> Option<Checked> {
+		let offset = self.source.len();
+		let snapshot = self.ctx.clone();
+		let mut stmts = self.append(input)?;
+		if stmts.is_empty() {
+			self.truncate(offset);
+			return Some(Checked::Stmt);
+		}
+		if stmts.iter().all(is_item) {
+			if !self.check_stmts(&mut stmts) {
+				self.rollback(offset, snapshot);
+				return None;
+			}
+			self.items.extend(stmts);
+			return Some(Checked::Items);
+		}
+		self.truncate(offset);
+
+		let header = format!("{{\n{}", self.lets);
+		let input_start = offset + header.len();
+		let mut stmts = self.append(&format!("{}{}\n}}", header, input))?;
+		let [ast::Stmt::Block(block)] = stmts.as_mut_slice() else {
+			self.truncate(offset);
+			print_message_without_module(&error_syntax!("unbalanced braces in the input"));
+			return None;
+		};
+		let mut checker = Checker::new(&mut self.ctx, &mut self.loader);
+		let type_id = checker.check_body_stmts(&mut block.stmts);
+		self.ctx.take_warnings();
+		let type_id = match type_id {
+			Ok(type_id) if !self.report_errors() => type_id,
+			result => {
+				if let Err(message) = result {
+					self.loader.add_message(message.mod_id(self.mod_id));
+					self.report_errors();
+				}
+				self.rollback(offset, snapshot);
+				return None;
+			}
+		};
+		let checked = match block.stmts.last() {
+			Some(ast::Stmt::Let(_)) => {
+				self.lets.push_str(input);
+				self.lets.push('\n');
+				Checked::Let
+			}
+			Some(ast::Stmt::Expr(expr)) if !input.trim_end().ends_with(';') => {
+				let range = expr.get_range();
+				let range = Range::new(range.start - input_start, range.end - input_start);
+				match type_id {
+					Some(type_id) if !type_id.is_empty_type() => Checked::Expr { type_id, range },
+					_ => Checked::Stmt,
+				}
+			}
+			_ => Checked::Stmt,
+		};
+		Some(checked)
+	}
+
+	/// builds a checked input into a function returning its value, with the definitions.
+	pub fn compile(&mut self, input: &str, checked: &Checked) -> Option<Compiled> {
+		let offset = self.source.len();
+		let snapshot = self.ctx.clone();
+		self.count += 1;
+		let name = format!("__repl_{}", self.count);
+		let value = match checked {
+			Checked::Expr { type_id, range } => {
+				let type_id = self.resolve_number(*type_id);
+				self.annotation(type_id).map(|annotation| (type_id, annotation, *range))
+			}
+			_ => None,
+		};
+		let wrapper = match value {
+			Some((_, ref annotation, range)) => {
+				let (before, expr) = (&input[..range.start], &input[range.start..range.end]);
+				format!("fn {}(): {} = {{\n{}{}return {};\n}}", name, annotation, self.lets, before, expr)
+			}
+			None => format!("fn {}() = {{\n{}{}\n}}", name, self.lets, input),
+		};
+		let mut stmts = self.append(&wrapper)?;
+		if !self.check_stmts(&mut stmts) {
+			self.rollback(offset, snapshot);
+			return None;
+		}
+		let mut program = self.items.clone();
+		program.extend(stmts);
+		self.loader.add_mod(self.mod_id, ast::Program { stmts: program, comments: Vec::new() });
+		let mut builder = Builder::new(&self.ctx.type_store, &mut self.ctx.event, &mut self.loader);
+		let ir = builder.build(self.mod_id);
+		Some(Compiled { ir, name, ret: value.map(|(type_id, ..)| type_id) })
+	}
+
+	pub fn display_type(&self, type_id: TypeId) -> String {
+		let mut text = String::new();
+		type_id.display_type(&mut text, &self.ctx.type_store, false);
+		text
+	}
+
+	/// appends `text` to the buffer and parses it, returning its statements.
+	fn append(&mut self, text: &str) -> Option<Vec<ast::Stmt>> {
+		let offset = self.source.len();
+		self.source.push_str(text);
+		self.source.push('\n');
+		self.loader.replace_source(self.mod_id, self.source.clone());
+		let mut lexer = Token::lexer(&self.source);
+		let mut parser = Parser::new(&mut lexer, self.mod_id, &mut self.loader).without_imports();
+		let program = match parser.parse_program() {
+			Ok(program) => program,
+			Err(message) => {
+				self.loader.add_message(message.mod_id(self.mod_id));
+				ast::Program { stmts: Vec::new(), comments: Vec::new() }
+			}
+		};
+		if self.report_errors() {
+			self.truncate(offset);
+			return None;
+		}
+		let stmts = program.stmts.into_iter();
+		Some(stmts.filter(|stmt| stmt.get_range().start >= offset).collect())
+	}
+
+	fn check_stmts(&mut self, stmts: &mut [ast::Stmt]) -> bool {
+		Checker::new(&mut self.ctx, &mut self.loader).check_mod_stmts(self.mod_id, stmts);
+		// the generated functions would only warn about the replayed `let`s
+		self.ctx.take_warnings();
+		!self.report_errors()
+	}
+
+	/// prints the recorded messages if any of them is an error.
+	fn report_errors(&mut self) -> bool {
+		let mut messages = self.loader.take_messages();
+		if !messages.any_errors() {
+			return false;
+		}
+		print_messages(&mut messages, &self.loader);
+		true
+	}
+
+	fn rollback(&mut self, offset: usize, snapshot: Context) {
+		self.ctx = snapshot;
+		self.truncate(offset);
+	}
+
+	fn truncate(&mut self, offset: usize) {
+		self.source.truncate(offset);
+		self.loader.replace_source(self.mod_id, self.source.clone());
+	}
+
+	/// a literal such as `1 + 2` gets the type it defaults to.
+	fn resolve_number(&self, type_id: TypeId) -> TypeId {
+		let Some(Type::NumRange(range)) = self.ctx.type_store.lookup_type(type_id) else {
+			return type_id;
+		};
+		match range.to_number() {
+			Number::F32 => TypeId::F32,
+			Number::F64 => TypeId::F64,
+			Number::I64 => TypeId::I64,
+			_ => TypeId::I32,
+		}
+	}
+
+	/// the return type to write for a value, `None` if it can't be returned.
+	fn annotation(&self, type_id: TypeId) -> Option<String> {
+		match self.ctx.type_store.lookup_type(type_id)? {
+			Type::Bool | Type::Str | Type::Char | Type::Number(_) | Type::Struct(_) => {
+				Some(self.ctx.type_store.lookup_display_ir_type(type_id))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl Default for Session {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+fn is_item(stmt: &ast::Stmt) -> bool {
+	matches!(
+		stmt,
+		ast::Stmt::Fn(_)
+			| ast::Stmt::ExternFn(_)
+			| ast::Stmt::TypeDef(_)
+			| ast::Stmt::Impl(_)
+			| ast::Stmt::ConstDel(_)
+			| ast::Stmt::ConstFn(_)
+	)
+}
+
+/// `{` and `(` not closed yet, delimiters in strings and comments don't count.
+pub fn open_delimiters(text: &str) -> i32 {
+	let mut depth = 0;
+	let mut chars = text.chars().peekable();
+	while let Some(char) = chars.next() {
+		match char {
+			'{' | '(' | '[' => depth += 1,
+			'}' | ')' | ']' => depth -= 1,
+			'"' | '\'' => {
+				while let Some(next) = chars.next() {
+					match next {
+						'\\' => {
+							chars.next();
+						}
+						next if next == char => break,
+						_ => {}
+					}
+				}
+			}
+			'/' if chars.peek() == Some(&'/') => {
+				chars.find(|char| *char == '\n');
+			}
+			_ => {}
+		}
+	}
+	depth
+}
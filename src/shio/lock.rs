@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const LOCK_HEADER: &str = "# generated by lemon, do not edit by hand.\n\n";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedDependency {
+	pub name: String,
+	pub path: String,
+	pub version: String,
+	/// sha256 of the dependency sources, e.g. "sha256:3f1a..."
+	pub hash: String,
+}
+
+impl LockedDependency {
+	pub fn new(name: String, path: String, version: String, hash: String) -> Self {
+		Self { name, path, version, hash }
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShioLock {
+	#[serde(default, rename = "dependency")]
+	pub dependencies: Vec<LockedDependency>,
+}
+
+impl ShioLock {
+	pub fn new(mut dependencies: Vec<LockedDependency>) -> Self {
+		dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+		Self { dependencies }
+	}
+
+	/// loads a shio.lock, a missing lock is empty.
+	pub fn load(path: &Path) -> Result<Self, String> {
+		if !path.is_file() {
+			return Ok(Self::default());
+		}
+		#[rustfmt::skip]
+		let lock_text = fs::read_to_string(path)
+		  .map_err(|_| "failed to read shio.lock".to_string())?;
+		toml::from_str(&lock_text).map_err(|_| "failed to parse shio.lock".to_string())
+	}
+
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		let lock_text = toml::to_string(self).map_err(|_| "failed to write shio.lock".to_string())?;
+		fs::write(path, format!("{}{}", LOCK_HEADER, lock_text))
+			.map_err(|_| "failed to write shio.lock".to_string())
+	}
+
+	pub fn get(&self, name: &str) -> Option<&LockedDependency> {
+		self.dependencies.iter().find(|dependency| dependency.name == name)
+	}
+}
@@ -1,11 +1,26 @@
 #![allow(dead_code, unused_variables)]
+mod lock;
+mod registry;
+mod scaffold;
 mod utils;
 
+pub use lock::*;
+pub use registry::*;
+pub use scaffold::*;
+
+use crate::{
+	file_system::FileSystem,
+	lints::{self, Lint},
+	message::Severity,
+};
 use rustc_hash::FxHashMap;
-use std::{fs, path::PathBuf};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
 use toml::Value;
+use utils::{get_optional_toml_value, get_toml_integer_value, get_toml_text_value};
 use utils::{get_toml_array_value, get_toml_bool_value};
-use utils::{get_toml_integer_value, get_toml_text_value};
 pub struct ShioLoader {
 	pub main: PathBuf,
 	pub cwd: PathBuf,
@@ -22,18 +37,18 @@ impl ShioLoader {
 		let loader = toml.get("loader").ok_or("shio.toml must have a loader section")?;
 		let main: PathBuf = get_toml_text_value(loader, "main")?.into();
 		let cwd = main.parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
-		let strict = get_toml_bool_value(loader, "strict").unwrap_or(true);
+		let strict = get_toml_bool_value(loader, "strict").unwrap_or(false);
 		let max_threads = get_toml_integer_value(loader, "max_threads").unwrap_or(4);
 		Ok(Self::new(main, cwd, strict, max_threads))
 	}
 
 	pub fn with_main(main: PathBuf) -> Self {
 		let cwd = main.parent().map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
-		Self::new(main, cwd, true, 4)
+		Self::new(main, cwd, false, 4)
 	}
 
 	pub fn with_cwd(main: PathBuf, cwd: PathBuf) -> Self {
-		Self::new(main, cwd, true, 4)
+		Self::new(main, cwd, false, 4)
 	}
 }
 
@@ -87,25 +102,40 @@ impl Default for ShioPackage {
 
 pub struct ShioDependencies {
 	local: FxHashMap<String, PathBuf>,
+	/// version requirements, e.g. "math" → "^1.2"
+	versions: FxHashMap<String, String>,
 }
 
 impl ShioDependencies {
 	pub fn new() -> Self {
-		Self { local: FxHashMap::default() }
+		Self { local: FxHashMap::default(), versions: FxHashMap::default() }
 	}
 
 	pub fn from_toml(toml: &Value) -> Result<Self, String> {
-		let mut local = FxHashMap::default();
-
-		if let Some(dependencies) = toml.get("dependencies").and_then(Value::as_table) {
-			for (name, path) in dependencies {
-				if let Some(path_str) = path.as_str() {
-					local.insert(name.clone(), PathBuf::from(path_str));
+		let mut dependencies = Self::new();
+		if let Some(table) = toml.get("dependencies").and_then(Value::as_table) {
+			for (name, dependency) in table {
+				// e.g. `math = "../math"` or `math = { path = "../math", version = "^1.2" }`
+				if let Some(path_str) = dependency.as_str() {
+					dependencies.add_local(name.clone(), PathBuf::from(path_str));
+					continue;
+				}
+				if !dependency.is_table() {
+					return Err(format!("invalid dependency '{}'", name));
+				}
+				let text = |key| {
+					get_optional_toml_value(dependency, key, |value| value.as_str().map(str::to_string))
+						.map_err(|err| format!("{} of dependency '{}', expected a string", err, name))
+				};
+				if let Some(path_str) = text("path")? {
+					dependencies.add_local(name.clone(), PathBuf::from(path_str));
+				}
+				if let Some(version) = text("version")? {
+					dependencies.add_version(name.clone(), version);
 				}
 			}
 		}
-
-		Ok(Self { local })
+		Ok(dependencies)
 	}
 
 	pub fn add_local(&mut self, name: String, path: PathBuf) {
@@ -119,6 +149,22 @@ impl ShioDependencies {
 	pub fn exists_local(&self, name: &str) -> bool {
 		self.local.contains_key(name)
 	}
+
+	pub fn iter_local(&self) -> impl Iterator<Item = (&String, &PathBuf)> {
+		self.local.iter()
+	}
+
+	pub fn add_version(&mut self, name: String, version: String) {
+		self.versions.insert(name, version);
+	}
+
+	pub fn get_version(&self, name: &str) -> Option<&String> {
+		self.versions.get(name)
+	}
+
+	pub fn iter_versions(&self) -> impl Iterator<Item = (&String, &String)> {
+		self.versions.iter()
+	}
 }
 
 impl Default for ShioDependencies {
@@ -127,15 +173,161 @@ impl Default for ShioDependencies {
 	}
 }
 
+/// lint levels from `[lints]`, e.g. `unused_variable = "deny"`.
+#[derive(Default)]
+pub struct ShioLints {
+	pub levels: Vec<(Lint, Severity)>,
+}
+
+impl ShioLints {
+	pub fn from_toml(toml: &Value) -> Result<Self, String> {
+		let Some(table) = toml.get("lints").and_then(Value::as_table) else {
+			return Ok(Self::default());
+		};
+		let mut levels = Vec::with_capacity(table.len());
+		for (name, level) in table {
+			let lint = Lint::from_name(name).ok_or(format!("unknown lint '{}' in [lints]", name))?;
+			let level = level
+				.as_str()
+				.and_then(lints::parse_level)
+				.ok_or(format!("invalid level of lint '{}', expected allow, warn or deny", name))?;
+			levels.push((lint, level));
+		}
+		Ok(Self { levels })
+	}
+}
+
+/// defaults for `lemon compile`, flags take precedence.
+#[derive(Default)]
+pub struct ShioBuild {
+	/// target triple, e.g. "x86_64-unknown-linux-gnu"
+	pub target: Option<String>,
+	/// one of "mold", "lld" or "clang"
+	pub linker: Option<String>,
+	/// from 0 (none) to 3 (aggressive)
+	pub opt_level: Option<usize>,
+	/// where objects and binaries go, relative to the shio.toml dir
+	pub out_dir: Option<PathBuf>,
+}
+
+impl ShioBuild {
+	pub fn from_toml(toml: &Value) -> Result<Self, String> {
+		let build = match toml.get("build") {
+			Some(build) => build,
+			None => return Ok(Self::default()),
+		};
+		let text = |key| {
+			get_optional_toml_value(build, key, |value| value.as_str().map(str::to_string))
+				.map_err(|err| format!("{} in [build], expected a string", err))
+		};
+		let target = text("target")?;
+		let linker = text("linker")?;
+		if let Some(linker) = linker.as_deref() {
+			if !["mold", "lld", "clang"].contains(&linker) {
+				return Err(format!("unknown linker '{}', expected mold, lld or clang", linker));
+			}
+		}
+		let opt_level = get_optional_toml_value(build, "opt_level", Value::as_integer)
+			.map_err(|err| format!("{} in [build], expected an integer", err))?;
+		let opt_level = match opt_level {
+			Some(level @ 0..=3) => Some(level as usize),
+			Some(_) => return Err("opt_level must be between 0 and 3".to_string()),
+			None => None,
+		};
+		let out_dir = text("out_dir")?.map(PathBuf::from);
+		Ok(Self { target, linker, opt_level, out_dir })
+	}
+}
+
+pub struct ShioWorkspace {
+	/// directory holding the workspace shio.toml
+	pub root: PathBuf,
+	/// package dirs of the members, relative to the root
+	pub members: Vec<PathBuf>,
+}
+
+impl ShioWorkspace {
+	pub fn new(root: PathBuf, members: Vec<PathBuf>) -> Self {
+		Self { root, members }
+	}
+
+	pub fn from_toml(toml: &Value, root: PathBuf) -> Result<Option<Self>, String> {
+		let workspace = match toml.get("workspace") {
+			Some(workspace) => workspace,
+			None => return Ok(None),
+		};
+		let members = get_toml_array_value(workspace, "members")?;
+		let members = members.iter().map(|member| root.join(member)).collect();
+		Ok(Some(Self::new(root, members)))
+	}
+
+	/// loads the shio.toml of every member.
+	pub fn load_members(&self) -> Result<Vec<ShioConfig>, String> {
+		let load_member = |member: &PathBuf| {
+			ShioConfig::load_from_dir(member)
+				.map_err(|err| format!("workspace member '{}', {}", member.display(), err))
+		};
+		self.members.iter().map(load_member).collect()
+	}
+}
+
+/// the packages of the shio.toml at `path` (./shio.toml by default) with their file systems:
+/// every member of its workspace, which import each other by package name, or the package itself.
+pub fn load_packages(path: Option<PathBuf>) -> Result<Vec<(ShioConfig, FileSystem)>, String> {
+	let workspace = match ShioConfig::load_workspace(path.clone())? {
+		Some(workspace) => workspace,
+		None => {
+			// paths in a shio.toml elsewhere are relative to its dir
+			let shio = match path {
+				Some(path) => ShioConfig::load_from_dir(&ShioConfig::root_of(&path))?,
+				None => ShioConfig::load_from_toml(None)?,
+			};
+			let cwd = shio.loader.cwd.clone();
+			return Ok(vec![(shio, FileSystem::from_current_dir(cwd))]);
+		}
+	};
+	let members = workspace.load_members()?;
+	let member_dirs: Vec<(String, PathBuf)> =
+		members.iter().map(|member| (member.package.name.clone(), member.root.clone())).collect();
+	let into_package = |shio: ShioConfig| {
+		let cwd = shio.loader.cwd.clone();
+		let mut file_system = FileSystem::from_workspace(cwd, workspace.root.clone());
+		for (name, dir) in member_dirs.iter() {
+			file_system.add_workspace_member(name.clone(), dir.clone());
+		}
+		(shio, file_system)
+	};
+	Ok(members.into_iter().map(into_package).collect())
+}
+
 pub struct ShioConfig {
 	pub package: ShioPackage,
 	pub loader: ShioLoader,
 	pub dependencies: ShioDependencies,
+	/// directory holding the shio.toml, local dependency paths are relative to it
+	pub root: PathBuf,
+	/// registry dir from `[registry] dir`, defaults to ~/.shio/remote
+	pub registry: Option<PathBuf>,
+	pub build: ShioBuild,
+	pub lints: ShioLints,
 }
 
 impl ShioConfig {
-	pub fn new(package: ShioPackage, loader: ShioLoader, dependencies: ShioDependencies) -> Self {
-		Self { package, loader, dependencies }
+	pub fn new(
+		package: ShioPackage,
+		loader: ShioLoader,
+		dependencies: ShioDependencies,
+		root: PathBuf,
+	) -> Self {
+		Self {
+			package,
+			loader,
+			dependencies,
+			root,
+			registry: None,
+			build: ShioBuild::default(),
+			lints: ShioLints::default(),
+		}
 	}
 
 	fn get_shio_toml_path() -> PathBuf {
@@ -143,8 +335,7 @@ impl ShioConfig {
 		PathBuf::from("./shio.toml")
 	}
 
-	pub fn load_from_toml(path: Option<PathBuf>) -> Result<Self, String> {
-		let path = path.unwrap_or(Self::get_shio_toml_path());
+	fn read_toml(path: &Path) -> Result<Value, String> {
 		#[rustfmt::skip]
 		let shio_text = fs::read_to_string(path)
 		  .map_err(|_| "failed to read shio.toml".to_string())?;
@@ -152,17 +343,62 @@ impl ShioConfig {
 		#[rustfmt::skip]
 		let shio_toml: Value  = toml::from_str(&shio_text)
 			.map_err(|_| "failed to parse shio.toml".to_string())?;
+		Ok(shio_toml)
+	}
+
+	/// loads the `[workspace]` section, if the shio.toml has one.
+	pub fn load_workspace(path: Option<PathBuf>) -> Result<Option<ShioWorkspace>, String> {
+		let path = path.unwrap_or(Self::get_shio_toml_path());
+		let shio_toml = Self::read_toml(&path)?;
+		ShioWorkspace::from_toml(&shio_toml, Self::root_of(&path))
+	}
 
+	pub fn load_from_toml(path: Option<PathBuf>) -> Result<Self, String> {
+		let path = path.unwrap_or(Self::get_shio_toml_path());
+		let shio_toml = Self::read_toml(&path)?;
 		let package = ShioPackage::from_toml(&shio_toml)?;
 		let loader = ShioLoader::from_toml(&shio_toml)?;
 		let dependencies = ShioDependencies::from_toml(&shio_toml)?;
-		Ok(Self::new(package, loader, dependencies))
+		let root = Self::root_of(&path);
+		let mut shio = Self::new(package, loader, dependencies, root);
+		if let Some(registry) = shio_toml.get("registry") {
+			shio.registry = Some(shio.root.join(get_toml_text_value(registry, "dir")?));
+		}
+		shio.build = ShioBuild::from_toml(&shio_toml)?;
+		shio.lints = ShioLints::from_toml(&shio_toml)?;
+		Ok(shio)
+	}
+
+	/// where `lemon compile` puts the object of the package, the binary drops the `.o`.
+	pub fn output_path(&self) -> PathBuf {
+		let out_dir = self.root.join(self.build.out_dir.clone().unwrap_or_default());
+		out_dir.join(format!("{}.o", self.package.name))
+	}
+
+	/// loads the shio.toml of a package directory, e.g. a dependency.
+	pub fn load_from_dir(dir: &Path) -> Result<Self, String> {
+		let mut shio = Self::load_from_toml(Some(dir.join("shio.toml")))?;
+		shio.loader.main = dir.join(&shio.loader.main);
+		shio.loader.cwd = dir.join(&shio.loader.cwd);
+		Ok(shio)
+	}
+
+	fn root_of(path: &Path) -> PathBuf {
+		match path.parent() {
+			Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+			_ => PathBuf::from("."),
+		}
 	}
 
 	pub fn with_defaults(main: PathBuf) -> Self {
-		let package = ShioPackage::default();
+		let mut package = ShioPackage::default();
+		// a single file package is named after the file, e.g. `main.ln` → main
+		if let Some(name) = main.file_stem() {
+			package.name = name.to_string_lossy().to_string();
+		}
 		let loader = ShioLoader::with_main(main);
 		let dependencies = ShioDependencies::new();
-		Self::new(package, loader, dependencies)
+		let root = loader.cwd.clone();
+		Self::new(package, loader, dependencies, root)
 	}
 }
@@ -0,0 +1,109 @@
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+use super::ShioConfig;
+
+const INDEX_FILE: &str = "index.toml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+	pub name: String,
+	pub version: String,
+	/// package dir, relative to the registry (e.g., "math/1.4.0")
+	pub path: String,
+	pub hash: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegistryIndex {
+	#[serde(default, rename = "package")]
+	pub packages: Vec<RegistryEntry>,
+}
+
+/// a registry on the file system (e.g., ~/.shio/remote or a network share):
+/// an index.toml plus a `<name>/<version>` dir for every published package.
+pub struct ShioRegistry {
+	pub dir: PathBuf,
+	pub index: RegistryIndex,
+}
+
+impl ShioRegistry {
+	/// opens a registry, a missing index is an empty registry.
+	pub fn open(dir: &Path) -> Result<Self, String> {
+		let index_path = dir.join(INDEX_FILE);
+		if !index_path.is_file() {
+			return Ok(Self { dir: dir.to_path_buf(), index: RegistryIndex::default() });
+		}
+		#[rustfmt::skip]
+		let index_text = fs::read_to_string(&index_path)
+		  .map_err(|_| format!("failed to read '{}'", index_path.display()))?;
+		let index = toml::from_str(&index_text)
+			.map_err(|_| format!("failed to parse '{}'", index_path.display()))?;
+		Ok(Self { dir: dir.to_path_buf(), index })
+	}
+
+	/// finds the highest version of a package matching every requirement.
+	pub fn find(&self, name: &str, requirements: &[VersionReq]) -> Option<&RegistryEntry> {
+		let mut found: Option<(Version, &RegistryEntry)> = None;
+		for entry in self.index.packages.iter().filter(|entry| entry.name == name) {
+			let Ok(version) = Version::parse(&entry.version) else { continue };
+			if !requirements.iter().all(|requirement| requirement.matches(&version)) {
+				continue;
+			}
+			if found.as_ref().is_none_or(|(best, _)| version > *best) {
+				found = Some((version, entry));
+			}
+		}
+		found.map(|(_, entry)| entry)
+	}
+
+	pub fn package_dir(&self, entry: &RegistryEntry) -> PathBuf {
+		self.dir.join(&entry.path)
+	}
+
+	/// copies a package (shio.toml and sources) to `<name>/<version>` and adds it to the index.
+	pub fn publish(
+		&mut self,
+		shio: &ShioConfig,
+		sources: &[PathBuf],
+		hash: String,
+	) -> Result<PathBuf, String> {
+		let (name, version) = (&shio.package.name, &shio.package.version);
+		Version::parse(version).map_err(|_| format!("invalid version '{}' of '{}'", version, name))?;
+		if self.index.packages.iter().any(|entry| &entry.name == name && &entry.version == version) {
+			return Err(format!("'{}' {} is already published", name, version));
+		}
+		let relative = format!("{}/{}", name, version);
+		let package_dir = self.dir.join(&relative);
+		let root = &shio.root;
+		let files = sources.iter().cloned().chain(Some(root.join("shio.toml")));
+		for file in files {
+			let relative_file = file
+				.strip_prefix(root)
+				.map_err(|_| format!("'{}' is outside the package", file.display()))?;
+			let target = package_dir.join(relative_file);
+			if let Some(parent) = target.parent() {
+				fs::create_dir_all(parent)
+					.map_err(|_| format!("failed to create '{}'", parent.display()))?;
+			}
+			fs::copy(&file, &target).map_err(|_| format!("failed to copy '{}'", file.display()))?;
+		}
+		let entry =
+			RegistryEntry { name: name.clone(), version: version.clone(), path: relative, hash };
+		self.index.packages.push(entry);
+		self.save()?;
+		Ok(package_dir)
+	}
+
+	fn save(&self) -> Result<(), String> {
+		let index_path = self.dir.join(INDEX_FILE);
+		let index_text =
+			toml::to_string(&self.index).map_err(|_| "failed to write registry index".to_string())?;
+		fs::write(&index_path, index_text)
+			.map_err(|_| format!("failed to write '{}'", index_path.display()))
+	}
+}
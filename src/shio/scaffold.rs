@@ -0,0 +1,102 @@
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+const MAIN_ENTRY: &str = "src/main.ln";
+const LIB_ENTRY: &str = "src/lib.ln";
+
+const MAIN_TEMPLATE: &str = r#"extern fn printf(fmt: str, ...): i32 = {};
+
+fn main() = {
+  printf("hello, world!\n");
+}
+"#;
+
+const LIB_TEMPLATE: &str = r#"pub fn add(a: i32, b: i32): i32 = {
+  return a + b;
+}
+"#;
+
+/// `lemon new`, creates a package in a new `name` dir under `parent`.
+pub fn new_package(parent: &Path, name: &str, is_lib: bool) -> Result<PathBuf, String> {
+	validate_package_name(name)?;
+	let dir = parent.join(name);
+	if dir.exists() {
+		return Err(format!("'{}' already exists", dir.display()));
+	}
+	create_package(&dir, name, is_lib)?;
+	Ok(dir)
+}
+
+/// `lemon init`, creates a package in an existing dir, named after it by default.
+pub fn init_package(dir: &Path, name: Option<&str>, is_lib: bool) -> Result<String, String> {
+	let name = match name {
+		Some(name) => name.to_string(),
+		None => match dir.file_name() {
+			Some(name) => name.to_string_lossy().to_string(),
+			None => return Err(format!("'{}' has no name to use, pass one", dir.display())),
+		},
+	};
+	create_package(dir, &name, is_lib)?;
+	Ok(name)
+}
+
+/// creates a package in `dir`: a shio.toml, a main (or lib) entry and a .gitignore.
+pub fn create_package(dir: &Path, name: &str, is_lib: bool) -> Result<(), String> {
+	validate_package_name(name)?;
+	if dir.join("shio.toml").exists() {
+		return Err(format!("'{}' already has a shio.toml", dir.display()));
+	}
+	let (entry, template) =
+		if is_lib { (LIB_ENTRY, LIB_TEMPLATE) } else { (MAIN_ENTRY, MAIN_TEMPLATE) };
+	let entry_path = dir.join(entry);
+	if entry_path.exists() {
+		return Err(format!("'{}' already exists", entry_path.display()));
+	}
+	let src_dir = dir.join("src");
+	fs::create_dir_all(&src_dir).map_err(|_| format!("failed to create '{}'", src_dir.display()))?;
+	write_file(&dir.join("shio.toml"), &shio_toml_template(name, entry))?;
+	write_file(&entry_path, template)?;
+	// keep an existing .gitignore, e.g. `lemon init` in a repository
+	let gitignore = dir.join(".gitignore");
+	if !gitignore.exists() {
+		write_file(&gitignore, &gitignore_template(name))?;
+	}
+	Ok(())
+}
+
+fn shio_toml_template(name: &str, entry: &str) -> String {
+	let mut text = String::new();
+	text.push_str("[package]\n");
+	text.push_str(&format!("name = \"{}\"\n", name));
+	text.push_str("version = \"0.1.0\"\n");
+	text.push_str("description = \"\"\n");
+	text.push_str("authors = []\n");
+	text.push_str("license = \"\"\n\n");
+	text.push_str("[loader]\n");
+	text.push_str(&format!("main = \"{}\"\n\n", entry));
+	text.push_str("[dependencies]\n");
+	text
+}
+
+fn gitignore_template(name: &str) -> String {
+	// build outputs: objects and the linked binary
+	format!("*.o\n/{}\n", name)
+}
+
+fn validate_package_name(name: &str) -> Result<(), String> {
+	if name.is_empty() {
+		return Err("package name can't be empty".to_string());
+	}
+	let is_valid_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+	let starts_with_letter = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic());
+	if !starts_with_letter || !name.chars().all(is_valid_char) {
+		return Err(format!("invalid package name '{}', use letters, digits, '_' or '-'", name));
+	}
+	Ok(())
+}
+
+fn write_file(path: &Path, text: &str) -> Result<(), String> {
+	fs::write(path, text).map_err(|_| format!("failed to write '{}'", path.display()))
+}
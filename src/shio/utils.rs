@@ -7,6 +7,21 @@ where
 	value.get(key).and_then(&convert).ok_or_else(|| format!("missing or invalid key {}", key))
 }
 
+/// a key that may be left out, but has to have the right type when it's there.
+pub fn get_optional_toml_value<T, F>(
+	value: &Value,
+	key: &str,
+	convert: F,
+) -> Result<Option<T>, String>
+where
+	F: Fn(&Value) -> Option<T>,
+{
+	match value.get(key) {
+		Some(found) => convert(found).map(Some).ok_or_else(|| format!("invalid key {}", key)),
+		None => Ok(None),
+	}
+}
+
 pub fn get_toml_text_value(value: &Value, key: &str) -> Result<String, String> {
 	get_toml_value(value, key, |v| v.as_str().map(|s| s.to_string()))
 }
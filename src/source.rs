@@ -5,10 +5,118 @@ pub struct Source {
 	pub raw: String,
 	pub abs_path: PathBuf,
 	pub pathname: String,
+	pub lines: LineIndex,
 }
 
 impl Source {
 	pub fn new(raw: String, abs_path: PathBuf, pathname: String) -> Self {
-		Self { raw, abs_path, pathname }
+		let lines = LineIndex::new(&raw);
+		Self { raw, abs_path, pathname, lines }
+	}
+
+	/// 1-based line and column of a byte offset.
+	pub fn line_column(&self, offset: usize) -> (usize, usize) {
+		self.lines.line_column(&self.raw, offset)
+	}
+
+	/// e.g. "src/main.ln:3:7"
+	pub fn location(&self, offset: usize) -> String {
+		let (line, column) = self.line_column(offset);
+		format!("{}:{}:{}", self.pathname, line, column)
+	}
+}
+
+/// byte offset where every line starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineIndex {
+	line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+	pub fn new(raw: &str) -> Self {
+		let breaks = raw.match_indices('\n').map(|(at, _)| at + 1);
+		Self { line_starts: std::iter::once(0).chain(breaks).collect() }
+	}
+
+	/// columns count chars, not bytes, an offset inside a char is on that char.
+	pub fn line_column(&self, raw: &str, offset: usize) -> (usize, usize) {
+		let mut offset = offset.min(raw.len());
+		while !raw.is_char_boundary(offset) {
+			offset -= 1;
+		}
+		let line = self.line_starts.partition_point(|start| *start <= offset);
+		let line_start = self.line_starts[line - 1];
+		(line, raw[line_start..offset].chars().count() + 1)
+	}
+
+	/// byte offset where a 1-based line starts.
+	pub fn line_start(&self, line: usize) -> Option<usize> {
+		self.line_starts.get(line.checked_sub(1)?).copied()
+	}
+
+	pub fn len(&self) -> usize {
+		self.line_starts.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.line_starts.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn source_of(raw: &str) -> Source {
+		Source::new(raw.to_string(), PathBuf::from("/main.ln"), "main.ln".to_string())
+	}
+
+	#[test]
+	fn indexes_line_starts() {
+		let lines = LineIndex::new("a\nbc\n\nd");
+		assert_eq!(lines.len(), 4);
+		assert_eq!(lines.line_start(1), Some(0));
+		assert_eq!(lines.line_start(3), Some(5));
+		assert_eq!(lines.line_start(0), None);
+		assert_eq!(lines.line_start(5), None);
+	}
+
+	#[test]
+	fn counts_lines_and_columns_from_one() {
+		let source = source_of("let a = 1;\nlet b = a;\n");
+		assert_eq!(source.line_column(0), (1, 1));
+		assert_eq!(source.line_column(4), (1, 5));
+		assert_eq!(source.line_column(10), (1, 11), "the newline ends its line");
+		assert_eq!(source.line_column(11), (2, 1));
+		assert_eq!(source.location(19), "main.ln:2:9");
+	}
+
+	#[test]
+	fn crlf_breaks_lines_like_lf() {
+		let source = source_of("let a = 1;\r\nlet b = a;\r\n");
+		assert_eq!(source.line_column(10), (1, 11), "the \\r is the last char of its line");
+		assert_eq!(source.line_column(12), (2, 1));
+		assert_eq!(source.location(20), "main.ln:2:9");
+	}
+
+	#[test]
+	fn columns_count_chars_not_bytes() {
+		// 'é' and 'λ' are two bytes, '🍋' is four
+		let source = source_of("é λ = \"🍋\";\nx");
+		assert_eq!(source.line_column(3), (1, 3), "'λ' starts after two chars");
+		assert_eq!(source.line_column(9), (1, 8), "'🍋' starts after seven");
+		assert_eq!(source.line_column(13), (1, 9), "the '\"' after it");
+		assert_eq!(source.line_column(11), (1, 8), "inside '🍋' is on it");
+		assert_eq!(source.location(16), "main.ln:2:1");
+	}
+
+	#[test]
+	fn end_of_file_is_after_the_last_char() {
+		let source = source_of("ab\ncd");
+		assert_eq!(source.line_column(5), (2, 3));
+		assert_eq!(source.line_column(99), (2, 3), "past the end is clamped");
+		let trailing = source_of("ab\n");
+		assert_eq!(trailing.line_column(3), (2, 1), "a trailing newline starts an empty line");
+		assert_eq!(source_of("").location(0), "main.ln:1:1");
 	}
 }
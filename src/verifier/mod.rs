@@ -0,0 +1,158 @@
+use std::fmt::Display;
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+	checker::types::{TypeId, TypeStore},
+	error_build,
+	ir::{self, IrBasicValue},
+	message::Message,
+};
+
+mod ownership;
+mod registers;
+mod types;
+
+pub use ownership::verify_ownership;
+
+/// checks the ir the builder made before a backend gets it: every block ends in
+/// a terminator, jumps land on blocks, registers are defined before they're used,
+/// operands have the types their instructions expect and only heap values are dropped.
+pub fn verify_ir(ir: &ir::IR, type_store: &TypeStore) -> Vec<Message> {
+	let mut verifier = Verifier::new(ir, type_store);
+	for function in ir.functions.iter().filter(|function| !function.is_extern_function()) {
+		verifier.verify_function(function);
+	}
+	verifier.messages
+}
+
+pub struct Verifier<'ir> {
+	ir: &'ir ir::IR,
+	type_store: &'ir TypeStore,
+	functions: FxHashMap<&'ir str, &'ir ir::Function>,
+	messages: Vec<Message>,
+}
+
+impl<'ir> Verifier<'ir> {
+	pub fn new(ir: &'ir ir::IR, type_store: &'ir TypeStore) -> Self {
+		let functions =
+			ir.functions.iter().map(|function| (function.name.as_str(), function)).collect();
+		Self { ir, type_store, functions, messages: Vec::new() }
+	}
+
+	fn verify_function(&mut self, function: &'ir ir::Function) {
+		if function.blocks.is_empty() {
+			self.messages.push(error_build!("'{}' has no blocks", function.name).code(804));
+			return;
+		}
+		// label -> index in the blocks
+		let mut labels = FxHashMap::default();
+		for (index, block) in function.blocks.iter().enumerate() {
+			if labels.insert(block.label, index).is_some() {
+				self.error(function, block, 804, "is defined twice");
+			}
+		}
+		let mut sound = true;
+		for block in &function.blocks {
+			sound &= self.verify_terminator(function, block, &labels);
+		}
+		// registers are followed along the jumps, which must land
+		if sound {
+			self.verify_registers(function, &labels);
+		}
+		self.verify_types(function);
+	}
+
+	/// the last instruction, and only it, is a `jmp`, `jmp_if` or `ret`.
+	fn verify_terminator(
+		&mut self,
+		function: &ir::Function,
+		block: &ir::IrBlock,
+		labels: &FxHashMap<usize, usize>,
+	) -> bool {
+		let Some((last, instrs)) = block.instrs().split_last().filter(|(last, _)| is_terminator(last))
+		else {
+			self.error(function, block, 804, "doesn't end in 'jmp', 'jmp_if' or 'ret'");
+			return false;
+		};
+		if instrs.iter().any(is_terminator) {
+			self.error(function, block, 804, "has instructions after its 'jmp', 'jmp_if' or 'ret'");
+			return false;
+		}
+		let entry = function.blocks[0].label;
+		let mut sound = true;
+		for label in successors(last) {
+			if !labels.contains_key(&label) {
+				self.error(function, block, 804, format!("jumps to 'blk{}', which doesn't exist", label));
+				sound = false;
+			} else if label == entry {
+				self.error(function, block, 804, format!("jumps to the entry block 'blk{}'", label));
+				sound = false;
+			}
+		}
+		sound
+	}
+
+	fn error(&mut self, function: &ir::Function, block: &ir::IrBlock, code: u16, text: impl Display) {
+		let message =
+			error_build!("'{}' in '{}' {}", block.format_label(), function.name, text).code(code);
+		self.messages.push(message);
+	}
+
+	fn display_type(&self, type_id: TypeId) -> String {
+		self.type_store.lookup_display_ir_type(type_id)
+	}
+}
+
+fn is_terminator(instr: &ir::Instr) -> bool {
+	matches!(instr, ir::Instr::Jmp(_) | ir::Instr::JmpIf(_) | ir::Instr::Ret(_))
+}
+
+/// the labels a terminator jumps to.
+fn successors(instr: &ir::Instr) -> Vec<usize> {
+	match instr {
+		ir::Instr::Jmp(jmp) => vec![jmp.label],
+		ir::Instr::JmpIf(jmp_if) => vec![jmp_if.true_label, jmp_if.false_label],
+		_ => Vec::new(),
+	}
+}
+
+/// the values an instruction reads, and the register it defines. the dest of a
+/// binary instruction, a `set` or a call is a slot written to, it's read as well.
+fn operands(instr: &ir::Instr) -> (Vec<&IrBasicValue>, Option<&IrBasicValue>) {
+	match instr {
+		ir::Instr::Add(bin)
+		| ir::Instr::Sub(bin)
+		| ir::Instr::Mul(bin)
+		| ir::Instr::Div(bin)
+		| ir::Instr::Mod(bin)
+		| ir::Instr::Neg(bin)
+		| ir::Instr::Not(bin)
+		| ir::Instr::CmpEq(bin)
+		| ir::Instr::CmpNe(bin)
+		| ir::Instr::CmpLt(bin)
+		| ir::Instr::CmpGt(bin)
+		| ir::Instr::CmpLe(bin)
+		| ir::Instr::CmpGe(bin)
+		| ir::Instr::And(bin)
+		| ir::Instr::Or(bin)
+		| ir::Instr::Shl(bin)
+		| ir::Instr::Shr(bin) => (vec![&bin.left, &bin.right, &bin.dest], None),
+		ir::Instr::Set(unary) | ir::Instr::Mov(unary) => (vec![&unary.src, &unary.dest], None),
+		ir::Instr::Load(unary) | ir::Instr::Halloc(unary) => (vec![&unary.src], Some(&unary.dest)),
+		ir::Instr::Salloc(salloc) => (Vec::new(), Some(&salloc.dest)),
+		ir::Instr::Getptr(getptr) => (vec![&getptr.self_base], Some(&getptr.dest)),
+		ir::Instr::Drop(value) => (vec![value], None),
+		ir::Instr::Ret(value) => (value.iter().collect(), None),
+		ir::Instr::Jmp(_) => (Vec::new(), None),
+		ir::Instr::JmpIf(jmp_if) => (vec![&jmp_if.cond], None),
+		ir::Instr::Call(call) => {
+			let mut uses: Vec<_> = call.args.iter().collect();
+			// a call without a value has a dest nobody reads
+			if !call.dest.type_id.is_empty_type() {
+				uses.push(&call.dest);
+			}
+			(uses, None)
+		}
+	}
+}
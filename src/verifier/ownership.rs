@@ -0,0 +1,287 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{operands, successors};
+use crate::{
+	checker::types::TypeStore,
+	ir::{self, IrBasicValue},
+	message::{Message, Stage},
+	range::Range,
+};
+
+/// what a path did with a heap value so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+	Owned,
+	Dropped,
+	// returned, the caller owns it now
+	Moved,
+	// the paths into a block disagree
+	Partly,
+}
+
+impl State {
+	fn join(self, other: State) -> State {
+		match (self, other) {
+			(left, right) if left == right => left,
+			(State::Dropped | State::Moved, State::Dropped | State::Moved) => State::Dropped,
+			_ => State::Partly,
+		}
+	}
+}
+
+type States<'ir> = FxHashMap<&'ir str, State>;
+
+/// checks that every `heap` is dropped exactly once on every path, that nothing
+/// is used after its `drop` and that borrowed values aren't moved.
+pub fn verify_ownership(ir: &ir::IR, type_store: &TypeStore) -> Vec<Message> {
+	let mut messages = Vec::new();
+	for function in ir.functions.iter().filter(|function| !function.blocks.is_empty()) {
+		let mut ownership = Ownership::new(function, type_store);
+		ownership.verify();
+		messages.extend(ownership.messages);
+	}
+	messages
+}
+
+struct Ownership<'ir> {
+	function: &'ir ir::Function,
+	type_store: &'ir TypeStore,
+	// heap register -> where it was allocated
+	allocs: FxHashMap<&'ir str, Option<Range>>,
+	// getptr register -> the heap register it points into
+	roots: FxHashMap<&'ir str, &'ir str>,
+	// a value is reported once per code
+	reported: FxHashSet<(&'ir str, u16)>,
+	messages: Vec<Message>,
+}
+
+impl<'ir> Ownership<'ir> {
+	fn new(function: &'ir ir::Function, type_store: &'ir TypeStore) -> Self {
+		let mut allocs = FxHashMap::default();
+		let mut roots = FxHashMap::default();
+		for block in &function.blocks {
+			for (index, instr) in block.instrs().iter().enumerate() {
+				match instr {
+					ir::Instr::Halloc(unary) if unary.dest.is_register() => {
+						let register = unary.dest.value.as_str();
+						allocs.entry(register).or_insert_with(|| block.instr_range(index));
+						roots.insert(register, register);
+					}
+					ir::Instr::Getptr(getptr) if getptr.self_base.is_register() => {
+						let base = getptr.self_base.value.as_str();
+						if let Some(root) = roots.get(base).copied() {
+							roots.insert(getptr.dest.value.as_str(), root);
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+		let reported = FxHashSet::default();
+		Self { function, type_store, allocs, roots, reported, messages: Vec::new() }
+	}
+
+	fn verify(&mut self) {
+		let blocks = &self.function.blocks;
+		let labels: FxHashMap<usize, usize> =
+			blocks.iter().enumerate().map(|(index, block)| (block.label, index)).collect();
+		let mut predecessors = vec![Vec::new(); blocks.len()];
+		for (index, block) in blocks.iter().enumerate() {
+			// a jump nowhere is the verifier's to report
+			let targets = block.instrs().last().map(successors).unwrap_or_default();
+			for target in targets.into_iter().filter_map(|label| labels.get(&label)) {
+				predecessors[*target].push(index);
+			}
+		}
+
+		// the states at the end of each block, `None` until a path reaches it
+		let mut states_out: Vec<Option<States>> = vec![None; blocks.len()];
+		let mut changed = true;
+		while changed {
+			changed = false;
+			for (index, block) in blocks.iter().enumerate() {
+				let Some(mut states) = states_in(index, &predecessors, &states_out) else {
+					continue;
+				};
+				for (position, instr) in block.instrs().iter().enumerate() {
+					self.transfer(block, position, instr, &mut states, false);
+				}
+				if states_out[index].as_ref() != Some(&states) {
+					states_out[index] = Some(states);
+					changed = true;
+				}
+			}
+		}
+
+		for (index, block) in blocks.iter().enumerate() {
+			let Some(mut states) = states_in(index, &predecessors, &states_out) else {
+				continue;
+			};
+			for (position, instr) in block.instrs().iter().enumerate() {
+				self.transfer(block, position, instr, &mut states, true);
+			}
+		}
+	}
+
+	/// applies an instruction to the states, reporting what it breaks once the states are settled.
+	fn transfer(
+		&mut self,
+		block: &'ir ir::IrBlock,
+		position: usize,
+		instr: &'ir ir::Instr,
+		states: &mut States<'ir>,
+		report: bool,
+	) {
+		let range = block.instr_range(position);
+		match instr {
+			ir::Instr::Halloc(unary) if unary.dest.is_register() => {
+				let register = unary.dest.value.as_str();
+				if report && matches!(states.get(register), Some(State::Owned | State::Partly)) {
+					self.error(register, 209, "is allocated again before it is dropped", range);
+				}
+				states.insert(register, State::Owned);
+			}
+			ir::Instr::Drop(value) if value.is_register() => {
+				let register = value.value.as_str();
+				let Some(state) = states.get(register).copied() else {
+					if report && self.allocs.contains_key(register) {
+						self.error(register, 210, "is dropped on a path that never allocates it", range);
+					}
+					return;
+				};
+				if report {
+					match state {
+						State::Owned => {}
+						State::Dropped => self.error(register, 210, "is dropped twice", range),
+						State::Moved => self.error(register, 210, "is dropped after it was moved", range),
+						State::Partly => self.error(register, 210, "is dropped again on some paths", range),
+					}
+				}
+				states.insert(register, State::Dropped);
+			}
+			ir::Instr::Ret(value) => {
+				if let Some(value) = value {
+					self.verify_move(value, range, states, report);
+				}
+				if report {
+					self.verify_dropped(states, range);
+				}
+			}
+			ir::Instr::Mov(unary) => {
+				self.verify_use(&unary.dest, range, states, report);
+				self.verify_move(&unary.src, range, states, report);
+			}
+			_ => {
+				let (uses, _) = operands(instr);
+				for value in uses {
+					self.verify_use(value, range, states, report);
+				}
+			}
+		}
+	}
+
+	/// a moved heap value is the new owner's to drop, a borrow can't be moved at all.
+	fn verify_move(
+		&mut self,
+		value: &'ir IrBasicValue,
+		range: Option<Range>,
+		states: &mut States<'ir>,
+		report: bool,
+	) {
+		if !value.is_register() {
+			return;
+		}
+		let register = value.value.as_str();
+		let returns_borrow = self.type_store.is_borrow(self.function.ret);
+		if report && self.type_store.is_borrow(value.type_id) && !returns_borrow {
+			self.error(register, 211, "is borrowed, it can't be moved", range);
+		}
+		self.verify_use(value, range, states, report);
+		if states.contains_key(register) {
+			states.insert(register, State::Moved);
+		}
+	}
+
+	fn verify_use(
+		&mut self,
+		value: &'ir IrBasicValue,
+		range: Option<Range>,
+		states: &States<'ir>,
+		report: bool,
+	) {
+		if !report || !value.is_register() {
+			return;
+		}
+		let Some(root) = self.roots.get(value.value.as_str()).copied() else {
+			return;
+		};
+		match states.get(root) {
+			Some(State::Dropped) => self.error(root, 208, "is used after it is dropped", range),
+			Some(State::Moved) => self.error(root, 208, "is used after it was moved", range),
+			Some(State::Partly) => self.error(root, 208, "may be used after it is dropped", range),
+			_ => {}
+		}
+	}
+
+	/// every heap value the path allocated is gone by its `ret`.
+	fn verify_dropped(&mut self, states: &States<'ir>, range: Option<Range>) {
+		let mut registers: Vec<_> =
+			states.iter().map(|(register, state)| (*register, *state)).collect();
+		// the messages come out in register order, not hash order
+		registers.sort_by_key(|(register, _)| register_number(register));
+		for (register, state) in registers {
+			match state {
+				State::Owned => self.error(register, 209, "is never dropped", range),
+				State::Partly => self.error(register, 209, "isn't dropped on every path", range),
+				State::Dropped | State::Moved => {}
+			}
+		}
+	}
+
+	fn error(&mut self, register: &'ir str, code: u16, text: &str, range: Option<Range>) {
+		if !self.reported.insert((register, code)) {
+			return;
+		}
+		let function = &self.function.name;
+		let text = format!("'{}' in '{}' {}", register, function, text);
+		let alloc = self.allocs.get(register).copied().flatten();
+		// drops the builder adds have no range, the allocation stands in for them
+		let mut message = Message::error(text).stage(Stage::Ownership).code(code);
+		message = match (range, alloc) {
+			(Some(range), Some(alloc)) if range != alloc => {
+				message.range(range).label(alloc, "allocated here")
+			}
+			(range, alloc) => message.range_if_some(range.or(alloc)),
+		};
+		if let Some(mod_id) = self.function.mod_id {
+			message = message.mod_id(mod_id);
+		}
+		self.messages.push(message);
+	}
+}
+
+/// the states on every path into a block, none are owned on entry.
+fn states_in<'ir>(
+	index: usize,
+	predecessors: &[Vec<usize>],
+	states_out: &[Option<States<'ir>>],
+) -> Option<States<'ir>> {
+	if index == 0 {
+		return Some(States::default());
+	}
+	let mut reached =
+		predecessors[index].iter().filter_map(|predecessor| states_out[*predecessor].as_ref());
+	let mut states = reached.next()?.clone();
+	for other in reached {
+		for (register, state) in other {
+			// a value allocated on one path only is that path's to drop
+			let joined = states.get(register).map_or(*state, |found| found.join(*state));
+			states.insert(register, joined);
+		}
+	}
+	Some(states)
+}
+
+fn register_number(register: &str) -> usize {
+	register.trim_start_matches('R').parse().unwrap_or(usize::MAX)
+}
@@ -0,0 +1,84 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{operands, successors, Verifier};
+use crate::ir;
+
+type Defined<'ir> = FxHashSet<&'ir str>;
+
+impl<'ir> Verifier<'ir> {
+	/// a register is read only where it was defined on every path from the entry,
+	/// the params and globals are defined on entry. blocks nothing jumps to aren't checked.
+	pub fn verify_registers(
+		&mut self,
+		function: &'ir ir::Function,
+		labels: &FxHashMap<usize, usize>,
+	) {
+		let blocks = &function.blocks;
+		let mut predecessors = vec![Vec::new(); blocks.len()];
+		for (index, block) in blocks.iter().enumerate() {
+			for label in block.instrs().last().map(successors).unwrap_or_default() {
+				predecessors[labels[&label]].push(index);
+			}
+		}
+		let params = function.args.iter().map(|arg| arg.value.as_str());
+		let globals = self.ir.globals.iter().map(|global| global.name.as_str());
+		let params: Defined = params.chain(globals).collect();
+
+		// the registers defined at the end of each block, `None` until a path reaches it
+		let mut defined_out: Vec<Option<Defined>> = vec![None; blocks.len()];
+		let mut changed = true;
+		while changed {
+			changed = false;
+			for (index, block) in blocks.iter().enumerate() {
+				let Some(mut defined) = defined_in(index, &params, &predecessors, &defined_out) else {
+					continue;
+				};
+				for instr in block.instrs() {
+					if let (_, Some(dest)) = operands(instr) {
+						defined.insert(dest.value.as_str());
+					}
+				}
+				if defined_out[index].as_ref() != Some(&defined) {
+					defined_out[index] = Some(defined);
+					changed = true;
+				}
+			}
+		}
+
+		for (index, block) in blocks.iter().enumerate() {
+			let Some(mut defined) = defined_in(index, &params, &predecessors, &defined_out) else {
+				continue;
+			};
+			for instr in block.instrs() {
+				let (uses, dest) = operands(instr);
+				for value in uses.into_iter().filter(|value| value.is_register()) {
+					let register = value.value.as_str();
+					if !defined.contains(register) {
+						self.error(function, block, 805, format!("uses '{}' before it is defined", register));
+						// once is enough
+						defined.insert(register);
+					}
+				}
+				if let Some(dest) = dest {
+					defined.insert(dest.value.as_str());
+				}
+			}
+		}
+	}
+}
+
+/// the registers defined on every path into a block, the params for the entry.
+fn defined_in<'ir>(
+	index: usize,
+	params: &Defined<'ir>,
+	predecessors: &[Vec<usize>],
+	defined_out: &[Option<Defined<'ir>>],
+) -> Option<Defined<'ir>> {
+	if index == 0 {
+		return Some(params.clone());
+	}
+	let mut reached =
+		predecessors[index].iter().filter_map(|predecessor| defined_out[*predecessor].as_ref());
+	let first = reached.next()?.clone();
+	Some(reached.fold(first, |defined, other| defined.intersection(other).copied().collect()))
+}
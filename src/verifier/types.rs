@@ -0,0 +1,191 @@
+use rustc_hash::FxHashSet;
+
+use super::Verifier;
+use crate::{
+	checker::types::TypeId,
+	ir::{self, IrBasicValue},
+};
+
+impl<'ir> Verifier<'ir> {
+	pub fn verify_types(&mut self, function: &'ir ir::Function) {
+		let heap: FxHashSet<&str> = function
+			.blocks
+			.iter()
+			.flat_map(|block| block.instrs())
+			.filter_map(|instr| match instr {
+				ir::Instr::Halloc(unary) => Some(unary.dest.value.as_str()),
+				_ => None,
+			})
+			.collect();
+
+		for block in &function.blocks {
+			for instr in block.instrs() {
+				let text = match instr {
+					ir::Instr::Add(bin) => self.verify_bin("add", bin, TypeId::is_number_type),
+					ir::Instr::Sub(bin) => self.verify_bin("sub", bin, TypeId::is_number_type),
+					ir::Instr::Mul(bin) => self.verify_bin("mul", bin, TypeId::is_number_type),
+					ir::Instr::Div(bin) => self.verify_bin("div", bin, TypeId::is_number_type),
+					ir::Instr::Mod(bin) => self.verify_bin("mod", bin, TypeId::is_number_type),
+					ir::Instr::Shl(bin) => self.verify_bin("shl", bin, is_integer_type),
+					ir::Instr::Shr(bin) => self.verify_bin("shr", bin, is_integer_type),
+					ir::Instr::And(bin) => self.verify_bin("and", bin, is_logic_type),
+					ir::Instr::Or(bin) => self.verify_bin("or", bin, is_logic_type),
+					ir::Instr::CmpEq(bin) => self.verify_cmp("cmp_eq", bin),
+					ir::Instr::CmpNe(bin) => self.verify_cmp("cmp_ne", bin),
+					ir::Instr::CmpLt(bin) => self.verify_cmp("cmp_lt", bin),
+					ir::Instr::CmpGt(bin) => self.verify_cmp("cmp_gt", bin),
+					ir::Instr::CmpLe(bin) => self.verify_cmp("cmp_le", bin),
+					ir::Instr::CmpGe(bin) => self.verify_cmp("cmp_ge", bin),
+					ir::Instr::JmpIf(jmp_if) if jmp_if.cond.type_id != TypeId::BOOL => Some(format!(
+						"'jmp_if' expects a bool, found '{}'",
+						self.display_type(jmp_if.cond.type_id)
+					)),
+					ir::Instr::Ret(value) => self.verify_ret(function, value.as_ref()),
+					ir::Instr::Call(call) => self.verify_call(call),
+					ir::Instr::Getptr(getptr) => self.verify_getptr(getptr),
+					ir::Instr::Load(unary) if !unary.src.is_register() => {
+						Some("'load' expects a register to load from".to_owned())
+					}
+					ir::Instr::Set(unary) if !unary.dest.is_register() => {
+						Some("'set' expects a register to store to".to_owned())
+					}
+					ir::Instr::Mov(unary) if !unary.dest.is_register() => {
+						Some("'mov' expects a register to store to".to_owned())
+					}
+					ir::Instr::Drop(value) if !value.is_register() => {
+						Some("'drop' expects a register".to_owned())
+					}
+					ir::Instr::Drop(value) if !heap.contains(value.value.as_str()) => {
+						Some(format!("drops '{}', which isn't the result of a 'heap'", value.value.as_str()))
+					}
+					_ => None,
+				};
+				if let Some(text) = text {
+					self.error(function, block, 806, text);
+				}
+			}
+		}
+	}
+
+	fn verify_bin(
+		&self,
+		name: &str,
+		bin: &ir::BinInstr,
+		expected: fn(&TypeId) -> bool,
+	) -> Option<String> {
+		if !expected(&bin.left.type_id) || !expected(&bin.right.type_id) {
+			let (left, right) =
+				(self.display_type(bin.left.type_id), self.display_type(bin.right.type_id));
+			return Some(format!("'{}' can't take '{}' and '{}'", name, left, right));
+		}
+		self.verify_same_types(name, bin)
+	}
+
+	fn verify_cmp(&self, name: &str, bin: &ir::BinInstr) -> Option<String> {
+		if bin.dest.type_id != TypeId::BOOL {
+			let dest = self.display_type(bin.dest.type_id);
+			return Some(format!("'{}' stores a bool, found a '{}' dest", name, dest));
+		}
+		self.verify_same_types(name, bin)
+	}
+
+	fn verify_same_types(&self, name: &str, bin: &ir::BinInstr) -> Option<String> {
+		if matches_type(bin.left.type_id, &bin.right) || matches_type(bin.right.type_id, &bin.left) {
+			return None;
+		}
+		let (left, right) = (self.display_type(bin.left.type_id), self.display_type(bin.right.type_id));
+		Some(format!("'{}' operands differ, '{}' and '{}'", name, left, right))
+	}
+
+	fn verify_ret(&self, function: &ir::Function, value: Option<&IrBasicValue>) -> Option<String> {
+		let value = value.filter(|value| !value.type_id.is_empty_type());
+		match value {
+			None if function.ret.is_empty_type() => None,
+			None => Some(format!("returns nothing, expected '{}'", self.display_type(function.ret))),
+			Some(value) if function.ret.is_empty_type() => {
+				Some(format!("returns a '{}' from a void function", self.display_type(value.type_id)))
+			}
+			Some(value) if !matches_type(function.ret, value) => {
+				let (found, expected) = (self.display_type(value.type_id), self.display_type(function.ret));
+				Some(format!("returns a '{}', expected '{}'", found, expected))
+			}
+			Some(_) => None,
+		}
+	}
+
+	fn verify_call(&self, call: &ir::CallInstr) -> Option<String> {
+		let Some(callee) = self.functions.get(call.callee.as_str()) else {
+			return Some(format!("calls '{}', which isn't defined", call.callee));
+		};
+		let params = callee.args.len();
+		let arity_matches = match callee.is_variadic_args() {
+			true => call.args.len() >= params,
+			false => call.args.len() == params,
+		};
+		if !arity_matches {
+			return Some(format!(
+				"calls '{}' with {} args, it takes {}",
+				call.callee,
+				call.args.len(),
+				params
+			));
+		}
+		if call.ret_id != callee.ret {
+			let (found, expected) = (self.display_type(call.ret_id), self.display_type(callee.ret));
+			return Some(format!(
+				"calls '{}' as returning '{}', it returns '{}'",
+				call.callee, found, expected
+			));
+		}
+		for (position, (arg, param)) in call.args.iter().zip(&callee.args).enumerate() {
+			// a borrow is an opaque pointer, e.g. the stack slot of a value
+			if self.type_store.is_borrow(param.type_id) && arg.is_register() {
+				continue;
+			}
+			if !matches_type(param.type_id, arg) {
+				let (found, expected) = (self.display_type(arg.type_id), self.display_type(param.type_id));
+				let position = position + 1;
+				let text = format!("passes a '{}' to '{}' as arg {}", found, call.callee, position);
+				return Some(format!("{}, expected '{}'", text, expected));
+			}
+		}
+		None
+	}
+
+	fn verify_getptr(&self, getptr: &ir::GetPtrInstr) -> Option<String> {
+		let self_name = getptr.self_name.as_str();
+		let Some(found) = self.ir.structs.iter().find(|found| found.name.as_deref() == Some(self_name))
+		else {
+			return Some(format!("'getptr' of '{}', which isn't a struct", self_name));
+		};
+		if getptr.offset >= found.fields.len() {
+			let fields = found.fields.len();
+			return Some(format!(
+				"'getptr' of field {} of '{}', it has {}",
+				getptr.offset, self_name, fields
+			));
+		}
+		if !getptr.self_base.is_register() {
+			return Some("'getptr' expects a register to offset".to_owned());
+		}
+		None
+	}
+}
+
+/// signed and unsigned, `is_int_type` is only the signed ones.
+fn is_integer_type(type_id: &TypeId) -> bool {
+	type_id.is_number_type() && !type_id.is_float()
+}
+
+fn is_logic_type(type_id: &TypeId) -> bool {
+	type_id.is_bool_type() || is_integer_type(type_id)
+}
+
+/// a number literal takes the type of its use, the builder types some as `i64`.
+fn matches_type(expected: TypeId, value: &IrBasicValue) -> bool {
+	if value.type_id == expected {
+		return true;
+	}
+	let is_number = matches!(value.value, ir::BasicValue::Int(_) | ir::BasicValue::Float(_));
+	is_number && expected.is_number_type() && expected.is_float() == value.type_id.is_float()
+}
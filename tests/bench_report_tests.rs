@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use lemonc::bench_report::RunTimes;
+
+fn run_times(samples_ms: &[u64]) -> RunTimes {
+	let samples: Vec<_> = samples_ms.iter().map(|ms| Duration::from_millis(*ms)).collect();
+	RunTimes::new(0, &samples)
+}
+
+fn assert_close(found: f64, expected: f64) {
+	assert!((found - expected).abs() < 1e-9, "expected {}, found {}", expected, found);
+}
+
+#[test]
+fn run_times_mean_and_sample_deviation() {
+	let times = run_times(&[2, 4, 4, 4, 5, 5, 7, 9]);
+	assert_close(times.mean_ms, 5.0);
+	// the sum of squares is 32, over n - 1
+	assert_close(times.deviation_ms, (32.0f64 / 7.0).sqrt());
+	assert_close(times.min_ms, 2.0);
+	assert_close(times.max_ms, 9.0);
+	assert_eq!(times.samples_ms.len(), 8);
+}
+
+#[test]
+fn run_times_of_one_sample_has_no_deviation() {
+	let times = run_times(&[3]);
+	assert_close(times.mean_ms, 3.0);
+	assert_close(times.deviation_ms, 0.0);
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use std::path::Path;
+
+use common::TempDir;
+use lemonc::{
+	builder::Builder,
+	checker::{context::Context, Checker},
+	loader::Loader,
+	parser::parse_mod,
+	shio::{self, ShioConfig},
+};
+
+/// loads a package with `[build]` set to `build`.
+fn load_build(dir: &Path, name: &str, build: &str) -> Result<ShioConfig, String> {
+	let sections = format!("[build]\n{}", build);
+	let package = common::write_package(dir, name, "0.1.0", &sections, &[]);
+	ShioConfig::load_from_dir(&package)
+}
+
+#[test]
+fn parses_the_build_section() {
+	let dir = TempDir::new("build-section");
+	let build =
+		"target = \"x86_64-unknown-linux-gnu\"\nlinker = \"lld\"\nopt_level = 2\nout_dir = \"out\"\n";
+	let shio = load_build(&dir, "app", build).expect("the [build] section is valid");
+	assert_eq!(shio.build.target.as_deref(), Some("x86_64-unknown-linux-gnu"));
+	assert_eq!(shio.build.linker.as_deref(), Some("lld"));
+	assert_eq!(shio.build.opt_level, Some(2));
+	assert_eq!(shio.output_path(), dir.join("app/out/app.o"));
+
+	let shio = load_build(&dir, "empty", "").unwrap();
+	assert!(shio.build.target.is_none() && shio.build.linker.is_none());
+	assert_eq!(shio.build.opt_level, None);
+	assert_eq!(shio.output_path(), dir.join("empty/empty.o"), "next to the shio.toml by default");
+}
+
+#[test]
+fn rejects_invalid_build_values() {
+	let dir = TempDir::new("build-invalid");
+	let cases = [
+		("opt_level = \"2\"", "invalid key opt_level in [build], expected an integer"),
+		("opt_level = 4", "opt_level must be between 0 and 3"),
+		("opt_level = -1", "opt_level must be between 0 and 3"),
+		("target = 64", "invalid key target in [build], expected a string"),
+		("linker = \"ld\"", "unknown linker 'ld', expected mold, lld or clang"),
+		("linker = true", "invalid key linker in [build], expected a string"),
+		("out_dir = [\"out\"]", "invalid key out_dir in [build], expected a string"),
+	];
+	for (index, (build, expected)) in cases.into_iter().enumerate() {
+		let err = load_build(&dir, &format!("app{}", index), build).err();
+		assert_eq!(err.as_deref(), Some(expected), "{}", build);
+	}
+}
+
+#[test]
+fn builds_the_package_named_in_shio_toml() {
+	let dir = TempDir::new("build-package");
+	let main = "const math = import(\"math\");\n\nfn main() = {\n\tmath.one();\n}\n";
+	let sections = "[dependencies]\nmath = \"../math\"\n[build]\nout_dir = \"bin\"\n";
+	let math = [("main.ln", "pub fn one(): i32 = {\n\treturn 1;\n}\n")];
+	common::write_package(&dir, "math", "1.0.0", "", &math);
+	let app = common::write_package(&dir, "app", "0.1.0", sections, &[("main.ln", main)]);
+
+	let mut packages = shio::load_packages(Some(app.join("shio.toml"))).unwrap();
+	assert_eq!(packages.len(), 1);
+	let (shio, file_system) = packages.remove(0);
+	assert_eq!(shio.output_path(), app.join("bin/app.o"));
+	let mut loader = Loader::new(shio, file_system);
+	let mod_id = loader.load_entry().expect("[loader] main is the entry");
+	parse_mod(mod_id, &mut loader);
+	let mut ctx = Context::new();
+	Checker::new(&mut ctx, &mut loader).check_messages(mod_id);
+	let mut messages = loader.take_messages();
+	let texts: Vec<_> = messages.sorted().map(|message| message.text.as_str()).collect();
+	assert!(texts.is_empty(), "app and its dependency check, {:#?}", texts);
+	let ir = Builder::new(&ctx.type_store, &mut ctx.event, &mut loader).build(mod_id);
+	assert!(ir.has_function("main"));
+	assert!(ir.functions.iter().any(|function| function.name.ends_with("one")), "math is built too");
+}
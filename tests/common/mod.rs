@@ -0,0 +1,179 @@
+// fixtures shared by the integration tests, each test binary uses a part of them.
+#![allow(dead_code)]
+
+use std::{
+	fs,
+	ops::Deref,
+	path::{Path, PathBuf},
+};
+
+use lemonc::{
+	assembler::Assembler,
+	builder::Builder,
+	checker::{context::Context, types::TypeStore, Checker},
+	file_system::FileSystem,
+	ir::IR,
+	loader::{Loader, ModId},
+	message::{Message, Severity},
+	parser::parse_mod,
+	shio::ShioConfig,
+	verifier::{verify_ir, verify_ownership},
+};
+
+/// the bench programs that go through every stage. the others don't:
+/// - `math.ln`, `loop.ln` and `extern.ln` infer an `i64` where an `i32` is expected
+/// - `gen.ln` calls `is_float` and the other type tests, which aren't builtins yet
+/// - `assign.ln` uses an `if` expression, which doesn't parse yet
+/// - `guess_the_number.ln` imports a `libc` dependency
+/// - `struct.ln` passes a pointer to a field to `printf` where its value is meant
+/// - `main.ln` computes `fibonacci(40)`, which takes minutes on the interpreter
+/// - `prim.ln` falls off its end, the interpreter and verifier tests check that it's caught
+pub const BENCH: [&str; 4] = ["bench/write.ln", "bench/if.ln", "bench/stress.ln", "bench/owner.ln"];
+
+/// a dir under the system temp dir for one test, removed once it's dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+	/// e.g. /tmp/lemon-lock-mismatch-42, `name` is unique across the test binaries.
+	pub fn new(name: &str) -> Self {
+		let dir = std::env::temp_dir().join(format!("lemon-{}-{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		fs::create_dir_all(&dir).expect("failed to create temp dir");
+		Self(dir)
+	}
+}
+
+impl Deref for TempDir {
+	type Target = Path;
+
+	fn deref(&self) -> &Path {
+		&self.0
+	}
+}
+
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		let _ = fs::remove_dir_all(&self.0);
+	}
+}
+
+/// writes a package whose main is `main.ln` to `dir/name`. `sections` go after its
+/// `[loader]`, e.g. its `[dependencies]`, and `files` are its sources by path.
+pub fn write_package(
+	dir: &Path,
+	name: &str,
+	version: &str,
+	sections: &str,
+	files: &[(&str, &str)],
+) -> PathBuf {
+	let package = dir.join(name);
+	fs::create_dir_all(&package).unwrap();
+	let toml = format!(
+		"[package]\nname = \"{}\"\nversion = \"{}\"\n[loader]\nmain = \"main.ln\"\n{}",
+		name, version, sections
+	);
+	fs::write(package.join("shio.toml"), toml).unwrap();
+	for (path, source) in files {
+		let path = package.join(path);
+		fs::create_dir_all(path.parent().unwrap()).unwrap();
+		fs::write(path, source).unwrap();
+	}
+	package
+}
+
+/// loads the entry of the package in `dir` without parsing it.
+pub fn load_package(dir: &Path) -> (Loader, ModId) {
+	load_package_with(dir, |_| {})
+}
+
+/// like `load_package`, with the file system set up by `setup`, e.g. to move its dependency dir.
+pub fn load_package_with(dir: &Path, setup: impl FnOnce(&mut FileSystem)) -> (Loader, ModId) {
+	let shio = ShioConfig::load_from_dir(dir).expect("failed to load shio.toml");
+	let mut file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+	setup(&mut file_system);
+	let mut loader = Loader::new(shio, file_system);
+	let mod_id = loader.load_entry().expect("failed to load entry");
+	(loader, mod_id)
+}
+
+/// a path from the root of the crate, e.g. `bench/if.ln`.
+pub fn crate_path(path: &str) -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+/// loads a program without parsing it.
+pub fn load(path: &str) -> (Loader, ModId) {
+	let shio = ShioConfig::with_defaults(crate_path(path));
+	let file_system = FileSystem::from_current_dir(shio.loader.cwd.clone());
+	let mut loader = Loader::new(shio, file_system);
+	let mod_id = loader.load_entry().expect("failed to load entry");
+	(loader, mod_id)
+}
+
+/// loads and parses a program with the mods it imports.
+pub fn parse(path: &str) -> (Loader, ModId) {
+	let (mut loader, mod_id) = load(path);
+	parse_mod(mod_id, &mut loader);
+	(loader, mod_id)
+}
+
+/// parses and checks a program, its errors and warnings are left in the loader.
+pub fn check(path: &str) -> (Loader, Context, ModId) {
+	let (mut loader, mod_id) = parse(path);
+	let mut ctx = Context::new();
+	Checker::new(&mut ctx, &mut loader).check_messages(mod_id);
+	(loader, ctx, mod_id)
+}
+
+/// checks and builds a program, which must check without errors.
+pub fn build(path: &str) -> (Context, IR, ModId) {
+	build_with(path, |builder| builder)
+}
+
+/// like `build`, with the builder set up by `setup`, e.g. to build the tests.
+pub fn build_with(path: &str, setup: impl FnOnce(Builder) -> Builder) -> (Context, IR, ModId) {
+	let (mut loader, mut ctx, mod_id) = check(path);
+	let mut messages = loader.take_messages();
+	if messages.any_errors() {
+		let error = messages.sorted().find(|message| message.severity == Severity::Error);
+		panic!(
+			"'{}' doesn't check, {}",
+			path,
+			error.map(|message| message.text.as_str()).unwrap_or("")
+		);
+	}
+	let builder = Builder::new(&ctx.type_store, &mut ctx.event, &mut loader);
+	let ir = setup(builder).build(mod_id);
+	(ctx, ir, mod_id)
+}
+
+/// assembles hand written ir.
+pub fn assemble(text: &str) -> (TypeStore, IR) {
+	let mut type_store = TypeStore::default();
+	let ir = Assembler::new(text, ModId::default(), &mut type_store)
+		.assemble_program("test.lnr".into())
+		.unwrap_or_else(|message| panic!("{}", message.text));
+	(type_store, ir)
+}
+
+/// verifies ir, its instructions and then its ownership.
+pub fn verify(ir: &IR, type_store: &TypeStore) -> Vec<Message> {
+	let mut messages = verify_ir(ir, type_store);
+	messages.extend(verify_ownership(ir, type_store));
+	messages
+}
+
+/// verifies hand written ir.
+pub fn verify_text(text: &str) -> Vec<Message> {
+	let (type_store, ir) = assemble(text);
+	verify(&ir, &type_store)
+}
+
+/// the message verifying `text` reports, found by a part of its text.
+pub fn assert_reports(text: &str, expected: &str) -> Message {
+	let mut messages = verify_text(text);
+	let found = messages.iter().position(|message| message.text.contains(expected));
+	let texts: Vec<_> = messages.iter().map(|message| message.text.clone()).collect();
+	let position = found.unwrap_or_else(|| panic!("'{}' not in {:#?}", expected, texts));
+	messages.swap_remove(position)
+}
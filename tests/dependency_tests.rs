@@ -0,0 +1,87 @@
+mod common;
+
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+use common::TempDir;
+use lemonc::{
+	message::{ErrorCode, Stage},
+	parser::parse_mod,
+};
+
+const IMPORT_MATH: &str = "const math = import(\"math\");\n\nfn main() = {\n\tmath.one();\n}\n";
+
+/// the dir of the mod `import("math")` resolves to from the entry of `app`.
+fn math_dir(app: &Path, deps: &Path) -> PathBuf {
+	let deps = deps.to_path_buf();
+	let (mut loader, mod_id) = common::load_package_with(app, |fs| fs.dependency_dir = deps);
+	let math = loader.load_source("math", mod_id).expect("math is not resolved");
+	assert!(loader.is_dependency_mod(math));
+	let abs_path = &loader.lookup_source(math).unwrap().abs_path;
+	assert_eq!(
+		abs_path.file_name().unwrap(),
+		"main.ln",
+		"the entry is the `main` of math's shio.toml"
+	);
+	abs_path.parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn resolves_a_path_dependency_by_name() {
+	let dir = TempDir::new("dependency-path");
+	let math =
+		common::write_package(&dir, "math", "1.0.0", "", &[("main.ln", "pub fn one(): i32 = 1;\n")]);
+	let sections = "[dependencies]\nmath = \"../math\"\n";
+	let app = common::write_package(&dir, "app", "0.1.0", sections, &[("main.ln", IMPORT_MATH)]);
+	assert_eq!(math_dir(&app, &dir.join("deps")), fs::canonicalize(math).unwrap());
+
+	let (mut loader, mod_id) = common::load_package(&app);
+	parse_mod(mod_id, &mut loader);
+	assert!(!loader.any_errors(), "app imports math by name");
+}
+
+#[test]
+fn resolves_a_global_dependency_by_name() {
+	let dir = TempDir::new("dependency-global");
+	let deps = dir.join("deps");
+	let math =
+		common::write_package(&deps, "math", "1.0.0", "", &[("main.ln", "pub fn one(): i32 = 1;\n")]);
+	let app = common::write_package(&dir, "app", "0.1.0", "", &[("main.ln", IMPORT_MATH)]);
+	assert_eq!(math_dir(&app, &deps), fs::canonicalize(math).unwrap());
+}
+
+#[test]
+fn path_dependencies_come_before_global_ones() {
+	let dir = TempDir::new("dependency-order");
+	let deps = dir.join("deps");
+	common::write_package(&deps, "math", "1.0.0", "", &[("main.ln", "pub fn one(): i32 = 1;\n")]);
+	let math =
+		common::write_package(&dir, "math", "2.0.0", "", &[("main.ln", "pub fn one(): i32 = 1;\n")]);
+	let sections = "[dependencies]\nmath = \"../math\"\n";
+	let app = common::write_package(&dir, "app", "0.1.0", sections, &[("main.ln", IMPORT_MATH)]);
+	assert_eq!(math_dir(&app, &deps), fs::canonicalize(math).unwrap());
+}
+
+#[test]
+fn unknown_dependency_lists_where_it_searched() {
+	let dir = TempDir::new("dependency-unknown");
+	let deps = dir.join("deps");
+	let app = common::write_package(&dir, "app", "0.1.0", "", &[("main.ln", IMPORT_MATH)]);
+	let remote = dir.join("remote");
+	let (mut loader, mod_id) = common::load_package_with(&app, |fs| {
+		fs.dependency_dir = deps.clone();
+		fs.remote_dir = remote.clone();
+	});
+	let message = loader.load_source("math", mod_id).expect_err("math is nowhere");
+	assert_eq!(message.text, "dependency 'math' not found");
+	assert_eq!(message.code, Some(ErrorCode(602)));
+	assert_eq!(message.stage, Some(Stage::Resolve));
+	let notes: Vec<_> = message.notes.iter().map(|note| note.text.as_str()).collect();
+	let searched = |path: &Path| format!("searched '{}'", path.display());
+	assert!(notes.contains(&searched(&deps.join("math")).as_str()), "{:#?}", notes);
+	assert!(notes.contains(&searched(&remote.join("index.toml")).as_str()), "{:#?}", notes);
+	let local = fs::canonicalize(&app).unwrap().join("math");
+	assert!(notes.contains(&searched(&local).as_str()), "a mod next to the importer, {:#?}", notes);
+}
@@ -0,0 +1,135 @@
+use std::{fs, path::Path};
+
+use lemonc::{explain::explain, message::ErrorCode};
+use walkdir::WalkDir;
+
+/// every `.code(N)` given to a message in the sources.
+fn used_codes() -> Vec<(u16, String)> {
+	let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+	let mut codes = Vec::new();
+	for entry in WalkDir::new(&src).into_iter().filter_map(Result::ok) {
+		if entry.path().extension().is_none_or(|extension| extension != "rs") {
+			continue;
+		}
+		let text = fs::read_to_string(entry.path()).expect("failed to read source");
+		for (at, pattern) in text.match_indices(".code(") {
+			let rest = &text[at + pattern.len()..];
+			let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+			if let Ok(code) = digits.parse() {
+				codes.push((code, entry.path().display().to_string()));
+			}
+		}
+	}
+	codes
+}
+
+#[test]
+fn every_used_code_is_explained() {
+	let codes = used_codes();
+	assert!(codes.len() > 50, "found only {} codes, is the walk broken?", codes.len());
+	for (code, path) in codes {
+		assert!(
+			explain(ErrorCode(code)).is_some(),
+			"{} in '{}' has no explanation",
+			ErrorCode(code),
+			path
+		);
+	}
+}
+
+#[test]
+fn explanations_start_with_their_code() {
+	for (code, _) in used_codes() {
+		let text = explain(ErrorCode(code)).unwrap_or_default();
+		assert!(text.starts_with(&format!("{}: ", ErrorCode(code))), "{}", text);
+	}
+}
+
+/// the end of the bracketed group opening at `open`, skipping strings and chars.
+fn close(text: &[u8], open: usize) -> usize {
+	let mut depth = 0;
+	let mut at = open;
+	while at < text.len() {
+		match text[at] {
+			b'(' | b'[' | b'{' => depth += 1,
+			b')' | b']' | b'}' => {
+				depth -= 1;
+				if depth == 0 {
+					return at + 1;
+				}
+			}
+			b'"' => {
+				at += 1;
+				while text[at] != b'"' {
+					at += if text[at] == b'\\' { 2 } else { 1 };
+				}
+			}
+			b'\'' if text.get(at + 2) == Some(&b'\'') => at += 2,
+			b'\'' if text.get(at + 1) == Some(&b'\\') => {
+				at += 2;
+				while text[at] != b'\'' {
+					at += 1;
+				}
+			}
+			_ => {}
+		}
+		at += 1;
+	}
+	at
+}
+
+/// whether the method chain after `at` sets a code.
+fn chain_has_code(text: &[u8], mut at: usize) -> bool {
+	loop {
+		let start = at + text[at..].iter().take_while(|byte| byte.is_ascii_whitespace()).count();
+		if text.get(start) != Some(&b'.') {
+			return false;
+		}
+		let name: Vec<u8> = text[start + 1..]
+			.iter()
+			.copied()
+			.take_while(|&byte| byte.is_ascii_alphanumeric() || byte == b'_')
+			.collect();
+		let open = start + 1 + name.len();
+		if text.get(open) != Some(&b'(') {
+			return false;
+		}
+		if name == b"code" {
+			return true;
+		}
+		at = close(text, open);
+	}
+}
+
+#[test]
+fn diagnostics_have_codes() {
+	let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+	let constructors = [
+		"error_syntax!(",
+		"error_resolve!(",
+		"error_type!(",
+		"error_ownership!(",
+		"error_build!(",
+		"error_codegen!(",
+		"Message::error",
+	];
+	let mut missing = Vec::new();
+	for dir in ["assembler", "verifier", "interp", "parser"] {
+		for entry in WalkDir::new(src.join(dir)).into_iter().filter_map(Result::ok) {
+			if entry.path().extension().is_none_or(|extension| extension != "rs") {
+				continue;
+			}
+			let text = fs::read_to_string(entry.path()).expect("failed to read source");
+			for constructor in constructors {
+				for (at, _) in text.match_indices(constructor) {
+					let open = at + text[at..].find('(').unwrap_or_default();
+					if !chain_has_code(text.as_bytes(), close(text.as_bytes(), open)) {
+						let line = text[..at].lines().count();
+						missing.push(format!("{}:{}", entry.path().display(), line));
+					}
+				}
+			}
+		}
+	}
+	assert!(missing.is_empty(), "diagnostics without a code:\n{}", missing.join("\n"));
+}
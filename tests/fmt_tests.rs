@@ -0,0 +1,73 @@
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+use lemonc::{
+	fmt::{format_mod, parse_source},
+	loader::{Loader, ModId},
+};
+
+/// every `.ln` and `.lemon` file under `dir`, sorted.
+fn sources(dir: &Path) -> Vec<PathBuf> {
+	let mut paths = vec![];
+	for entry in fs::read_dir(dir).expect("failed to read dir") {
+		let path = entry.expect("failed to read entry").path();
+		if path.is_dir() {
+			paths.extend(sources(&path));
+		} else if path.extension().is_some_and(|ext| ext == "ln" || ext == "lemon") {
+			paths.push(path);
+		}
+	}
+	paths.sort();
+	paths
+}
+
+fn comment_texts(loader: &Loader, mod_id: ModId) -> Vec<String> {
+	let program = loader.get_mod(mod_id).expect("mod is parsed");
+	program.comments.iter().map(|comment| comment.text.clone()).collect()
+}
+
+/// formats every file under `dir` twice, the second pass must change nothing and
+/// the comments must survive the first. files with syntax errors are not formatted,
+/// they must be the `unparsed` ones.
+fn assert_idempotent(dir: &str, unparsed: &[&str]) {
+	let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(dir);
+	let mut formatted = 0;
+	let mut skipped = vec![];
+	for path in sources(&root) {
+		let text = fs::read_to_string(&path).expect("failed to read source");
+		let (loader, mod_id) = parse_source(&path, text);
+		if loader.any_errors() {
+			skipped.push(path.strip_prefix(&root).unwrap().display().to_string());
+			continue;
+		}
+		let once = format_mod(&loader, mod_id);
+		let comments = comment_texts(&loader, mod_id);
+		let (loader, mod_id) = parse_source(&path, once.clone());
+		assert!(!loader.any_errors(), "'{}' does not parse once formatted:\n{}", path.display(), once);
+		assert_eq!(
+			comment_texts(&loader, mod_id),
+			comments,
+			"formatting '{}' lost comments",
+			path.display()
+		);
+		let twice = format_mod(&loader, mod_id);
+		assert_eq!(once, twice, "formatting '{}' is not idempotent", path.display());
+		formatted += 1;
+	}
+	assert_eq!(skipped, unparsed, "files in '{}' with syntax errors", dir);
+	assert!(formatted > 0, "no file formatted in '{}'", dir);
+}
+
+#[test]
+fn fmt_bench_is_idempotent() {
+	// they use syntax the parser doesn't support yet
+	assert_idempotent("bench", &["assign.ln", "guess_the_number.ln"]);
+}
+
+#[test]
+fn fmt_snippets_are_idempotent() {
+	// the lexer and recovery snippets have syntax errors on purpose
+	assert_idempotent("tests/snippets", &["lexer/main.lemon", "recovery/lex.ln"]);
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use lemonc::message::{ErrorCode, Message};
+
+/// parses and checks a snippet that imports itself, giving the cycle error.
+fn cycle_error(name: &str) -> Message {
+	let (mut loader, _, _) = common::check(&format!("tests/snippets/cycle/{}", name));
+	let mut messages = loader.take_messages();
+	let cycle = messages.sorted().find(|message| message.code == Some(ErrorCode(610)));
+	cycle.cloned().unwrap_or_else(|| panic!("{} has no import cycle error", name))
+}
+
+#[test]
+fn mods_importing_each_other_are_a_cycle() {
+	let message = cycle_error("a.ln");
+	assert!(message.text.ends_with("cycle/a.ln' imports itself"), "{}", message.text);
+	let notes: Vec<_> = message.notes.iter().map(|note| note.text.as_str()).collect();
+	assert_eq!(notes.len(), 2, "{:#?}", notes);
+	assert!(notes[0].ends_with("a.ln' imports './b.ln'"), "{}", notes[0]);
+	assert!(notes[1].starts_with("'./b.ln' imports"), "{}", notes[1]);
+	assert!(notes[1].ends_with("cycle/a.ln'"), "{}", notes[1]);
+	assert!(message.range.is_some(), "the error points at the import");
+}
+
+#[test]
+fn mod_importing_itself_is_a_cycle() {
+	let message = cycle_error("self.ln");
+	assert_eq!(message.notes.len(), 1, "{:#?}", message.notes);
+}
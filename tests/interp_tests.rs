@@ -0,0 +1,33 @@
+mod common;
+
+use lemonc::{interp::Interp, message::MessageResult};
+
+/// checks and builds a program, then runs its main on the interpreter.
+fn interpret(path: &str) -> (MessageResult<i32>, usize) {
+	let (ctx, ir, _) = common::build(path);
+	let mut interp = Interp::new(&ir, &ctx.type_store);
+	let result = interp.run_main();
+	(result, interp.leaks().len())
+}
+
+#[test]
+fn interp_runs_bench() {
+	for path in common::BENCH {
+		let (result, leaks) = interpret(path);
+		assert_eq!(result.ok(), Some(0), "'{}' did not exit with 0", path);
+		assert_eq!(leaks, 0, "'{}' leaked", path);
+	}
+}
+#[test]
+fn interp_reports_missing_return() {
+	let (result, _) = interpret("bench/prim.ln");
+	let message = result.expect_err("'prim' falls off its end");
+	assert!(message.text.contains("without returning a value"), "{}", message.text);
+}
+
+#[test]
+fn interp_returns_the_value_of_an_expr_body() {
+	let (result, leaks) = interpret("tests/snippets/interp/expr_body.ln");
+	assert_eq!(result.map_err(|message| message.text), Ok(9));
+	assert_eq!(leaks, 0);
+}
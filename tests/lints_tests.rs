@@ -0,0 +1,113 @@
+mod common;
+
+use std::path::Path;
+
+use common::TempDir;
+use lemonc::{
+	checker::{context::Context, Checker},
+	lints::{self, Lint},
+	loader::{Loader, ModId},
+	message::{ErrorCode, Message, Severity},
+	parser::parse_mod,
+	shio::ShioConfig,
+};
+
+/// the messages of a snippet with `flags`, e.g. from `-D unused_variable`.
+fn messages_with(path: &str, flags: &[(Lint, Severity)]) -> Vec<Message> {
+	let (mut loader, mod_id) = common::load(path);
+	loader.extend_lints(flags);
+	check_messages(loader, mod_id)
+}
+
+fn check_messages(mut loader: Loader, mod_id: ModId) -> Vec<Message> {
+	parse_mod(mod_id, &mut loader);
+	let mut ctx = Context::new();
+	Checker::new(&mut ctx, &mut loader).check_messages(mod_id);
+	loader.take_messages().sorted().cloned().collect()
+}
+
+fn messages(name: &str) -> Vec<Message> {
+	messages_with(&format!("tests/snippets/lints/{}", name), &[])
+}
+
+/// the severity of every message of `code`, in source order.
+fn severities(messages: &[Message], code: u16) -> Vec<Severity> {
+	let found = messages.iter().filter(|message| message.code == Some(ErrorCode(code)));
+	found.map(|message| message.severity).collect()
+}
+
+/// a package with `[lints]` and an unused value in `main`.
+fn check_package(dir: &Path, lints: &str) -> Vec<Message> {
+	let main = [("main.ln", "fn main() = {\n\tlet x = 1;\n}\n")];
+	let package = common::write_package(dir, "app", "0.1.0", lints, &main);
+	let (loader, mod_id) = common::load_package(&package);
+	check_messages(loader, mod_id)
+}
+
+#[test]
+fn levels_come_from_shio_toml() {
+	let dir = TempDir::new("lints-toml");
+	let default = check_package(&dir.join("default"), "");
+	assert_eq!(severities(&default, 701), [Severity::Warning]);
+	let denied = check_package(&dir.join("deny"), "[lints]\nunused_variable = \"deny\"\n");
+	assert_eq!(severities(&denied, 701), [Severity::Error]);
+	let allowed = check_package(&dir.join("allow"), "[lints]\nunused_variable = \"allow\"\n");
+	assert_eq!(severities(&allowed, 701), []);
+}
+
+#[test]
+fn shio_toml_rejects_unknown_lints_and_levels() {
+	let dir = TempDir::new("lints-toml-unknown");
+	let unknown =
+		[("unknown", "[lints]\nunused_variables = \"deny\"\n", "unknown lint 'unused_variables'")];
+	let invalid = [("invalid", "[lints]\nunused_variable = \"forbid\"\n", "invalid level of lint")];
+	for (name, lints, expected) in unknown.into_iter().chain(invalid) {
+		let package = common::write_package(&dir, name, "0.1.0", lints, &[]);
+		let err = ShioConfig::load_from_dir(&package).err().expect("the lints are invalid");
+		assert!(err.contains(expected), "{}", err);
+	}
+}
+
+#[test]
+fn attributes_set_the_level_of_their_fn() {
+	let messages = messages("attrs.ln");
+	assert_eq!(severities(&messages, 701), [Severity::Error], "only `y` in `denied` is reported");
+	assert_eq!(severities(&messages, 704), [Severity::Warning], "shadowing is allowed by default");
+}
+
+#[test]
+fn attributes_take_precedence_over_flags() {
+	let flags = [(Lint::UnusedVariable, Severity::Warning), (Lint::ShadowedBinding, Severity::Allow)];
+	let messages = messages_with("tests/snippets/lints/attrs.ln", &flags);
+	assert_eq!(severities(&messages, 701), [Severity::Error]);
+	assert_eq!(severities(&messages, 704), [Severity::Warning]);
+}
+
+#[test]
+fn flags_apply_in_command_line_order() {
+	let deny = (1, "unused_variable", Severity::Error);
+	let allow = (3, "unused_variable", Severity::Allow);
+	let warn = (5, "unreachable_code", Severity::Warning);
+	// clap groups the flags by kind, `-D` before `-A` on the command line
+	let levels = lints::from_flags([allow, warn, deny]).unwrap();
+	let expected = [
+		(Lint::UnusedVariable, Severity::Error),
+		(Lint::UnusedVariable, Severity::Allow),
+		(Lint::UnreachableCode, Severity::Warning),
+	];
+	assert_eq!(levels, expected);
+}
+
+#[test]
+fn flags_reject_unknown_lints() {
+	let err = lints::from_flags([(1, "unused_variables", Severity::Error)]).unwrap_err();
+	assert_eq!(err, "unknown lint 'unused_variables'");
+}
+
+#[test]
+fn unknown_attributes_and_lints_are_errors() {
+	let unknown_attr = messages("unknown_attr.ln");
+	assert_eq!(severities(&unknown_attr, 401), [Severity::Error], "{:#?}", unknown_attr);
+	let unknown_lint = messages("unknown_lint.ln");
+	assert_eq!(severities(&unknown_lint, 402), [Severity::Error], "{:#?}", unknown_lint);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use lemonc::{
+	assembler::{assemble_mod, Assembler},
+	checker::types::TypeStore,
+	disassembler::Disassembler,
+	interp::Interp,
+	loader::ModId,
+	message::MessageResult,
+};
+
+/// checks and builds a program, then prints its ir.
+fn disassemble(path: &str) -> String {
+	let (ctx, ir, _) = common::build(path);
+	let mut text = String::new();
+	Disassembler::new(&ctx.type_store).disassemble_program(&ir, &mut text);
+	text
+}
+
+/// parses printed ir and prints it again.
+fn reassemble(text: &str) -> MessageResult<String> {
+	let mut type_store = TypeStore::default();
+	let ir =
+		Assembler::new(text, ModId::default(), &mut type_store).assemble_program("test.lnr".into())?;
+	let mut output = String::new();
+	Disassembler::new(&type_store).disassemble_program(&ir, &mut output);
+	Ok(output)
+}
+
+#[test]
+fn lnr_round_trips_bench() {
+	for path in common::BENCH {
+		let text = disassemble(path);
+		let output = reassemble(&text).unwrap_or_else(|message| panic!("'{}': {}", path, message.text));
+		assert_eq!(output, text, "'{}' changed going through the assembler", path);
+	}
+}
+
+#[test]
+fn lnr_round_trips_literals() {
+	let text = "fn main(): void = {\n  blk1: R1 = stack str\n        set R1, str \"a\\\"b\\\\c\\n\\t\"\n        \
+	            R2 = stack char\n        set R2, char '\\''\n        R3 = stack f64\n        \
+	            set R3, f64 2\n        ret\n}\n\n";
+	assert_eq!(reassemble(text).map_err(|message| message.text).as_deref(), Ok(text));
+}
+
+#[test]
+fn lnr_runs_hand_written_ir() {
+	let (loader, mod_id) = common::load("bench/mod.lnr");
+	let mut type_store = TypeStore::default();
+	let ir = assemble_mod(mod_id, &loader, &mut type_store)
+		.unwrap_or_else(|message| panic!("{}", message.text));
+	let mut interp = Interp::new(&ir, &type_store);
+	assert_eq!(interp.run_main().ok(), Some(0));
+	assert!(interp.leaks().is_empty());
+}
+
+#[test]
+fn lnr_reports_unknown_types() {
+	let message = reassemble("fn main(): i33 = {}\n").expect_err("'i33' is not a type");
+	assert!(message.text.contains("unknown type 'i33'"), "{}", message.text);
+}
+
+#[test]
+fn lnr_catches_heap_out_of_bounds() {
+	// a single byte has room for one cell, the second field is past it
+	let text =
+		"type Pair = { i32, i32 }, size=8\n\nfn main(): i32 = {\n  blk1: R1 = heap 1\n        \
+	            R2 = Pair getptr ptr R1 1\n        set R2, i32 7\n        drop Pair R1\n        \
+	            ret i32 0\n}\n";
+	let (type_store, ir) = common::assemble(text);
+	let message = Interp::new(&ir, &type_store).run_main().expect_err("the write is out of bounds");
+	assert!(
+		message.text.contains("out of bounds write at cell 1 of heap allocation"),
+		"{}",
+		message.text
+	);
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+use common::TempDir;
+use lemonc::{
+	file_system::FileSystem,
+	loader::Loader,
+	parser::parse_mod,
+	shio::{self, LockedDependency, ShioLock},
+};
+
+/// `app` imports `math` through a path dependency.
+fn write_packages(dir: &Path) -> PathBuf {
+	let math = [("main.ln", "pub fn one(): i32 = 1;\n")];
+	common::write_package(dir, "math", "1.0.0", "", &math);
+	let main = [("main.ln", "const math = import(\"math\");\n\nfn main() = {}\n")];
+	common::write_package(dir, "app", "0.1.0", "[dependencies]\nmath = \"../math\"\n", &main)
+}
+
+fn load_app(app: &Path) -> Loader {
+	let (mut loader, mod_id) = common::load_package(app);
+	parse_mod(mod_id, &mut loader);
+	loader
+}
+
+#[test]
+fn lock_round_trips() {
+	let dir = TempDir::new("lock-round_trip");
+	let path = dir.join("shio.lock");
+	let math = LockedDependency::new(
+		"math".to_owned(),
+		"../math".to_owned(),
+		"1.0.0".to_owned(),
+		"sha256:00".to_owned(),
+	);
+	let io = LockedDependency::new(
+		"io".to_owned(),
+		"../io".to_owned(),
+		"0.2.0".to_owned(),
+		"sha256:11".to_owned(),
+	);
+	let lock = ShioLock::new(vec![math, io]);
+	assert_eq!(lock.dependencies[0].name, "io", "dependencies are sorted by name");
+
+	lock.save(&path).expect("failed to save");
+	let text = fs::read_to_string(&path).unwrap();
+	assert!(text.starts_with("# generated by lemon"), "{}", text);
+	assert_eq!(ShioLock::load(&path), Ok(lock));
+}
+
+#[test]
+fn missing_lock_is_empty() {
+	let dir = TempDir::new("lock-missing");
+	assert_eq!(ShioLock::load(&dir.join("shio.lock")), Ok(ShioLock::default()));
+}
+
+#[test]
+fn hash_sources_follows_contents() {
+	let dir = TempDir::new("lock-hash");
+	let math = write_packages(&dir).with_file_name("math");
+	let hash = FileSystem::hash_sources(&math).unwrap();
+	assert!(hash.starts_with("sha256:"), "{}", hash);
+	assert_eq!(FileSystem::hash_sources(&math).unwrap(), hash);
+	fs::write(math.join("main.ln"), "pub fn one(): i32 = 2;\n").unwrap();
+	assert_ne!(FileSystem::hash_sources(&math).unwrap(), hash);
+}
+
+/// `app` imports `math`, which was fetched into `deps`.
+fn write_fetched(dir: &Path) -> PathBuf {
+	let math = [("main.ln", "pub fn one(): i32 = 1;\n")];
+	common::write_package(&dir.join("deps"), "math", "1.0.0", "", &math);
+	let main = [("main.ln", "const math = import(\"math\");\n\nfn main() = {}\n")];
+	common::write_package(dir, "app", "0.1.0", "", &main)
+}
+
+fn load_fetched(dir: &Path, app: &Path) -> Loader {
+	let deps = dir.join("deps");
+	let (mut loader, mod_id) = common::load_package_with(app, |fs| fs.dependency_dir = deps);
+	parse_mod(mod_id, &mut loader);
+	loader
+}
+
+#[test]
+fn sync_lock_detects_mismatch() {
+	let dir = TempDir::new("lock-mismatch");
+	let app = write_fetched(&dir);
+	load_fetched(&dir, &app).sync_lock(false).expect("first sync writes the lock");
+	let lock = ShioLock::load(&app.join("shio.lock")).unwrap();
+	let locked = lock.get("math").expect("math was not locked");
+	let math = fs::canonicalize(dir.join("deps/math")).unwrap();
+	assert_eq!(locked.path, math.display().to_string(), "fetched dependencies stay absolute");
+	assert_eq!(locked.version, "1.0.0");
+
+	fs::write(math.join("main.ln"), "pub fn one(): i32 = 2;\n").unwrap();
+	let message =
+		load_fetched(&dir, &app).sync_lock(false).expect_err("math changed since it was locked");
+	assert!(message.text.contains("dependency 'math' changed"), "{}", message.text);
+	assert_eq!(ShioLock::load(&app.join("shio.lock")).unwrap(), lock, "a mismatch keeps the lock");
+
+	load_fetched(&dir, &app).sync_lock(true).expect("--update-lock accepts the change");
+	let updated = ShioLock::load(&app.join("shio.lock")).unwrap();
+	assert_ne!(updated.get("math").unwrap().hash, locked.hash);
+}
+
+#[test]
+fn path_dependencies_are_not_locked() {
+	let dir = TempDir::new("lock-path");
+	let app = write_packages(&dir);
+	load_app(&app).sync_lock(false).expect("first sync");
+	assert_eq!(ShioLock::load(&app.join("shio.lock")), Ok(ShioLock::default()));
+
+	fs::write(dir.join("math/main.ln"), "pub fn one(): i32 = 2;\n").unwrap();
+	load_app(&app).sync_lock(false).expect("editing a path dependency isn't a mismatch");
+}
+
+#[test]
+fn editing_a_workspace_member_builds_again() {
+	let dir = TempDir::new("lock-workspace");
+	fs::write(dir.join("shio.toml"), "[workspace]\nmembers = [\"app\", \"math\"]\n").unwrap();
+	let math = [("main.ln", "pub fn one(): i32 = 1;\n")];
+	common::write_package(&dir, "math", "1.0.0", "", &math);
+	// no [dependencies], app finds math as a member
+	let main = [("main.ln", "const math = import(\"math\");\n\nfn main() = {}\n")];
+	common::write_package(&dir, "app", "0.1.0", "", &main);
+	let sync_members = || {
+		for (shio, file_system) in shio::load_packages(Some(dir.join("shio.toml"))).unwrap() {
+			let mut loader = Loader::new(shio, file_system);
+			let mod_id = loader.load_entry().expect("failed to load entry");
+			parse_mod(mod_id, &mut loader);
+			assert!(!loader.any_errors());
+			loader.sync_lock(false).expect("members aren't locked");
+		}
+	};
+	sync_members();
+	fs::write(dir.join("math/main.ln"), "pub fn one(): i32 = 2;\n").unwrap();
+	sync_members();
+	assert_eq!(ShioLock::load(&dir.join("app/shio.lock")), Ok(ShioLock::default()));
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::{fs, io::Cursor, path::PathBuf};
+
+use common::TempDir;
+use lemonc::lsp::serve;
+use serde_json::{json, Value};
+
+const PROGRAM: &str = "fn main() = {\n\tlet count = 1;\n\tlet _total = count + 1;\n}\n";
+
+/// writes `text` to `dir/main.ln`, giving its canonical path.
+fn temp_file(dir: &TempDir, text: &str) -> PathBuf {
+	let path = dir.join("main.ln");
+	fs::write(&path, text).unwrap();
+	fs::canonicalize(path).unwrap()
+}
+
+fn frame(message: Value) -> String {
+	let content = message.to_string();
+	format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+}
+
+/// serves the requests, giving the exit code and the messages the server wrote.
+fn run(requests: Vec<Value>) -> (i32, Vec<Value>) {
+	let input: String = requests.into_iter().map(frame).collect();
+	let mut output = Vec::new();
+	let exit_code = serve(&mut Cursor::new(input), &mut output);
+	let output = String::from_utf8(output).expect("the output is not utf-8");
+	let messages = output.split("Content-Length: ").filter(|frame| !frame.is_empty()).map(|frame| {
+		let (_, content) = frame.split_once("\r\n\r\n").expect("a frame without a header");
+		serde_json::from_str(content).expect("a frame that isn't json")
+	});
+	(exit_code, messages.collect())
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+	json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+	json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// initialize, open `text`, the given requests, then shutdown and exit.
+fn session(uri: &str, text: &str, requests: Vec<Value>) -> (i32, Vec<Value>) {
+	let document = json!({ "uri": uri, "languageId": "lemon", "version": 1, "text": text });
+	let mut all = vec![
+		request(1, "initialize", json!({ "capabilities": {} })),
+		notification("initialized", json!({})),
+		notification("textDocument/didOpen", json!({ "textDocument": document })),
+	];
+	all.extend(requests);
+	all.push(request(99, "shutdown", Value::Null));
+	all.push(notification("exit", Value::Null));
+	run(all)
+}
+
+fn response(messages: &[Value], id: u64) -> &Value {
+	let found = messages.iter().find(|message| message["id"] == id);
+	found.unwrap_or_else(|| panic!("no response to {}, {:#?}", id, messages))
+}
+
+fn diagnostics(messages: &[Value]) -> Vec<&Value> {
+	let published =
+		messages.iter().filter(|message| message["method"] == "textDocument/publishDiagnostics");
+	published.flat_map(|message| message["params"]["diagnostics"].as_array().unwrap()).collect()
+}
+
+#[test]
+fn hover_and_definition_of_a_value() {
+	let dir = TempDir::new("lsp-hover");
+	let path = temp_file(&dir, PROGRAM);
+	let uri = format!("file://{}", path.display());
+	let at_count =
+		json!({ "textDocument": { "uri": uri }, "position": { "line": 2, "character": 15 } });
+	let requests = vec![
+		request(2, "textDocument/hover", at_count.clone()),
+		request(3, "textDocument/definition", at_count),
+	];
+	let (exit_code, messages) = session(&uri, PROGRAM, requests);
+	assert_eq!(exit_code, 0, "an exit after shutdown");
+
+	let initialize = response(&messages, 1);
+	assert_eq!(initialize["result"]["capabilities"]["hoverProvider"], true);
+	assert!(diagnostics(&messages).is_empty(), "{:#?}", diagnostics(&messages));
+
+	let hover = &response(&messages, 2)["result"];
+	let value = hover["contents"]["value"].as_str().expect("no hover contents");
+	assert!(value.starts_with("```lemon\n"), "{}", value);
+	assert_eq!(hover["range"]["start"], json!({ "line": 2, "character": 14 }));
+
+	let definition = &response(&messages, 3)["result"];
+	assert_eq!(definition["uri"], uri);
+	assert_eq!(definition["range"]["start"]["line"], 1, "{:#?}", definition);
+	assert_eq!(response(&messages, 99)["result"], Value::Null);
+}
+
+#[test]
+fn unsupported_code_is_a_diagnostic() {
+	// the checker used to panic on a const fn and exit on a token that doesn't lex
+	for (name, text) in [("const_fn", "const fn one(): i32 = 1;\n"), ("lex", "§ fn main() = {}\n")] {
+		let dir = TempDir::new(&format!("lsp-{}", name));
+		let path = temp_file(&dir, text);
+		let uri = format!("file://{}", path.display());
+		let (exit_code, messages) = session(&uri, text, vec![]);
+		assert_eq!(exit_code, 0, "{}: {:#?}", name, messages);
+		let diagnostics = diagnostics(&messages);
+		assert!(!diagnostics.is_empty(), "{}: no diagnostics", name);
+		assert!(diagnostics.iter().all(|diagnostic| diagnostic["severity"] == 1), "{:#?}", diagnostics);
+	}
+}
+
+#[test]
+fn number_literals_out_of_range_are_diagnostics() {
+	// the checker used to unwrap the size of a literal it couldn't parse
+	let cases =
+		[("int", "340282366920938463463374607431768211456", "E0110"), ("float", "1e400", "E0109")];
+	for (name, literal, code) in cases {
+		let dir = TempDir::new(&format!("lsp-{}-literal", name));
+		let text = format!("fn main() = {{\n\tlet _big = {};\n}}\n", literal);
+		let path = temp_file(&dir, &text);
+		let uri = format!("file://{}", path.display());
+		let (exit_code, messages) = session(&uri, &text, vec![]);
+		assert_eq!(exit_code, 0, "{}: {:#?}", name, messages);
+		let diagnostics = diagnostics(&messages);
+		assert_eq!(diagnostics.len(), 1, "{:#?}", diagnostics);
+		assert_eq!(diagnostics[0]["code"], code, "{:#?}", diagnostics);
+		assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 1, "character": 12 }));
+	}
+}
+
+#[test]
+fn exit_without_shutdown_fails() {
+	let (exit_code, messages) = run(vec![notification("exit", Value::Null)]);
+	assert_eq!(exit_code, 1);
+	assert!(messages.is_empty());
+}
@@ -0,0 +1,18 @@
+mod common;
+
+#[test]
+fn structs_of_two_mods_do_not_collide() {
+	let (_, ir, _) = common::build("tests/snippets/mangle/main.ln");
+	let mut structs: Vec<_> = ir.structs.iter().filter_map(|found| found.name.as_deref()).collect();
+	structs.sort();
+	assert_eq!(structs, ["Point", "mod1.Point"]);
+
+	for method in ["Point.new", "Point.sum", "mod1.Point.new", "mod1.Point.sum"] {
+		assert!(ir.has_function(method), "'{}' was not built", method);
+	}
+	let mut names: Vec<_> = ir.functions.iter().map(|function| function.name.as_str()).collect();
+	let count = names.len();
+	names.sort();
+	names.dedup();
+	assert_eq!(names.len(), count, "two fns share a symbol");
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use lemonc::{
+	interp::Interp,
+	ir,
+	message::{ErrorCode, Stage},
+	verifier::verify_ownership,
+};
+
+const SCOPES: &str = "tests/snippets/ownership/scopes.ln";
+
+fn assert_reports(text: &str, code: u16, expected: &str) {
+	let message = common::assert_reports(text, expected);
+	assert_eq!(message.code, Some(ErrorCode(code)));
+	assert_eq!(message.stage, Some(Stage::Ownership));
+}
+
+#[test]
+fn ownership_accepts_built_programs() {
+	for path in common::BENCH.into_iter().chain([SCOPES]) {
+		let (ctx, ir, _) = common::build(path);
+		let messages = verify_ownership(&ir, &ctx.type_store);
+		let texts: Vec<_> = messages.iter().map(|message| &message.text).collect();
+		assert!(texts.is_empty(), "'{}': {:?}", path, texts);
+	}
+	let text = std::fs::read_to_string(common::crate_path("bench/mod.lnr")).unwrap();
+	assert!(common::verify_text(&text).is_empty());
+}
+
+#[test]
+fn ownership_drops_scopes_on_every_path() {
+	let (ctx, ir, _) = common::build(SCOPES);
+	let mut interp = Interp::new(&ir, &ctx.type_store);
+	assert_eq!(interp.run_main().ok(), Some(0));
+	assert!(interp.leaks().is_empty());
+}
+
+#[test]
+fn ownership_maps_errors_to_source() {
+	let (ctx, mut ir, mod_id) = common::build(SCOPES);
+	// forget the drop of `p` on the path that skips the `if`
+	let area = ir.functions.iter_mut().find(|function| function.name == "area").unwrap();
+	let block = area.blocks.last_mut().unwrap();
+	let position = block.instrs().iter().position(|instr| matches!(instr, ir::Instr::Drop(_)));
+	let position = position.expect("'area' drops 'p' before its last 'ret'");
+	block.remove_instr(position);
+
+	let messages = verify_ownership(&ir, &ctx.type_store);
+	assert_eq!(messages.len(), 1, "{:?}", messages);
+	let message = &messages[0];
+	assert!(message.text.ends_with("in 'area' is never dropped"), "{}", message.text);
+	assert_eq!(message.code, Some(ErrorCode(209)));
+	assert_eq!(message.mod_id, Some(mod_id));
+	// the `return 2`, with the allocation of `p` as a label
+	let source = std::fs::read_to_string(common::crate_path(SCOPES)).unwrap();
+	let range = message.range.expect("the 'ret' has a range");
+	assert_eq!(&source[range.start..range.end], "return 2");
+	let label = &message.labels[0];
+	assert_eq!(&source[label.range.start..label.range.end], "{ x, y: x }");
+}
+
+#[test]
+fn ownership_reports_leaks() {
+	let text = "fn main(): void = {\n  blk1: R1 = heap 8\n        ret\n}\n";
+	assert_reports(text, 209, "'R1' in 'main' is never dropped");
+	let text =
+		"fn main(R1: bool): void = {\n  blk1: R2 = heap 8\n        jmp_if bool R1, blk2, blk3\n\n  \
+	            blk2: drop ptr R2\n        jmp blk3\n\n  blk3: ret\n}\n";
+	assert_reports(text, 209, "'R2' in 'main' isn't dropped on every path");
+}
+
+#[test]
+fn ownership_reports_double_drops() {
+	let text =
+		"fn main(): void = {\n  blk1: R1 = heap 8\n        drop ptr R1\n        drop ptr R1\n        \
+	            ret\n}\n";
+	assert_reports(text, 210, "'R1' in 'main' is dropped twice");
+	let text = "fn main(R1: bool): void = {\n  blk1: jmp_if bool R1, blk2, blk3\n\n  \
+	            blk2: R2 = heap 8\n        drop ptr R2\n        ret\n\n  blk3: drop ptr R2\n        ret\n}\n";
+	assert_reports(text, 210, "'R2' in 'main' is dropped on a path that never allocates it");
+}
+
+#[test]
+fn ownership_reports_use_after_drop() {
+	let text = "type Pair = { i32, i32 }, size=8\n\nfn main(): void = {\n  blk1: R1 = heap 8\n        \
+	            R2 = Pair getptr ptr R1 0\n        drop Pair R1\n        set R2, i32 1\n        ret\n}\n";
+	assert_reports(text, 208, "'R1' in 'main' is used after it is dropped");
+}
+
+#[test]
+fn ownership_reports_moves_of_borrows() {
+	let text =
+		"type Pair = { i32, i32 }, size=8\n\nfn take(R1: ptr): Pair = {\n  blk1: ret ptr R1\n}\n";
+	assert_reports(text, 211, "'R1' in 'take' is borrowed, it can't be moved");
+	let text = "fn take(R1: ptr): ptr = {\n  blk1: ret ptr R1\n}\n";
+	assert!(common::verify_text(text).is_empty());
+}
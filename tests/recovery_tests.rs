@@ -0,0 +1,25 @@
+mod common;
+
+use lemonc::message::Severity;
+
+/// parses and checks a snippet, giving the error messages it reported in order.
+fn errors(name: &str) -> Vec<String> {
+	let (mut loader, _, _) = common::check(&format!("tests/snippets/recovery/{}", name));
+	let mut messages = loader.take_messages();
+	let errors = messages.sorted().filter(|message| message.severity == Severity::Error);
+	errors.map(|message| message.text.clone()).collect()
+}
+
+#[test]
+fn checks_the_stmts_after_an_error_in_a_body() {
+	let errors = errors("body.ln");
+	assert_eq!(errors.len(), 3, "{:#?}", errors);
+	assert!(errors[1].contains("bool"), "the error inside the if block, {}", errors[1]);
+}
+
+#[test]
+fn reports_a_lex_error_while_recovering() {
+	let errors = errors("lex.ln");
+	assert_eq!(errors.len(), 2, "{:#?}", errors);
+	assert!(errors[1].contains("unsupported token"), "{}", errors[1]);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use common::TempDir;
+use lemonc::{
+	file_system::FileSystem,
+	loader::{Loader, ModId},
+	message::ErrorCode,
+	shio::{ShioConfig, ShioRegistry},
+};
+use semver::VersionReq;
+
+const MATH: &str = "pub fn one(): i32 = 1;\n";
+const IMPORT_MATH: &str = "const math = import(\"math\");\n\nfn main() = {}\n";
+
+/// publishes a `math` package of `version` to the registry in `registry_dir`.
+fn publish_math(dir: &Path, registry_dir: &Path, version: &str) -> Result<(), String> {
+	let package =
+		common::write_package(&dir.join(version), "math", version, "", &[("main.ln", MATH)]);
+	let shio = ShioConfig::load_from_dir(&package).unwrap();
+	let sources = FileSystem::package_sources(&shio.root).unwrap();
+	let hash = FileSystem::hash_sources(&shio.root).unwrap();
+	let mut registry = ShioRegistry::open(registry_dir)?;
+	registry.publish(&shio, &sources, hash).map(|_| ())
+}
+
+/// `app` depends on `math` by version, found in the registry in `registry_dir`.
+fn load_app(dir: &Path, registry_dir: &Path, files: &[(&str, &str)]) -> (Loader, ModId) {
+	let sections = "[dependencies]\nmath = { version = \"^1.0\" }\n";
+	let app = common::write_package(dir, "app", "0.1.0", sections, files);
+	let registry_dir = registry_dir.to_path_buf();
+	common::load_package_with(&app, |fs| {
+		fs.dependency_dir = dir.join("deps");
+		fs.remote_dir = registry_dir;
+	})
+}
+
+#[test]
+fn publish_adds_a_version_to_the_index() {
+	let dir = TempDir::new("registry-publish");
+	let registry_dir = dir.join("registry");
+	fs::create_dir_all(&registry_dir).unwrap();
+	publish_math(&dir, &registry_dir, "1.0.0").expect("failed to publish");
+	publish_math(&dir, &registry_dir, "1.2.0").expect("failed to publish");
+	let err = publish_math(&dir, &registry_dir, "1.2.0").unwrap_err();
+	assert_eq!(err, "'math' 1.2.0 is already published");
+
+	let registry = ShioRegistry::open(&registry_dir).unwrap();
+	assert_eq!(registry.index.packages.len(), 2);
+	let entry = registry.find("math", &[VersionReq::parse("^1.0").unwrap()]).unwrap();
+	assert_eq!((entry.version.as_str(), entry.path.as_str()), ("1.2.0", "math/1.2.0"));
+	assert!(registry.package_dir(entry).join("main.ln").is_file());
+	assert!(registry.package_dir(entry).join("shio.toml").is_file());
+	let entry = registry.find("math", &[VersionReq::parse("~1.0").unwrap()]).unwrap();
+	assert_eq!(entry.version, "1.0.0");
+	assert!(registry.find("math", &[VersionReq::parse("^2").unwrap()]).is_none());
+	assert!(registry.find("io", &[]).is_none());
+}
+
+#[test]
+fn imports_a_published_package() {
+	let dir = TempDir::new("registry-import");
+	let registry_dir = dir.join("registry");
+	fs::create_dir_all(&registry_dir).unwrap();
+	publish_math(&dir, &registry_dir, "1.2.0").unwrap();
+	let (mut loader, mod_id) = load_app(&dir, &registry_dir, &[("main.ln", IMPORT_MATH)]);
+	let math = loader.load_source("math", mod_id).expect("math is in the registry");
+	let abs_path = &loader.lookup_source(math).unwrap().abs_path;
+	assert!(abs_path.ends_with("registry/math/1.2.0/main.ln"), "{}", abs_path.display());
+}
+
+#[test]
+fn edited_package_does_not_match_its_hash() {
+	let dir = TempDir::new("registry-hash");
+	let registry_dir = dir.join("registry");
+	fs::create_dir_all(&registry_dir).unwrap();
+	publish_math(&dir, &registry_dir, "1.2.0").unwrap();
+	fs::write(registry_dir.join("math/1.2.0/main.ln"), "pub fn one(): i32 = 2;\n").unwrap();
+	let (mut loader, mod_id) = load_app(&dir, &registry_dir, &[("main.ln", IMPORT_MATH)]);
+	let message = loader.load_source("math", mod_id).expect_err("math was edited");
+	assert_eq!(message.code, Some(ErrorCode(611)));
+	assert_eq!(message.text, "'math' 1.2.0 changed since it was published");
+}
+
+#[test]
+fn local_mod_is_found_before_a_broken_registry() {
+	let dir = TempDir::new("registry-broken");
+	let registry_dir = dir.join("registry");
+	fs::create_dir_all(&registry_dir).unwrap();
+	fs::write(registry_dir.join("index.toml"), "[[package\n").unwrap();
+	let files = [("main.ln", IMPORT_MATH), ("math.ln", MATH)];
+	let (mut loader, mod_id) = load_app(&dir, &registry_dir, &files);
+	let math = loader.load_source("math", mod_id).expect("math.ln is next to main.ln");
+	assert!(loader.lookup_source(math).unwrap().abs_path.ends_with("app/math.ln"));
+
+	let (mut loader, mod_id) =
+		load_app(&dir.join("missing"), &registry_dir, &[("main.ln", IMPORT_MATH)]);
+	let message = loader.load_source("math", mod_id).expect_err("the registry can't be read");
+	assert_eq!(message.code, Some(ErrorCode(609)));
+}
@@ -0,0 +1,70 @@
+use lemonc::{
+	checker::types::TypeId,
+	interp::{Interp, Value},
+	session::{open_delimiters, Checked, Session},
+};
+
+/// checks, builds and interprets an input, giving its value.
+fn evaluate(session: &mut Session, input: &str) -> Option<Value> {
+	let checked = session.check(input).unwrap_or_else(|| panic!("'{}' doesn't check", input));
+	let compiled =
+		session.compile(input, &checked).unwrap_or_else(|| panic!("'{}' doesn't build", input));
+	let mut interp = Interp::new(&compiled.ir, &session.ctx.type_store);
+	let value =
+		interp.call(&compiled.name, Vec::new()).unwrap_or_else(|message| panic!("{}", message.text));
+	compiled.ret.map(|_| value)
+}
+
+#[test]
+fn open_delimiters_counts_unclosed_braces() {
+	assert_eq!(open_delimiters("1 + 2"), 0);
+	assert_eq!(open_delimiters("fn add(a: i32, b: i32): i32 = {"), 1);
+	assert_eq!(open_delimiters("if (a > (b + 1)) {\n  {"), 2);
+	assert_eq!(open_delimiters("{ [ ( ) ] }"), 0);
+	assert_eq!(open_delimiters("}"), -1);
+}
+
+#[test]
+fn open_delimiters_skips_strings_and_comments() {
+	assert_eq!(open_delimiters("let s = \"{(\";"), 0);
+	assert_eq!(open_delimiters("let c = '{';"), 0);
+	assert_eq!(open_delimiters("let s = \"\\\"{\";"), 0);
+	assert_eq!(open_delimiters("{ // }\n"), 1);
+}
+
+#[test]
+fn check_tells_inputs_apart() {
+	let mut session = Session::new();
+	assert!(matches!(session.check("fn double(x: i32): i32 = x * 2;"), Some(Checked::Items)));
+	assert!(matches!(session.check("let base: i32 = 20;"), Some(Checked::Let)));
+	assert!(matches!(session.check("double(base);"), Some(Checked::Stmt)));
+	match session.check("double(base)") {
+		Some(Checked::Expr { type_id, range }) => {
+			assert_eq!(type_id, TypeId::I32);
+			assert_eq!((range.start, range.end), (0, 12));
+		}
+		_ => panic!("'double(base)' is an expression"),
+	}
+}
+
+#[test]
+fn check_rolls_back_an_error() {
+	let mut session = Session::new();
+	assert!(session.check("let flag: bool = 1;").is_none());
+	assert!(session.check("flag").is_none(), "'flag' was not bound");
+	assert!(matches!(session.check("let flag: i32 = 1;"), Some(Checked::Let)));
+	assert!(matches!(session.check("flag"), Some(Checked::Expr { .. })));
+}
+
+#[test]
+fn compile_keeps_definitions_and_lets() {
+	let mut session = Session::new();
+	assert_eq!(evaluate(&mut session, "fn double(x: i32): i32 = x * 2;"), None);
+	assert_eq!(evaluate(&mut session, "let base: i32 = 20;"), None);
+	assert_eq!(evaluate(&mut session, "double(base) + 2"), Some(Value::Int(42)));
+	// a literal is built with the type it defaults to
+	let checked = session.check("1 + 2").unwrap();
+	let compiled = session.compile("1 + 2", &checked).unwrap();
+	assert_eq!(compiled.ret, Some(TypeId::I32));
+	assert!(compiled.ir.has_function(&compiled.name), "'{}' was not built", compiled.name);
+}
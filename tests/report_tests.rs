@@ -0,0 +1,105 @@
+mod common;
+
+use std::path::PathBuf;
+
+use lemonc::{
+	message::{Message, RootMessages, Stage, EXIT_FAILURE},
+	note,
+	range::Range,
+	report,
+	source::Source,
+};
+use serde_json::{json, Value};
+
+fn json_line(message: &Message, source: Option<&Source>) -> Value {
+	let line = report::json_message(message, source).expect("failed to serialize");
+	assert!(!line.contains('\n'), "one message per line, {}", line);
+	serde_json::from_str(&line).unwrap()
+}
+
+#[test]
+fn json_message_has_a_stable_schema() {
+	let source =
+		Source::new("let a = 1;\nlet b = a;\n".into(), PathBuf::from("/main.ln"), "main.ln".into());
+	let message = Message::error_ownership("use of moved value 'a'")
+		.code(201)
+		.range(Range::new(19, 20))
+		.label(Range::new(8, 9), "moved here")
+		.note(note!("consider borrowing it"));
+	let range = |start, end, line, column, end_line, end_column| {
+		json!({
+			"start": start, "end": end,
+			"line": line, "column": column,
+			"end_line": end_line, "end_column": end_column,
+		})
+	};
+	let expected = json!({
+		"severity": "error",
+		"stage": "ownership",
+		"code": "E0201",
+		"text": "use of moved value 'a'",
+		"file": "main.ln",
+		"range": range(19, 20, 2, 9, 2, 10),
+		"notes": [{ "text": "consider borrowing it", "range": null }],
+		"labels": [{ "text": "moved here", "range": range(8, 9, 1, 9, 1, 10) }],
+	});
+	assert_eq!(json_line(&message, Some(&source)), expected);
+}
+
+#[test]
+fn json_message_without_a_source_has_no_ranges() {
+	let message = Message::error("failed to read shio.toml").range(Range::new(0, 4));
+	let expected = json!({
+		"severity": "error",
+		"stage": null,
+		"code": null,
+		"text": "failed to read shio.toml",
+		"file": null,
+		"range": null,
+		"notes": [],
+		"labels": [],
+	});
+	assert_eq!(json_line(&message, None), expected);
+}
+
+#[test]
+fn stages_have_stable_exit_codes() {
+	let stages = [
+		(Stage::Syntax, 2),
+		(Stage::Resolve, 3),
+		(Stage::Type, 4),
+		(Stage::Ownership, 5),
+		(Stage::Build, 6),
+		(Stage::Codegen, 7),
+		(Stage::Comptime, 8),
+	];
+	for (stage, code) in stages {
+		assert_eq!(stage.exit_code(), code, "{}", stage.name());
+	}
+	assert_eq!(Message::error("no stage").exit_code(), EXIT_FAILURE);
+}
+
+#[test]
+fn the_earliest_failing_stage_sets_the_exit_code() {
+	let mut messages = RootMessages::new();
+	messages.message(Message::warning_type("unused value 'a'"));
+	assert_eq!(messages.exit_code(), None, "warnings don't fail");
+	messages.message(Message::error_ownership("use of moved value 'a'"));
+	messages.message(Message::error_type("expected 'i32', found 'bool'"));
+	assert_eq!(messages.exit_code(), Some(4));
+	messages.message(Message::error_syntax("unexpected token"));
+	assert_eq!(messages.exit_code(), Some(2));
+}
+
+#[test]
+fn checked_programs_exit_with_their_stage() {
+	let exit_code = |path: &str| {
+		let (mut loader, _, _) = common::check(path);
+		loader.take_messages().exit_code()
+	};
+	assert_eq!(exit_code("tests/snippets/recovery/lex.ln"), Some(2));
+	assert_eq!(exit_code("tests/snippets/recovery/body.ln"), Some(4));
+	assert_eq!(exit_code("tests/snippets/cycle/a.ln"), Some(3));
+	assert_eq!(exit_code("tests/snippets/report/borrow.ln"), Some(5));
+	assert_eq!(exit_code("tests/snippets/warnings/main.ln"), None);
+}
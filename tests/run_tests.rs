@@ -0,0 +1,37 @@
+use std::{
+	path::Path,
+	process::{Command, Output},
+};
+
+/// `lemon run` on a snippet, `args` go after `--`.
+fn lemon_run(name: &str, args: &[&str]) -> Output {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snippets/run").join(name);
+	let mut command = Command::new(env!("CARGO_BIN_EXE_lemonc"));
+	command.arg("run").arg(path);
+	if !args.is_empty() {
+		command.arg("--").args(args);
+	}
+	command.output().expect("failed to run lemon")
+}
+
+#[test]
+fn run_exits_with_the_code_main_returns() {
+	let output = lemon_run("exit.ln", &[]);
+	assert_eq!(output.status.code(), Some(3), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn run_passes_the_args_after_dashes() {
+	// argv[0] is the file
+	assert_eq!(lemon_run("args.ln", &[]).status.code(), Some(1));
+	assert_eq!(lemon_run("args.ln", &["one", "two"]).status.code(), Some(3));
+	assert_eq!(lemon_run("args.ln", &["--flag"]).status.code(), Some(2));
+}
+
+#[test]
+fn run_rejects_a_main_the_jit_cannot_call() {
+	let output = lemon_run("bad_main.ln", &[]);
+	assert_eq!(output.status.code(), Some(1));
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(stdout.contains("main must take no args, or an argc and an argv"), "{}", stdout);
+}
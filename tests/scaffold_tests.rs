@@ -0,0 +1,80 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use common::TempDir;
+use lemonc::{
+	checker::{context::Context, Checker},
+	parser::parse_mod,
+	shio::{self, ShioConfig},
+};
+
+/// loads the shio.toml of a new package and checks its entry.
+fn check_package(dir: &Path) -> ShioConfig {
+	let shio = ShioConfig::load_from_dir(dir).expect("the shio.toml doesn't load");
+	let (mut loader, mod_id) = common::load_package(dir);
+	parse_mod(mod_id, &mut loader);
+	Checker::new(&mut Context::new(), &mut loader).check_messages(mod_id);
+	assert!(!loader.any_errors(), "the template doesn't check");
+	shio
+}
+
+#[test]
+fn new_creates_a_bin_package() {
+	let dir = TempDir::new("scaffold-bin");
+	let app = shio::new_package(&dir, "app", false).expect("failed to create app");
+	assert_eq!(app, dir.join("app"));
+	let shio = check_package(&app);
+	assert_eq!((shio.package.name.as_str(), shio.package.version.as_str()), ("app", "0.1.0"));
+	assert_eq!(shio.loader.main, app.join("src/main.ln"));
+	assert!(fs::read_to_string(app.join("src/main.ln")).unwrap().contains("fn main()"));
+	assert!(!app.join("src/lib.ln").exists());
+	assert_eq!(fs::read_to_string(app.join(".gitignore")).unwrap(), "*.o\n/app\n");
+}
+
+#[test]
+fn new_lib_points_at_a_lib_entry() {
+	let dir = TempDir::new("scaffold-lib");
+	let math = shio::new_package(&dir, "math", true).expect("failed to create math");
+	let shio = ShioConfig::load_from_dir(&math).unwrap();
+	assert_eq!(shio.loader.main, math.join("src/lib.ln"));
+	assert!(fs::read_to_string(&shio.loader.main).unwrap().starts_with("pub fn add"));
+	assert!(!math.join("src/main.ln").exists());
+}
+
+#[test]
+fn new_rejects_an_existing_dir() {
+	let dir = TempDir::new("scaffold-existing");
+	fs::create_dir_all(dir.join("app")).unwrap();
+	let err = shio::new_package(&dir, "app", false).unwrap_err();
+	assert_eq!(err, format!("'{}' already exists", dir.join("app").display()));
+	assert!(!dir.join("app/shio.toml").exists());
+}
+
+#[test]
+fn init_names_the_package_after_its_dir() {
+	let dir = TempDir::new("scaffold-init");
+	let app = dir.join("app");
+	fs::create_dir_all(&app).unwrap();
+	fs::write(app.join(".gitignore"), "target/\n").unwrap();
+	assert_eq!(shio::init_package(&app, None, false), Ok("app".to_string()));
+	check_package(&app);
+	assert_eq!(fs::read_to_string(app.join(".gitignore")).unwrap(), "target/\n", "kept as it was");
+
+	let err = shio::init_package(&app, None, false).unwrap_err();
+	assert!(err.ends_with("already has a shio.toml"), "{}", err);
+}
+
+#[test]
+fn rejects_empty_and_invalid_names() {
+	let dir = TempDir::new("scaffold-names");
+	assert_eq!(shio::init_package(&dir, Some(""), false), Err("package name can't be empty".into()));
+	assert_eq!(shio::new_package(&dir, "", false), Err("package name can't be empty".into()));
+	let err = shio::init_package(Path::new("/"), None, false).unwrap_err();
+	assert_eq!(err, "'/' has no name to use, pass one");
+	for name in ["1app", "my app", "app.ln"] {
+		let err = shio::new_package(&dir, name, false).unwrap_err();
+		assert!(err.starts_with("invalid package name"), "{}", err);
+	}
+	assert!(!dir.join("shio.toml").exists());
+}
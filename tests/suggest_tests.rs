@@ -0,0 +1,61 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use common::TempDir;
+use lemonc::checker::diags::suggest::{edit_distance, similar_name};
+
+/// `app` has `math` as a path dependency and a `lib/math.ln` of its own.
+fn write_packages(dir: &Path) -> PathBuf {
+	common::write_package(dir, "math", "1.0.0", "", &[("main.ln", "pub fn one(): i32 = 1;\n")]);
+	let files = [("main.ln", "fn main() = {}\n"), ("lib/math.ln", "pub fn one(): i32 = 1;\n")];
+	common::write_package(dir, "app", "0.1.0", "[dependencies]\nmath = \"../math\"\n", &files)
+}
+
+/// the notes of the error for importing `path` from the entry of `app`.
+fn import_notes(app: &Path, path: &str) -> Vec<String> {
+	let (mut loader, mod_id) = common::load_package(app);
+	let message = loader.load_source(path, mod_id).expect_err("the import should not be found");
+	message.notes.iter().map(|note| note.text.clone()).collect()
+}
+
+#[test]
+fn edit_distance_counts_edits() {
+	assert_eq!(edit_distance("kitten", "sitting"), 3);
+	assert_eq!(edit_distance("count", "count"), 0);
+	assert_eq!(edit_distance("", "abc"), 3);
+	assert_eq!(edit_distance("abc", ""), 3);
+	// a swap of two adjacent chars is one edit
+	assert_eq!(edit_distance("ab", "ba"), 1);
+	assert_eq!(edit_distance("cuont", "count"), 1);
+}
+
+#[test]
+fn similar_name_picks_the_closest() {
+	assert_eq!(similar_name("cuont", ["count", "amount", "counter"]), Some("count"));
+	assert_eq!(similar_name("lenght", ["width", "length"]), Some("length"));
+	assert_eq!(similar_name("x", ["y"]), Some("y"));
+}
+
+#[test]
+fn similar_name_rejects_far_and_exact_names() {
+	assert_eq!(similar_name("count", ["total", "sum"]), None);
+	assert_eq!(similar_name("count", ["count"]), None);
+	assert_eq!(similar_name("count", []), None);
+}
+
+#[test]
+fn suggests_a_path_dependency() {
+	let dir = TempDir::new("suggest-dependency");
+	let app = write_packages(&dir);
+	let notes = import_notes(&app, "mth");
+	assert!(notes.contains(&"did you mean 'math'?".to_owned()), "{:#?}", notes);
+}
+
+#[test]
+fn suggests_a_mod_in_the_imported_dir() {
+	let dir = TempDir::new("suggest-dir");
+	let app = write_packages(&dir);
+	let notes = import_notes(&app, "./lib/mth.ln");
+	assert!(notes.contains(&"did you mean './lib/math.ln'?".to_owned()), "{:#?}", notes);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use lemonc::{
+	checker::types::TypeId, disassembler::Disassembler, interp::Interp, ir, message::ErrorCode,
+};
+
+const MATH: &str = "tests/snippets/tests/math.ln";
+
+/// checks and builds a program, with its tests if `tests` has a filter, then
+/// hands its verified ir to `test` with the exit code of its main.
+fn with_run(path: &str, tests: Option<Option<&str>>, test: impl FnOnce(&ir::IR, i32)) {
+	let (ctx, ir, _) = common::build_with(path, |builder| match tests {
+		Some(filter) => builder.with_tests(filter.map(str::to_owned)),
+		None => builder,
+	});
+	let messages = common::verify(&ir, &ctx.type_store);
+	assert!(messages.is_empty(), "{:?}", messages);
+
+	let mut interp = Interp::new(&ir, &ctx.type_store);
+	let exit_code = interp.run_main().unwrap_or_else(|message| panic!("{}", message.text));
+	assert!(interp.leaks().is_empty(), "'{}' leaked", path);
+	test(&ir, exit_code);
+}
+
+#[test]
+fn test_harness_runs_every_test() {
+	with_run(MATH, Some(None), |ir, exit_code| {
+		// `double_drops_points` fails
+		assert_eq!(exit_code, 1);
+		let tests = ["double_adds_twice", "double_drops_points", "double_of_five"];
+		for name in tests {
+			let function = ir.functions.iter().find(|function| function.name == name);
+			let function = function.unwrap_or_else(|| panic!("'{}' isn't built", name));
+			assert_eq!(function.ret, TypeId::I32, "'{}' returns whether it failed", name);
+		}
+		let mains = ir.functions.iter().filter(|function| function.is_main()).count();
+		assert_eq!(mains, 1, "the harness replaces the program's main");
+	});
+}
+
+#[test]
+fn test_harness_filters_tests() {
+	with_run(MATH, Some(Some("five")), |_, exit_code| assert_eq!(exit_code, 0));
+	with_run(MATH, Some(Some("points")), |_, exit_code| assert_eq!(exit_code, 1));
+}
+
+#[test]
+fn test_fns_are_left_out_of_builds() {
+	with_run(MATH, None, |ir, exit_code| {
+		assert_eq!(exit_code, 0);
+		assert!(!ir.has_function("double_adds_twice"));
+	});
+}
+
+#[test]
+fn assert_in_a_helper_fails_its_test() {
+	const HELPER: &str = "tests/snippets/tests/helper.ln";
+	// the run goes on to the summary instead of exiting with 101
+	with_run(HELPER, Some(None), |_, exit_code| assert_eq!(exit_code, 1));
+	with_run(HELPER, Some(Some("one")), |_, exit_code| assert_eq!(exit_code, 0));
+	with_run(HELPER, Some(Some("zero")), |_, exit_code| assert_eq!(exit_code, 1));
+
+	// the failed flag is a global, which goes through the textual ir
+	let (ctx, ir, _) = common::build_with(HELPER, |builder| builder.with_tests(None));
+	let mut text = String::new();
+	Disassembler::new(&ctx.type_store).disassemble_program(&ir, &mut text);
+	assert!(text.contains("global test_failed: i32"), "{}", text);
+	let (type_store, ir) = common::assemble(&text);
+	let messages = common::verify(&ir, &type_store);
+	assert!(messages.is_empty(), "{:?}", messages);
+	assert_eq!(Interp::new(&ir, &type_store).run_main().unwrap(), 1);
+}
+
+#[test]
+fn assert_exits_with_its_location() {
+	with_run("tests/snippets/tests/fails.ln", None, |ir, exit_code| {
+		assert_eq!(exit_code, 101);
+		let main = ir.functions.iter().find(|function| function.is_main()).unwrap();
+		let locations: Vec<_> = main
+			.blocks
+			.iter()
+			.flat_map(|block| block.instrs())
+			.filter_map(|instr| match instr {
+				ir::Instr::Call(call) if call.callee == "dprintf" => Some(call.args[2].value.clone()),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(locations.len(), 2, "{:?}", locations);
+		let ir::BasicValue::String(location) = &locations[1] else {
+			panic!("the location is a str, found {:?}", locations[1]);
+		};
+		assert!(location.ends_with("tests/fails.ln:3:3"), "{}", location);
+	});
+}
+
+#[test]
+fn test_fns_take_no_params() {
+	let (mut loader, _, _) = common::check("tests/snippets/tests/invalid.ln");
+	let mut messages = loader.take_messages();
+	let message = messages.sorted().find(|message| message.code == Some(ErrorCode(403)));
+	let message = message.expect("'takes_params' is rejected");
+	assert_eq!(message.text, "invalid test function 'takes_params'");
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::assert_reports;
+use lemonc::verifier::verify_ir;
+
+/// checks, builds and verifies a program, returns the verifier's messages.
+fn verify_program(path: &str) -> Vec<String> {
+	let (ctx, ir, _) = common::build(path);
+	verify_ir(&ir, &ctx.type_store).into_iter().map(|message| message.text).collect()
+}
+
+#[test]
+fn verifier_accepts_bench() {
+	for path in common::BENCH {
+		assert_eq!(verify_program(path), Vec::<String>::new(), "'{}'", path);
+	}
+	let text = std::fs::read_to_string(common::crate_path("bench/mod.lnr")).unwrap();
+	assert!(common::verify_text(&text).is_empty());
+}
+
+#[test]
+fn verifier_reports_missing_return() {
+	let messages = verify_program("bench/prim.ln");
+	let expected = "in 'prim' doesn't end in 'jmp', 'jmp_if' or 'ret'";
+	assert!(messages.iter().any(|message| message.contains(expected)), "{:?}", messages);
+}
+
+#[test]
+fn verifier_reports_broken_blocks() {
+	assert_reports("fn main(): void = {\n  blk1: R1 = stack i32\n}\n", "doesn't end in");
+	assert_reports(
+		"fn main(): void = {\n  blk1: jmp blk7\n}\n",
+		"jumps to 'blk7', which doesn't exist",
+	);
+	assert_reports(
+		"fn main(): void = {\n  blk1: ret\n        jmp blk2\n\n  blk2: ret\n}\n",
+		"has instructions after",
+	);
+}
+
+#[test]
+fn verifier_reports_undefined_registers() {
+	let text = "fn main(R1: bool): i32 = {\n  blk1: jmp_if bool R1, blk2, blk3\n\n  \
+	            blk2: R2 = stack i32\n        jmp blk3\n\n  blk3: load R3, i32 R2\n        ret i32 R3\n}\n";
+	assert_reports(text, "'blk3' in 'main' uses 'R2' before it is defined");
+}
+
+#[test]
+fn verifier_reports_operand_types() {
+	let text = "fn main(R1: i32): void = {\n  blk1: R2 = stack i32\n        \
+	            R2 = cmp_lt i32 R1, i32 1\n        ret\n}\n";
+	assert_reports(text, "'cmp_lt' stores a bool, found a 'i32' dest");
+	let text = "fn main(R1: i32, R2: i64): void = {\n  blk1: R3 = stack i32\n        \
+	            R3 = add i32 R1, i64 R2\n        ret\n}\n";
+	assert_reports(text, "'add' operands differ, 'i32' and 'i64'");
+	assert_reports(
+		"fn main(): i32 = {\n  blk1: ret bool true\n}\n",
+		"returns a 'bool', expected 'i32'",
+	);
+}
+
+#[test]
+fn verifier_reports_drop_of_stack_values() {
+	let text = "fn main(): void = {\n  blk1: R1 = stack i32\n        drop i32 R1\n        ret\n}\n";
+	assert_reports(text, "drops 'R1', which isn't the result of a 'heap'");
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use std::path::Path;
+
+use common::TempDir;
+use lemonc::{loader::Requirement, message::MessageResult, parser::parse_mod, shio::ShioConfig};
+
+/// writes a package, `dependencies` are `(name, version requirement)` found next to it.
+fn write_package(dir: &Path, name: &str, version: &str, dependencies: &[(&str, &str)]) {
+	let mut sections = "[dependencies]\n".to_owned();
+	let mut source = String::new();
+	for (dependency, requirement) in dependencies {
+		sections +=
+			&format!("{0} = {{ path = \"../{0}\", version = \"{1}\" }}\n", dependency, requirement);
+		source += &format!("const {0} = import(\"{0}\");\n", dependency);
+	}
+	let main = source + "\nfn main() = {}\n";
+	common::write_package(dir, name, version, &sections, &[("main.ln", &main)]);
+}
+
+fn check_versions(dir: &Path) -> MessageResult<()> {
+	let (mut loader, mod_id) = common::load_package(&dir.join("app"));
+	parse_mod(mod_id, &mut loader);
+	loader.check_versions()
+}
+
+#[test]
+fn invalid_requirement_names_the_package() {
+	let requirement = Requirement::new("math".to_owned(), "app".to_owned(), "one.two".to_owned());
+	let message = requirement.parse().expect_err("'one.two' is not a requirement");
+	assert_eq!(message.text, "invalid version requirement 'one.two' in package 'app'");
+}
+
+#[test]
+fn check_versions_accepts_matching_dependency() {
+	let dir = TempDir::new("versions-matching");
+	write_package(&dir, "math", "1.4.0", &[]);
+	write_package(&dir, "app", "0.1.0", &[("math", "^1.2")]);
+	assert!(check_versions(&dir).is_ok());
+}
+
+#[test]
+fn check_versions_reports_mismatch() {
+	let dir = TempDir::new("versions-mismatch");
+	write_package(&dir, "math", "2.0.0", &[]);
+	write_package(&dir, "app", "0.1.0", &[("math", "^1.2")]);
+	let message = check_versions(&dir).expect_err("2.0.0 does not match ^1.2");
+	assert_eq!(message.text, "dependency 'math' 2.0.0 does not match '^1.2'");
+	let notes: Vec<_> = message.notes.iter().map(|note| note.text.as_str()).collect();
+	assert_eq!(notes[0], "required by package 'app'");
+	assert!(notes[1].starts_with("found 'math' 2.0.0 in"), "{}", notes[1]);
+}
+
+#[test]
+fn check_versions_reports_conflicts() {
+	let dir = TempDir::new("versions-conflict");
+	write_package(&dir, "math", "1.4.0", &[]);
+	write_package(&dir, "util", "0.1.0", &[("math", "^2.0")]);
+	write_package(&dir, "app", "0.1.0", &[("math", "~1.4"), ("util", "=0.1.0")]);
+	let message = check_versions(&dir).expect_err("no math meets both ~1.4 and ^2.0");
+	assert_eq!(message.text, "conflicting versions of dependency 'math'");
+	let notes: Vec<_> = message.notes.iter().map(|note| note.text.as_str()).collect();
+	assert!(notes.contains(&"package 'app' requires '~1.4'"), "{:?}", notes);
+	assert!(notes.contains(&"package 'util' requires '^2.0', not met"), "{:?}", notes);
+}
+
+/// whether `app` requiring `requirement` of math accepts math `version`.
+fn accepts(requirement: &str, version: &str) -> bool {
+	let dir = TempDir::new(&format!("versions-{}-{}", requirement, version));
+	write_package(&dir, "math", version, &[]);
+	write_package(&dir, "app", "0.1.0", &[("math", requirement)]);
+	check_versions(&dir).is_ok()
+}
+
+#[test]
+fn check_versions_follows_the_operator() {
+	assert!(accepts("^1.2", "1.9.3"));
+	assert!(!accepts("^1.2", "2.0.0"));
+	// below 1.0 the minor is the breaking part
+	assert!(!accepts("^0.2", "0.3.0"));
+	assert!(accepts("~1.2", "1.2.9"));
+	assert!(!accepts("~1.2", "1.3.0"));
+	assert!(!accepts("=1.2.0", "1.2.1"));
+}
+
+#[test]
+fn check_versions_reports_conflicts_between_compatible_ranges() {
+	let dir = TempDir::new("versions-narrow-conflict");
+	write_package(&dir, "math", "1.4.0", &[]);
+	write_package(&dir, "util", "0.1.0", &[("math", "=1.4.0")]);
+	write_package(&dir, "app", "0.1.0", &[("math", "^1.5"), ("util", "^0.1")]);
+	let message = check_versions(&dir).expect_err("1.4.0 does not meet ^1.5");
+	assert_eq!(message.text, "conflicting versions of dependency 'math'");
+	let notes: Vec<_> = message.notes.iter().map(|note| note.text.as_str()).collect();
+	assert!(notes.contains(&"package 'app' requires '^1.5', not met"), "{:?}", notes);
+	assert!(notes.contains(&"package 'util' requires '=1.4.0'"), "{:?}", notes);
+}
+
+#[test]
+fn rejects_malformed_dependency_values() {
+	let dir = TempDir::new("versions-malformed");
+	let cases = [
+		("math = { path = 1 }", "invalid key path of dependency 'math', expected a string"),
+		("math = { version = 1.2 }", "invalid key version of dependency 'math', expected a string"),
+		("math = 3", "invalid dependency 'math'"),
+	];
+	for (index, (dependency, expected)) in cases.into_iter().enumerate() {
+		let sections = format!("[dependencies]\n{}\n", dependency);
+		let package = common::write_package(&dir, &format!("app{}", index), "0.1.0", &sections, &[]);
+		let err = ShioConfig::load_from_dir(&package).err();
+		assert_eq!(err.as_deref(), Some(expected), "{}", dependency);
+	}
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+
+use lemonc::message::{ErrorCode, Message, Severity};
+
+const SNIPPET: &str = "tests/snippets/warnings/main.ln";
+
+/// the warnings of the snippet, each with the source text it points at.
+fn warnings() -> Vec<(Message, String)> {
+	let source = fs::read_to_string(common::crate_path(SNIPPET)).unwrap();
+	let (mut loader, _, _) = common::check(SNIPPET);
+	let mut messages = loader.take_messages();
+	assert!(!messages.any_errors(), "warnings don't fail the check");
+	let into_warning = |message: &Message| {
+		let range = message.range.expect("a warning points at its code");
+		(message.clone(), source[range.start..range.end].to_string())
+	};
+	messages.sorted().map(into_warning).collect()
+}
+
+/// the source text of each warning with `code`, in source order.
+fn pointed_at(code: u16) -> Vec<String> {
+	let found = warnings().into_iter().filter(|(message, _)| message.code == Some(ErrorCode(code)));
+	found
+		.map(|(message, text)| {
+			assert_eq!(message.severity, Severity::Warning, "{}", message.text);
+			text
+		})
+		.collect()
+}
+
+#[test]
+fn warns_on_an_unused_value() {
+	assert_eq!(pointed_at(701), ["unused"], "`_ignored` and `_after` are left alone");
+}
+
+#[test]
+fn warns_on_an_unused_borrow() {
+	assert_eq!(pointed_at(702), ["borrow"], "`_borrow` is left alone");
+}
+
+#[test]
+fn warns_on_unreachable_code() {
+	assert_eq!(pointed_at(703), ["let _after = 2"]);
+}
+
+#[test]
+fn warns_on_a_shadowed_binding_when_asked() {
+	let warnings = warnings();
+	let (shadowed, text) = warnings
+		.iter()
+		.find(|(message, _)| message.code == Some(ErrorCode(704)))
+		.expect("`@warn(shadowed_binding)` reports the shadowing");
+	assert_eq!(text, "count");
+	assert_eq!(shadowed.text, "'count' shadows an earlier binding");
+	let previous = &shadowed.labels[0];
+	assert!(
+		previous.range.start < shadowed.range.unwrap().start,
+		"the label is on the first 'count'"
+	);
+}
+
+#[test]
+fn warnings_still_compile() {
+	let (_, ir, _) = common::build(SNIPPET);
+	for function in ["unused", "borrowed", "unreachable", "shadowed", "main"] {
+		assert!(ir.has_function(function), "'{}' was not built", function);
+	}
+}
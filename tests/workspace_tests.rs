@@ -0,0 +1,55 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use common::TempDir;
+use lemonc::{loader::Loader, parser::parse_mod, shio};
+
+/// a workspace of `app` and `math`, `app` imports `math` by its package name.
+fn write_workspace(dir: &Path, members: &str) {
+	fs::write(dir.join("shio.toml"), format!("[workspace]\nmembers = {}\n", members)).unwrap();
+	let math = [("main.ln", "pub fn one(): i32 = 1;\n")];
+	common::write_package(dir, "math", "1.0.0", "", &math);
+	let main = [("main.ln", "const math = import(\"math\");\n\nfn main() = {}\n")];
+	common::write_package(dir, "app", "0.1.0", "", &main);
+}
+
+#[test]
+fn members_import_each_other_by_name() {
+	let dir = TempDir::new("workspace-members");
+	write_workspace(&dir, "[\"app\", \"math\"]");
+	let packages =
+		shio::load_packages(Some(dir.join("shio.toml"))).expect("failed to load workspace");
+	let names: Vec<_> = packages.iter().map(|(shio, _)| shio.package.name.as_str()).collect();
+	assert_eq!(names, ["app", "math"]);
+
+	for (shio, file_system) in packages {
+		let name = shio.package.name.clone();
+		let mut loader = Loader::new(shio, file_system);
+		let mod_id = loader.load_entry().expect("failed to load entry");
+		parse_mod(mod_id, &mut loader);
+		assert!(!loader.any_errors(), "{} failed to load", name);
+		let imports_math = loader.mod_ids().iter().any(|id| loader.is_dependency_mod(*id));
+		assert_eq!(imports_math, name == "app", "{} imports", name);
+	}
+}
+
+#[test]
+fn package_outside_the_cwd_loads_its_main() {
+	let dir = TempDir::new("workspace-single");
+	write_workspace(&dir, "[]");
+	let packages = shio::load_packages(Some(dir.join("app/shio.toml"))).expect("failed to load app");
+	let (shio, file_system) = packages.into_iter().next().unwrap();
+	assert_eq!(shio.loader.main, dir.join("app/main.ln"));
+	let mut loader = Loader::new(shio, file_system);
+	loader.load_entry().expect("main.ln is next to the shio.toml");
+}
+
+#[test]
+fn missing_member_is_reported() {
+	let dir = TempDir::new("workspace-missing");
+	write_workspace(&dir, "[\"app\", \"io\"]");
+	let err = shio::load_packages(Some(dir.join("shio.toml"))).err().expect("io has no package");
+	assert!(err.starts_with("workspace member '"), "{}", err);
+	assert!(err.ends_with("io', failed to read shio.toml"), "{}", err);
+}